@@ -0,0 +1,24 @@
+-- Revert the baseline: drops every treasury table, view and function.
+-- All processed data is lost; it can be rebuilt from the indexer by a resync.
+
+DROP VIEW IF EXISTS v_vendor_summary;
+DROP VIEW IF EXISTS v_financial_summary;
+DROP VIEW IF EXISTS v_events_with_context;
+DROP VIEW IF EXISTS v_treasury_summary;
+DROP VIEW IF EXISTS v_recent_events;
+DROP VIEW IF EXISTS v_milestone_timeline;
+DROP VIEW IF EXISTS v_vendor_contracts_summary;
+
+DROP TABLE IF EXISTS sync_status;
+DROP TABLE IF EXISTS utxos;
+DROP TABLE IF EXISTS events;
+DROP TABLE IF EXISTS milestone_evidence;
+DROP TABLE IF EXISTS evidence_archive;
+DROP TABLE IF EXISTS vendor_contract_revisions;
+DROP TABLE IF EXISTS milestones;
+DROP TABLE IF EXISTS vendor_contracts;
+DROP TABLE IF EXISTS vendor_aliases;
+DROP TABLE IF EXISTS vendors;
+DROP TABLE IF EXISTS treasury_contracts;
+
+DROP FUNCTION IF EXISTS update_updated_at();
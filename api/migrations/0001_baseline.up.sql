@@ -0,0 +1,565 @@
+-- Treasury Fund Tracking - Normalized Schema
+-- Processes TOM (Treasury Oversight Metadata) events from YACI Store
+--
+-- Baseline migration. Names are unqualified: the migration runner sets
+-- search_path to the target schema (normally `treasury`). Written to be
+-- idempotent so databases created before versioned migrations adopt it.
+
+-- ============================================================================
+-- TABLES
+-- ============================================================================
+
+-- Treasury Contracts (TRSC) - Root treasury reserve contracts
+CREATE TABLE IF NOT EXISTS treasury_contracts (
+    id SERIAL PRIMARY KEY,
+    contract_instance TEXT UNIQUE NOT NULL,     -- Policy ID (on-chain instance identifier)
+    contract_address TEXT,                       -- Script address (addr1x...)
+    stake_credential TEXT,                       -- Shared stake credential
+    name TEXT,                                   -- Human-readable name/label
+    publish_tx_hash VARCHAR(64),                 -- First publish event
+    publish_time BIGINT,                         -- Block time of publish
+    initialized_tx_hash VARCHAR(64),             -- First initialize event
+    initialized_at BIGINT,                       -- Block time of init
+    permissions JSONB,                           -- Permission rules from publish metadata
+    status TEXT DEFAULT 'active',                -- active/paused/swept
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Vendors - Projects grouped by payout stake credential (or address)
+CREATE TABLE IF NOT EXISTS vendors (
+    id SERIAL PRIMARY KEY,
+    vendor_key TEXT UNIQUE NOT NULL,             -- stake:<credential>, addr:<address> or alias key
+    name TEXT,                                   -- Latest vendor.name (or alias override)
+    payout_address TEXT,                         -- Latest payout address
+    stake_credential TEXT,                       -- Stake credential of the payout address (hex)
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Vendor Aliases - Manual overrides of vendor grouping
+CREATE TABLE IF NOT EXISTS vendor_aliases (
+    id SERIAL PRIMARY KEY,
+    alias_type TEXT NOT NULL CHECK (alias_type IN ('address', 'stake_credential', 'name')),
+    alias_value TEXT NOT NULL,                   -- Address, stake credential or vendor name
+    vendor_key TEXT NOT NULL,                    -- Vendor key the match is grouped under
+    vendor_name TEXT,                            -- Optional display name override
+    note TEXT,                                   -- Why the alias exists
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(alias_type, alias_value)
+);
+
+-- Vendor Contracts (PSSC) - Project-specific contracts linked to treasury
+CREATE TABLE IF NOT EXISTS vendor_contracts (
+    id SERIAL PRIMARY KEY,
+    treasury_id INT REFERENCES treasury_contracts(id),
+    project_id TEXT UNIQUE NOT NULL,             -- Logical identifier (e.g., "EC-0008-25")
+    other_identifiers TEXT[],                    -- Related IDs from otherIdentifiers array
+    project_name TEXT,                           -- Label from fund event
+    description TEXT,                            -- Project description (joined if array)
+    vendor_name TEXT,                            -- vendor.name from metadata
+    vendor_address TEXT,                         -- Payment destination (vendor.label in metadata)
+    contract_url TEXT,                           -- contract - link to agreement document
+    contract_address TEXT,                       -- PSSC script address (from fund tx output)
+    fund_tx_hash VARCHAR(64) NOT NULL,           -- Fund transaction
+    fund_slot BIGINT,                            -- Blockchain slot
+    fund_block_time BIGINT,                      -- Block timestamp
+    initial_amount_lovelace BIGINT,              -- Initial funding amount (from tx output)
+    status TEXT DEFAULT 'active',                -- active/paused/completed/cancelled/swept
+    vendor_id INT REFERENCES vendors(id) ON DELETE SET NULL, -- Resolved vendor
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Milestones - Each vendor contract has ordered milestones
+CREATE TABLE IF NOT EXISTS milestones (
+    id SERIAL PRIMARY KEY,
+    vendor_contract_id INT NOT NULL REFERENCES vendor_contracts(id) ON DELETE CASCADE,
+    milestone_id TEXT NOT NULL,                  -- Logical identifier (e.g., "m-0")
+    milestone_order INT NOT NULL,                -- Position (1, 2, 3...)
+    label TEXT,                                  -- Milestone name
+    description TEXT,                            -- Detailed description
+    acceptance_criteria TEXT,                    -- Completion criteria
+    amount_lovelace BIGINT,                      -- Allocated amount (if specified)
+    maturity BIGINT,                             -- Scheduled completion time (if specified)
+    status TEXT DEFAULT 'pending',               -- pending/completed/disbursed/withdrawn/removed
+    complete_tx_hash VARCHAR(64),                -- Completion transaction
+    complete_time BIGINT,                        -- Completion timestamp
+    complete_description TEXT,                   -- Description from complete event
+    evidence JSONB,                              -- Evidence array from complete event
+    disburse_tx_hash VARCHAR(64),                -- Disbursement transaction
+    disburse_time BIGINT,                        -- Disbursement timestamp
+    disburse_amount BIGINT,                      -- Actual disbursed amount
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(vendor_contract_id, milestone_id)
+);
+
+-- Vendor Contract Revisions - Contract terms recorded at each fund/modify event
+CREATE TABLE IF NOT EXISTS vendor_contract_revisions (
+    id SERIAL PRIMARY KEY,
+    vendor_contract_id INT NOT NULL REFERENCES vendor_contracts(id) ON DELETE CASCADE,
+    revision INT NOT NULL,                       -- 1 = fund, then one per modify
+    tx_hash VARCHAR(64) NOT NULL,                -- Fund/modify transaction
+    slot BIGINT,                                 -- Blockchain slot
+    block_time BIGINT,                           -- Block timestamp
+    event_type TEXT NOT NULL,                    -- fund/modify
+    reason TEXT,                                 -- Reason from modify metadata
+    snapshot JSONB NOT NULL,                     -- Project fields + milestone schedule
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(vendor_contract_id, revision),
+    UNIQUE(vendor_contract_id, tx_hash)
+);
+
+-- Evidence archive - Content-addressed copies of fetched evidence documents
+CREATE TABLE IF NOT EXISTS evidence_archive (
+    content_hash VARCHAR(64) PRIMARY KEY,        -- SHA-256 of the content (hex)
+    size_bytes BIGINT NOT NULL,                  -- Content size
+    media_type TEXT,                             -- Media type
+    archived_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Milestone evidence - Typed records normalised from milestones.evidence
+CREATE TABLE IF NOT EXISTS milestone_evidence (
+    id SERIAL PRIMARY KEY,
+    milestone_id INT NOT NULL REFERENCES milestones(id) ON DELETE CASCADE,
+    position INT NOT NULL,                       -- Position in the evidence list
+    label TEXT,                                  -- Evidence label
+    url TEXT,                                    -- URL as published (http(s):// or ipfs://)
+    ipfs_cid TEXT,                               -- IPFS CID
+    declared_hash TEXT,                          -- Declared content hash (hex)
+    hash_algorithm TEXT,                         -- sha256/blake2b-256
+    raw JSONB,                                   -- Original evidence entry
+    content_hash VARCHAR(64) REFERENCES evidence_archive(content_hash),
+    status TEXT DEFAULT 'pending' CHECK (status IN ('pending', 'archived', 'verified', 'mismatch', 'unavailable', 'unresolvable')),
+    fetch_attempts INT DEFAULT 0,                -- Fetch attempts
+    last_checked_at TIMESTAMPTZ,                 -- Last fetch attempt
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE(milestone_id, position)
+);
+
+-- Events - Audit log of all TOM events
+CREATE TABLE IF NOT EXISTS events (
+    id SERIAL PRIMARY KEY,
+    tx_hash VARCHAR(64) UNIQUE NOT NULL,         -- Transaction hash
+    slot BIGINT,                                 -- Blockchain slot
+    block_number BIGINT,                         -- Block number
+    block_time BIGINT,                           -- Block timestamp
+    event_type TEXT NOT NULL,                    -- publish/initialize/fund/complete/disburse/etc.
+    treasury_id INT REFERENCES treasury_contracts(id),
+    vendor_contract_id INT REFERENCES vendor_contracts(id),
+    milestone_id INT REFERENCES milestones(id),
+    amount_lovelace BIGINT,                      -- Amount involved
+    reason TEXT,                                 -- Justification (pause/cancel/modify)
+    destination TEXT,                            -- Destination label (disburse)
+    metadata JSONB,                              -- Original TOM metadata body
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- UTXOs - Track UTXOs at treasury-related addresses
+CREATE TABLE IF NOT EXISTS utxos (
+    id SERIAL PRIMARY KEY,
+    tx_hash VARCHAR(64) NOT NULL,                -- Transaction hash
+    output_index SMALLINT NOT NULL,              -- Output index
+    address TEXT,                                -- Owner address (optional for tracking)
+    address_type TEXT,                           -- treasury/vendor_contract/vendor
+    vendor_contract_id INT REFERENCES vendor_contracts(id),
+    lovelace_amount BIGINT,                      -- Amount (optional for tracking)
+    slot BIGINT,                                 -- Creation slot (optional for tracking)
+    block_number BIGINT,                         -- Block number
+    spent BOOLEAN DEFAULT FALSE,                 -- Is spent?
+    spent_tx_hash VARCHAR(64),                   -- Spending transaction
+    spent_slot BIGINT,                           -- When spent
+    UNIQUE(tx_hash, output_index)
+);
+
+-- Sync Status - Track synchronization progress
+CREATE TABLE IF NOT EXISTS sync_status (
+    id SERIAL PRIMARY KEY,
+    sync_type TEXT UNIQUE NOT NULL,              -- events/utxos
+    last_slot BIGINT DEFAULT 0,                  -- Last processed slot
+    last_block BIGINT,                           -- Last processed block
+    last_tx_hash VARCHAR(64),                    -- Last processed tx
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Insert initial sync status records
+INSERT INTO sync_status (sync_type, last_slot) VALUES ('events', 0), ('utxos', 0)
+ON CONFLICT (sync_type) DO NOTHING;
+
+-- Columns added before versioned migrations (no-ops on fresh databases)
+ALTER TABLE vendor_contracts ADD COLUMN IF NOT EXISTS vendor_id INT REFERENCES vendors(id) ON DELETE SET NULL;
+ALTER TABLE milestones ADD COLUMN IF NOT EXISTS maturity BIGINT;
+
+-- ============================================================================
+-- INDEXES
+-- ============================================================================
+
+-- Treasury contracts
+CREATE INDEX IF NOT EXISTS idx_treasury_instance ON treasury_contracts(contract_instance);
+CREATE INDEX IF NOT EXISTS idx_treasury_address ON treasury_contracts(contract_address);
+CREATE INDEX IF NOT EXISTS idx_treasury_status ON treasury_contracts(status);
+
+-- Vendor contracts (projects)
+CREATE INDEX IF NOT EXISTS idx_vendor_treasury ON vendor_contracts(treasury_id);
+CREATE INDEX IF NOT EXISTS idx_vendor_project_id ON vendor_contracts(project_id);
+CREATE INDEX IF NOT EXISTS idx_vendor_status ON vendor_contracts(status);
+CREATE INDEX IF NOT EXISTS idx_vendor_fund_time ON vendor_contracts(fund_block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_vendor_contract_address ON vendor_contracts(contract_address);
+CREATE INDEX IF NOT EXISTS idx_vendor_contract_vendor ON vendor_contracts(vendor_id);
+
+-- Milestones
+CREATE INDEX IF NOT EXISTS idx_milestone_vendor ON milestones(vendor_contract_id);
+CREATE INDEX IF NOT EXISTS idx_milestone_status ON milestones(status);
+CREATE INDEX IF NOT EXISTS idx_milestone_order ON milestones(vendor_contract_id, milestone_order);
+
+-- Events
+CREATE INDEX IF NOT EXISTS idx_event_type ON events(event_type);
+CREATE INDEX IF NOT EXISTS idx_event_vendor ON events(vendor_contract_id);
+CREATE INDEX IF NOT EXISTS idx_event_treasury ON events(treasury_id);
+CREATE INDEX IF NOT EXISTS idx_event_slot ON events(slot DESC);
+CREATE INDEX IF NOT EXISTS idx_event_block_time ON events(block_time DESC);
+
+-- UTXOs
+CREATE INDEX IF NOT EXISTS idx_utxo_address ON utxos(address);
+CREATE INDEX IF NOT EXISTS idx_utxo_vendor ON utxos(vendor_contract_id);
+CREATE INDEX IF NOT EXISTS idx_utxo_unspent ON utxos(address) WHERE NOT spent;
+CREATE INDEX IF NOT EXISTS idx_utxo_slot ON utxos(slot DESC);
+CREATE INDEX IF NOT EXISTS idx_utxo_vendor_unspent ON utxos(vendor_contract_id) WHERE NOT spent;
+
+-- Full-text search documents (must match api/src/db/queries.rs; used by /api/v1/search).
+-- They replace the older single-table search indexes.
+DROP INDEX IF EXISTS idx_vendor_search;
+DROP INDEX IF EXISTS idx_vendor_fulltext;
+CREATE INDEX IF NOT EXISTS idx_vendor_search_document ON vendor_contracts
+    USING gin ((
+        setweight(to_tsvector('english', COALESCE(project_id, '') || ' ' || COALESCE(project_name, '')), 'A') ||
+        setweight(to_tsvector('english', COALESCE(description, '')), 'B') ||
+        setweight(to_tsvector('english', COALESCE(vendor_name, '')), 'C')
+    ));
+CREATE INDEX IF NOT EXISTS idx_milestone_search_document ON milestones
+    USING gin ((
+        setweight(to_tsvector('english', COALESCE(label, '')), 'A') ||
+        setweight(to_tsvector('english', COALESCE(description, '') || ' ' || COALESCE(acceptance_criteria, '') || ' ' || COALESCE(complete_description, '')), 'B') ||
+        setweight(jsonb_to_tsvector('english', COALESCE(evidence, '[]'::jsonb), '["string"]'), 'C')
+    ));
+CREATE INDEX IF NOT EXISTS idx_event_search_document ON events
+    USING gin ((
+        setweight(to_tsvector('english', COALESCE(reason, '')), 'A') ||
+        setweight(jsonb_to_tsvector('english', COALESCE(metadata, '{}'::jsonb), '["string"]'), 'C')
+    ));
+
+-- Events by milestone (for milestone event history)
+CREATE INDEX IF NOT EXISTS idx_event_milestone ON events(milestone_id);
+
+-- Events by type and time (for activity feed filtering)
+CREATE INDEX IF NOT EXISTS idx_event_type_time ON events(event_type, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_evidence_status ON milestone_evidence(status);
+CREATE INDEX IF NOT EXISTS idx_evidence_content_hash ON milestone_evidence(content_hash);
+
+-- ============================================================================
+-- TRIGGER FOR updated_at
+-- ============================================================================
+
+CREATE OR REPLACE FUNCTION update_updated_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_treasury_contracts_updated_at ON treasury_contracts;
+CREATE TRIGGER trg_treasury_contracts_updated_at
+    BEFORE UPDATE ON treasury_contracts
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at();
+
+DROP TRIGGER IF EXISTS trg_vendor_contracts_updated_at ON vendor_contracts;
+CREATE TRIGGER trg_vendor_contracts_updated_at
+    BEFORE UPDATE ON vendor_contracts
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at();
+
+DROP TRIGGER IF EXISTS trg_milestones_updated_at ON milestones;
+CREATE TRIGGER trg_milestones_updated_at
+    BEFORE UPDATE ON milestones
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at();
+
+-- ============================================================================
+-- VIEWS
+-- ============================================================================
+
+-- Views are recreated rather than replaced so column changes apply
+DROP VIEW IF EXISTS v_vendor_summary;
+DROP VIEW IF EXISTS v_financial_summary;
+DROP VIEW IF EXISTS v_events_with_context;
+DROP VIEW IF EXISTS v_treasury_summary;
+DROP VIEW IF EXISTS v_recent_events;
+DROP VIEW IF EXISTS v_milestone_timeline;
+DROP VIEW IF EXISTS v_vendor_contracts_summary;
+
+-- Vendor contracts with milestone stats, financials, and balance
+CREATE VIEW v_vendor_contracts_summary AS
+SELECT
+    vc.id,
+    vc.treasury_id,
+    vc.project_id,
+    vc.other_identifiers,
+    vc.project_name,
+    vc.description,
+    vc.vendor_name,
+    vc.vendor_address,
+    vc.contract_url,
+    vc.contract_address,
+    vc.fund_tx_hash,
+    vc.fund_slot,
+    vc.fund_block_time,
+    vc.initial_amount_lovelace,
+    vc.status,
+    vc.created_at,
+    vc.updated_at,
+    -- Treasury context
+    tc.contract_instance as treasury_instance,
+    tc.name as treasury_name,
+    -- Milestone counts
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status <> 'removed') as total_milestones,
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status = 'pending') as pending_milestones,
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status = 'completed') as completed_milestones,
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status = 'disbursed') as disbursed_milestones,
+    -- Financial totals from milestones
+    COALESCE(SUM(DISTINCT m.disburse_amount), 0)::BIGINT as total_disbursed_lovelace,
+    -- Current balance from UTXOs
+    COALESCE(SUM(u.lovelace_amount) FILTER (WHERE NOT u.spent), 0)::BIGINT as current_balance_lovelace,
+    COUNT(u.id) FILTER (WHERE NOT u.spent) as utxo_count,
+    -- Last event time
+    (SELECT MAX(e.block_time) FROM events e WHERE e.vendor_contract_id = vc.id) as last_event_time,
+    -- Event count
+    (SELECT COUNT(*) FROM events e WHERE e.vendor_contract_id = vc.id) as event_count,
+    -- Resolved vendor
+    vc.vendor_id
+FROM vendor_contracts vc
+LEFT JOIN treasury_contracts tc ON tc.id = vc.treasury_id
+LEFT JOIN milestones m ON m.vendor_contract_id = vc.id
+LEFT JOIN utxos u ON u.vendor_contract_id = vc.id
+GROUP BY vc.id, tc.contract_instance, tc.name;
+
+-- Milestone timeline with vendor context
+CREATE VIEW v_milestone_timeline AS
+SELECT
+    m.id,
+    m.milestone_id,
+    m.milestone_order,
+    m.label,
+    m.description,
+    m.acceptance_criteria,
+    m.amount_lovelace,
+    m.status,
+    m.complete_tx_hash,
+    m.complete_time,
+    m.complete_description,
+    m.evidence,
+    m.disburse_tx_hash,
+    m.disburse_time,
+    m.disburse_amount,
+    vc.project_id,
+    vc.project_name,
+    vc.vendor_address
+FROM milestones m
+JOIN vendor_contracts vc ON vc.id = m.vendor_contract_id
+ORDER BY vc.project_id, m.milestone_order;
+
+-- Recent events with full context
+CREATE VIEW v_recent_events AS
+SELECT
+    e.id,
+    e.tx_hash,
+    e.slot,
+    e.block_number,
+    e.block_time,
+    e.event_type,
+    e.amount_lovelace,
+    e.reason,
+    e.destination,
+    e.metadata,
+    e.created_at,
+    tc.contract_instance as treasury_instance,
+    vc.project_id,
+    vc.project_name,
+    m.label as milestone_label,
+    m.milestone_order
+FROM events e
+LEFT JOIN treasury_contracts tc ON tc.id = e.treasury_id
+LEFT JOIN vendor_contracts vc ON vc.id = e.vendor_contract_id
+LEFT JOIN milestones m ON m.id = e.milestone_id
+ORDER BY e.slot DESC;
+
+-- Treasury summary stats
+CREATE VIEW v_treasury_summary AS
+SELECT
+    tc.id as treasury_id,
+    tc.contract_instance,
+    tc.contract_address,
+    tc.stake_credential,
+    tc.name,
+    tc.status,
+    tc.publish_tx_hash,
+    tc.publish_time,
+    tc.initialized_tx_hash,
+    tc.initialized_at,
+    tc.permissions,
+    COUNT(DISTINCT vc.id) as vendor_contract_count,
+    COUNT(DISTINCT vc.id) FILTER (WHERE vc.status = 'active') as active_contracts,
+    COUNT(DISTINCT vc.id) FILTER (WHERE vc.status = 'completed') as completed_contracts,
+    COUNT(DISTINCT vc.id) FILTER (WHERE vc.status = 'cancelled') as cancelled_contracts,
+    COALESCE(SUM(u.lovelace_amount) FILTER (WHERE NOT u.spent AND u.address = tc.contract_address), 0)::BIGINT as treasury_balance,
+    COUNT(u.id) FILTER (WHERE NOT u.spent AND u.address = tc.contract_address) as utxo_count,
+    (SELECT COUNT(*) FROM events WHERE treasury_id = tc.id) as total_events,
+    (SELECT MAX(block_time) FROM events WHERE treasury_id = tc.id) as last_event_time,
+    tc.created_at,
+    tc.updated_at
+FROM treasury_contracts tc
+LEFT JOIN vendor_contracts vc ON vc.treasury_id = tc.id
+LEFT JOIN utxos u ON u.address = tc.contract_address
+GROUP BY tc.id;
+
+-- Events with full context (treasury, project, milestone info)
+CREATE VIEW v_events_with_context AS
+SELECT
+    e.id,
+    e.tx_hash,
+    e.slot,
+    e.block_number,
+    e.block_time,
+    e.event_type,
+    e.amount_lovelace,
+    e.reason,
+    e.destination,
+    e.metadata,
+    e.created_at,
+    -- Treasury context
+    tc.contract_instance as treasury_instance,
+    tc.name as treasury_name,
+    -- Project context
+    vc.project_id,
+    vc.project_name,
+    vc.vendor_name,
+    vc.contract_address as project_address,
+    -- Milestone context
+    m.milestone_id,
+    m.label as milestone_label,
+    m.milestone_order
+FROM events e
+LEFT JOIN treasury_contracts tc ON tc.id = e.treasury_id
+LEFT JOIN vendor_contracts vc ON vc.id = e.vendor_contract_id
+LEFT JOIN milestones m ON m.id = e.milestone_id;
+
+-- Financial summary view (allocated vs disbursed vs remaining)
+CREATE VIEW v_financial_summary AS
+SELECT
+    tc.id as treasury_id,
+    tc.contract_instance,
+    tc.name as treasury_name,
+    -- Allocation totals
+    COALESCE(SUM(vc.initial_amount_lovelace), 0)::BIGINT as total_allocated_lovelace,
+    -- Disbursement totals
+    COALESCE(SUM(m_totals.total_disbursed), 0)::BIGINT as total_disbursed_lovelace,
+    -- Remaining (allocated - disbursed - withdrawn - swept back)
+    (COALESCE(SUM(vc.initial_amount_lovelace), 0) - COALESCE(SUM(m_totals.total_disbursed), 0)
+        - COALESCE(SUM(e_totals.total_withdrawn), 0) - COALESCE(SUM(e_totals.total_swept), 0))::BIGINT as total_remaining_lovelace,
+    -- Treasury balance (actual UTXOs)
+    COALESCE((
+        SELECT SUM(u.lovelace_amount)
+        FROM utxos u
+        WHERE u.address = tc.contract_address AND NOT u.spent
+    ), 0)::BIGINT as treasury_balance_lovelace,
+    -- Project-level balance (sum of project UTXOs)
+    COALESCE((
+        SELECT SUM(u2.lovelace_amount)
+        FROM utxos u2
+        JOIN vendor_contracts vc2 ON vc2.id = u2.vendor_contract_id
+        WHERE vc2.treasury_id = tc.id AND NOT u2.spent
+    ), 0)::BIGINT as project_balance_lovelace,
+    -- Counts
+    COUNT(DISTINCT vc.id) as project_count,
+    COUNT(DISTINCT CASE WHEN vc.status = 'active' THEN vc.id END) as active_project_count,
+    -- Project fund flows (withdraw and sweep events)
+    COALESCE(SUM(e_totals.total_withdrawn), 0)::BIGINT as total_withdrawn_lovelace,
+    COALESCE(SUM(e_totals.total_swept), 0)::BIGINT as total_swept_lovelace
+FROM treasury_contracts tc
+LEFT JOIN vendor_contracts vc ON vc.treasury_id = tc.id
+LEFT JOIN (
+    SELECT
+        m.vendor_contract_id,
+        SUM(COALESCE(m.disburse_amount, 0)) as total_disbursed
+    FROM milestones m
+    GROUP BY m.vendor_contract_id
+) m_totals ON m_totals.vendor_contract_id = vc.id
+LEFT JOIN (
+    SELECT
+        e.vendor_contract_id,
+        SUM(COALESCE(e.amount_lovelace, 0)) FILTER (WHERE e.event_type = 'withdraw') as total_withdrawn,
+        SUM(COALESCE(e.amount_lovelace, 0)) FILTER (WHERE e.event_type = 'sweep') as total_swept
+    FROM events e
+    WHERE e.vendor_contract_id IS NOT NULL
+    GROUP BY e.vendor_contract_id
+) e_totals ON e_totals.vendor_contract_id = vc.id
+GROUP BY tc.id;
+
+-- Vendor summary (totals across all of a vendor's projects)
+CREATE VIEW v_vendor_summary AS
+SELECT
+    v.id,
+    v.vendor_key,
+    v.name,
+    v.payout_address,
+    v.stake_credential,
+    -- Projects
+    p.project_count,
+    p.active_project_count,
+    p.total_allocated_lovelace,
+    -- Disbursed and current balance
+    ms.total_disbursed_lovelace,
+    b.current_balance_lovelace,
+    b.utxo_count,
+    -- Milestone delivery (due = has maturity and is completed or overdue)
+    ms.total_milestones,
+    ms.completed_milestones,
+    ms.due_milestones,
+    ms.on_time_milestones,
+    p.first_fund_time,
+    p.last_fund_time,
+    v.created_at,
+    v.updated_at
+FROM vendors v
+CROSS JOIN LATERAL (
+    SELECT
+        COUNT(*) as project_count,
+        COUNT(*) FILTER (WHERE vc.status = 'active') as active_project_count,
+        COALESCE(SUM(vc.initial_amount_lovelace), 0)::BIGINT as total_allocated_lovelace,
+        MIN(vc.fund_block_time) as first_fund_time,
+        MAX(vc.fund_block_time) as last_fund_time
+    FROM vendor_contracts vc
+    WHERE vc.vendor_id = v.id
+) p
+CROSS JOIN LATERAL (
+    SELECT
+        COUNT(*) FILTER (WHERE m.status <> 'removed') as total_milestones,
+        COUNT(*) FILTER (WHERE m.complete_time IS NOT NULL) as completed_milestones,
+        COUNT(*) FILTER (
+            WHERE m.maturity IS NOT NULL AND m.status <> 'removed'
+              AND (m.complete_time IS NOT NULL OR m.maturity < EXTRACT(EPOCH FROM NOW())::BIGINT)
+        ) as due_milestones,
+        COUNT(*) FILTER (WHERE m.maturity IS NOT NULL AND m.complete_time <= m.maturity) as on_time_milestones,
+        COALESCE(SUM(m.disburse_amount), 0)::BIGINT as total_disbursed_lovelace
+    FROM milestones m
+    JOIN vendor_contracts vc ON vc.id = m.vendor_contract_id
+    WHERE vc.vendor_id = v.id
+) ms
+CROSS JOIN LATERAL (
+    SELECT
+        COALESCE(SUM(u.lovelace_amount), 0)::BIGINT as current_balance_lovelace,
+        COUNT(u.id) as utxo_count
+    FROM utxos u
+    JOIN vendor_contracts vc ON vc.id = u.vendor_contract_id
+    WHERE vc.vendor_id = v.id AND NOT u.spent
+) b;
@@ -0,0 +1,3 @@
+-- Dropping the function removes the triggers that use it
+DROP FUNCTION IF EXISTS bump_sync_generation() CASCADE;
+DROP TABLE IF EXISTS sync_generation;
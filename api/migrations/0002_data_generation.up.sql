@@ -0,0 +1,47 @@
+-- Data generation counter
+--
+-- Bumped by trigger whenever treasury data changes, at most once per
+-- transaction. API processes poll it to invalidate cached responses, so
+-- changes made by any writer (sync worker, treasury-cli) are picked up.
+
+CREATE TABLE sync_generation (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    generation BIGINT NOT NULL DEFAULT 0,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_xid BIGINT
+);
+
+INSERT INTO sync_generation DEFAULT VALUES;
+
+-- search_path is pinned to the schema the migration runs in, so triggers
+-- resolve sync_generation regardless of the writer's search_path
+CREATE FUNCTION bump_sync_generation()
+RETURNS TRIGGER
+SET search_path FROM CURRENT
+AS $$
+BEGIN
+    UPDATE sync_generation
+    SET generation = generation + 1, changed_at = NOW(), last_xid = txid_current()
+    WHERE last_xid IS DISTINCT FROM txid_current();
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY[
+        'treasury_contracts', 'vendors', 'vendor_aliases', 'vendor_contracts', 'milestones',
+        'vendor_contract_revisions', 'evidence_archive', 'milestone_evidence', 'events', 'utxos',
+        'sync_status'
+    ] LOOP
+        EXECUTE format(
+            'CREATE TRIGGER trg_%1$s_generation AFTER INSERT OR UPDATE OR DELETE ON %1$I
+             FOR EACH ROW EXECUTE FUNCTION bump_sync_generation()', tbl);
+        EXECUTE format(
+            'CREATE TRIGGER trg_%1$s_generation_truncate AFTER TRUNCATE ON %1$I
+             FOR EACH STATEMENT EXECUTE FUNCTION bump_sync_generation()', tbl);
+    END LOOP;
+END;
+$$;
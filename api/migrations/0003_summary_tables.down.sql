@@ -0,0 +1,78 @@
+-- Restore the aggregate views from the baseline, then drop the summary tables
+
+CREATE OR REPLACE VIEW v_vendor_contracts_summary AS
+SELECT
+    vc.id,
+    vc.treasury_id,
+    vc.project_id,
+    vc.other_identifiers,
+    vc.project_name,
+    vc.description,
+    vc.vendor_name,
+    vc.vendor_address,
+    vc.contract_url,
+    vc.contract_address,
+    vc.fund_tx_hash,
+    vc.fund_slot,
+    vc.fund_block_time,
+    vc.initial_amount_lovelace,
+    vc.status,
+    vc.created_at,
+    vc.updated_at,
+    -- Treasury context
+    tc.contract_instance as treasury_instance,
+    tc.name as treasury_name,
+    -- Milestone counts
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status <> 'removed') as total_milestones,
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status = 'pending') as pending_milestones,
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status = 'completed') as completed_milestones,
+    COUNT(DISTINCT m.id) FILTER (WHERE m.status = 'disbursed') as disbursed_milestones,
+    -- Financial totals from milestones
+    COALESCE(SUM(DISTINCT m.disburse_amount), 0)::BIGINT as total_disbursed_lovelace,
+    -- Current balance from UTXOs
+    COALESCE(SUM(u.lovelace_amount) FILTER (WHERE NOT u.spent), 0)::BIGINT as current_balance_lovelace,
+    COUNT(u.id) FILTER (WHERE NOT u.spent) as utxo_count,
+    -- Last event time
+    (SELECT MAX(e.block_time) FROM events e WHERE e.vendor_contract_id = vc.id) as last_event_time,
+    -- Event count
+    (SELECT COUNT(*) FROM events e WHERE e.vendor_contract_id = vc.id) as event_count,
+    -- Resolved vendor
+    vc.vendor_id
+FROM vendor_contracts vc
+LEFT JOIN treasury_contracts tc ON tc.id = vc.treasury_id
+LEFT JOIN milestones m ON m.vendor_contract_id = vc.id
+LEFT JOIN utxos u ON u.vendor_contract_id = vc.id
+GROUP BY vc.id, tc.contract_instance, tc.name;
+
+CREATE OR REPLACE VIEW v_treasury_summary AS
+SELECT
+    tc.id as treasury_id,
+    tc.contract_instance,
+    tc.contract_address,
+    tc.stake_credential,
+    tc.name,
+    tc.status,
+    tc.publish_tx_hash,
+    tc.publish_time,
+    tc.initialized_tx_hash,
+    tc.initialized_at,
+    tc.permissions,
+    COUNT(DISTINCT vc.id) as vendor_contract_count,
+    COUNT(DISTINCT vc.id) FILTER (WHERE vc.status = 'active') as active_contracts,
+    COUNT(DISTINCT vc.id) FILTER (WHERE vc.status = 'completed') as completed_contracts,
+    COUNT(DISTINCT vc.id) FILTER (WHERE vc.status = 'cancelled') as cancelled_contracts,
+    COALESCE(SUM(u.lovelace_amount) FILTER (WHERE NOT u.spent AND u.address = tc.contract_address), 0)::BIGINT as treasury_balance,
+    COUNT(u.id) FILTER (WHERE NOT u.spent AND u.address = tc.contract_address) as utxo_count,
+    (SELECT COUNT(*) FROM events WHERE treasury_id = tc.id) as total_events,
+    (SELECT MAX(block_time) FROM events WHERE treasury_id = tc.id) as last_event_time,
+    tc.created_at,
+    tc.updated_at
+FROM treasury_contracts tc
+LEFT JOIN vendor_contracts vc ON vc.treasury_id = tc.id
+LEFT JOIN utxos u ON u.address = tc.contract_address
+GROUP BY tc.id;
+
+DROP TABLE IF EXISTS treasury_summary;
+DROP TABLE IF EXISTS project_summary;
+DROP VIEW IF EXISTS v_treasury_summary_recomputed;
+DROP VIEW IF EXISTS v_project_summary_recomputed;
@@ -0,0 +1,213 @@
+-- Per-project and per-treasury summary tables
+--
+-- Replace the aggregate joins in v_vendor_contracts_summary and
+-- v_treasury_summary. Those joined milestones x UTXOs (and vendor contracts x
+-- UTXOs), multiplying balances and counts, and summed DISTINCT disbursement
+-- amounts, dropping milestones with equal amounts.
+--
+-- The v_*_summary_recomputed views are the single definition of every
+-- summary column. The event processor refreshes the rows an event touched
+-- from them; `treasury-cli summaries rebuild` refreshes all rows and
+-- `treasury-cli summaries check` compares the tables against them.
+
+-- ============================================================================
+-- RECOMPUTATION
+-- ============================================================================
+
+CREATE VIEW v_project_summary_recomputed AS
+SELECT
+    vc.id as vendor_contract_id,
+    m.total_milestones,
+    m.pending_milestones,
+    m.completed_milestones,
+    m.disbursed_milestones,
+    m.total_disbursed_lovelace,
+    u.current_balance_lovelace,
+    u.utxo_count,
+    e.event_count,
+    e.last_event_time
+FROM vendor_contracts vc
+CROSS JOIN LATERAL (
+    SELECT
+        COUNT(*) FILTER (WHERE status <> 'removed') as total_milestones,
+        COUNT(*) FILTER (WHERE status = 'pending') as pending_milestones,
+        COUNT(*) FILTER (WHERE status = 'completed') as completed_milestones,
+        COUNT(*) FILTER (WHERE status = 'disbursed') as disbursed_milestones,
+        COALESCE(SUM(disburse_amount), 0)::BIGINT as total_disbursed_lovelace
+    FROM milestones
+    WHERE vendor_contract_id = vc.id
+) m
+CROSS JOIN LATERAL (
+    SELECT
+        COALESCE(SUM(lovelace_amount), 0)::BIGINT as current_balance_lovelace,
+        COUNT(*) as utxo_count
+    FROM utxos
+    WHERE vendor_contract_id = vc.id AND NOT spent
+) u
+CROSS JOIN LATERAL (
+    SELECT COUNT(*) as event_count, MAX(block_time) as last_event_time
+    FROM events
+    WHERE vendor_contract_id = vc.id
+) e;
+
+CREATE VIEW v_treasury_summary_recomputed AS
+SELECT
+    tc.id as treasury_id,
+    p.vendor_contract_count,
+    p.active_contracts,
+    p.completed_contracts,
+    p.cancelled_contracts,
+    u.treasury_balance,
+    u.utxo_count,
+    e.total_events,
+    e.last_event_time
+FROM treasury_contracts tc
+CROSS JOIN LATERAL (
+    SELECT
+        COUNT(*) as vendor_contract_count,
+        COUNT(*) FILTER (WHERE status = 'active') as active_contracts,
+        COUNT(*) FILTER (WHERE status = 'completed') as completed_contracts,
+        COUNT(*) FILTER (WHERE status = 'cancelled') as cancelled_contracts
+    FROM vendor_contracts
+    WHERE treasury_id = tc.id
+) p
+CROSS JOIN LATERAL (
+    SELECT
+        COALESCE(SUM(lovelace_amount), 0)::BIGINT as treasury_balance,
+        COUNT(*) as utxo_count
+    FROM utxos
+    WHERE address = tc.contract_address AND NOT spent
+) u
+CROSS JOIN LATERAL (
+    SELECT COUNT(*) as total_events, MAX(block_time) as last_event_time
+    FROM events
+    WHERE treasury_id = tc.id
+) e;
+
+-- ============================================================================
+-- TABLES
+-- ============================================================================
+
+CREATE TABLE project_summary (
+    vendor_contract_id INT PRIMARY KEY REFERENCES vendor_contracts(id) ON DELETE CASCADE,
+    total_milestones BIGINT NOT NULL,
+    pending_milestones BIGINT NOT NULL,
+    completed_milestones BIGINT NOT NULL,
+    disbursed_milestones BIGINT NOT NULL,
+    total_disbursed_lovelace BIGINT NOT NULL,
+    current_balance_lovelace BIGINT NOT NULL,
+    utxo_count BIGINT NOT NULL,
+    event_count BIGINT NOT NULL,
+    last_event_time BIGINT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE treasury_summary (
+    treasury_id INT PRIMARY KEY REFERENCES treasury_contracts(id) ON DELETE CASCADE,
+    vendor_contract_count BIGINT NOT NULL,
+    active_contracts BIGINT NOT NULL,
+    completed_contracts BIGINT NOT NULL,
+    cancelled_contracts BIGINT NOT NULL,
+    treasury_balance BIGINT NOT NULL,
+    utxo_count BIGINT NOT NULL,
+    total_events BIGINT NOT NULL,
+    last_event_time BIGINT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO project_summary (
+    vendor_contract_id, total_milestones, pending_milestones, completed_milestones,
+    disbursed_milestones, total_disbursed_lovelace, current_balance_lovelace,
+    utxo_count, event_count, last_event_time
+)
+SELECT * FROM v_project_summary_recomputed;
+
+INSERT INTO treasury_summary (
+    treasury_id, vendor_contract_count, active_contracts, completed_contracts,
+    cancelled_contracts, treasury_balance, utxo_count, total_events, last_event_time
+)
+SELECT * FROM v_treasury_summary_recomputed;
+
+-- Summary refreshes run after the event's own writes, so they bump the data
+-- generation again and cached responses never keep pre-refresh summaries
+CREATE TRIGGER trg_project_summary_generation AFTER INSERT OR UPDATE OR DELETE ON project_summary
+    FOR EACH ROW EXECUTE FUNCTION bump_sync_generation();
+CREATE TRIGGER trg_project_summary_generation_truncate AFTER TRUNCATE ON project_summary
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_sync_generation();
+CREATE TRIGGER trg_treasury_summary_generation AFTER INSERT OR UPDATE OR DELETE ON treasury_summary
+    FOR EACH ROW EXECUTE FUNCTION bump_sync_generation();
+CREATE TRIGGER trg_treasury_summary_generation_truncate AFTER TRUNCATE ON treasury_summary
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_sync_generation();
+
+-- ============================================================================
+-- VIEWS
+-- ============================================================================
+
+-- Same columns as before, read from the summary tables
+CREATE OR REPLACE VIEW v_vendor_contracts_summary AS
+SELECT
+    vc.id,
+    vc.treasury_id,
+    vc.project_id,
+    vc.other_identifiers,
+    vc.project_name,
+    vc.description,
+    vc.vendor_name,
+    vc.vendor_address,
+    vc.contract_url,
+    vc.contract_address,
+    vc.fund_tx_hash,
+    vc.fund_slot,
+    vc.fund_block_time,
+    vc.initial_amount_lovelace,
+    vc.status,
+    vc.created_at,
+    vc.updated_at,
+    -- Treasury context
+    tc.contract_instance as treasury_instance,
+    tc.name as treasury_name,
+    -- Milestone counts
+    COALESCE(s.total_milestones, 0) as total_milestones,
+    COALESCE(s.pending_milestones, 0) as pending_milestones,
+    COALESCE(s.completed_milestones, 0) as completed_milestones,
+    COALESCE(s.disbursed_milestones, 0) as disbursed_milestones,
+    -- Financial totals from milestones
+    COALESCE(s.total_disbursed_lovelace, 0) as total_disbursed_lovelace,
+    -- Current balance from UTXOs
+    COALESCE(s.current_balance_lovelace, 0) as current_balance_lovelace,
+    COALESCE(s.utxo_count, 0) as utxo_count,
+    -- Last event time
+    s.last_event_time,
+    -- Event count
+    COALESCE(s.event_count, 0) as event_count,
+    -- Resolved vendor
+    vc.vendor_id
+FROM vendor_contracts vc
+LEFT JOIN treasury_contracts tc ON tc.id = vc.treasury_id
+LEFT JOIN project_summary s ON s.vendor_contract_id = vc.id;
+
+CREATE OR REPLACE VIEW v_treasury_summary AS
+SELECT
+    tc.id as treasury_id,
+    tc.contract_instance,
+    tc.contract_address,
+    tc.stake_credential,
+    tc.name,
+    tc.status,
+    tc.publish_tx_hash,
+    tc.publish_time,
+    tc.initialized_tx_hash,
+    tc.initialized_at,
+    tc.permissions,
+    COALESCE(s.vendor_contract_count, 0) as vendor_contract_count,
+    COALESCE(s.active_contracts, 0) as active_contracts,
+    COALESCE(s.completed_contracts, 0) as completed_contracts,
+    COALESCE(s.cancelled_contracts, 0) as cancelled_contracts,
+    COALESCE(s.treasury_balance, 0) as treasury_balance,
+    COALESCE(s.utxo_count, 0) as utxo_count,
+    COALESCE(s.total_events, 0) as total_events,
+    s.last_event_time,
+    tc.created_at,
+    tc.updated_at
+FROM treasury_contracts tc
+LEFT JOIN treasury_summary s ON s.treasury_id = tc.id;
@@ -0,0 +1,15 @@
+-- Sync worker heartbeat
+--
+-- Written by the sync leader at the end of every cycle, including cycles that
+-- find no new events, so any process can tell whether sync is alive and how
+-- far it has scanned. Deliberately not covered by the data generation
+-- triggers: a heartbeat is not a data change.
+
+CREATE TABLE sync_heartbeat (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    -- host:pid of the worker that wrote the beat
+    worker TEXT NOT NULL,
+    -- Slot up to which yaci_store has been scanned for TOM events
+    scanned_slot BIGINT NOT NULL,
+    beat_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+ALTER TABLE sync_heartbeat
+    DROP COLUMN IF EXISTS slots_per_sec,
+    DROP COLUMN IF EXISTS caught_up;
@@ -0,0 +1,8 @@
+-- Sync progress rate, for catch-up estimates
+--
+-- caught_up: the cycle processed every event up to the indexer tip it read
+-- slots_per_sec: slots scanned per second since the same worker's previous beat
+
+ALTER TABLE sync_heartbeat
+    ADD COLUMN caught_up BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN slots_per_sec DOUBLE PRECISION;
@@ -0,0 +1,19 @@
+-- API keys
+--
+-- A key is shown once when issued; only its SHA-256 hash is stored.
+-- key_prefix (the first characters of the key) identifies it in listings and
+-- logs without revealing it. Revoked keys are kept for the audit trail.
+
+CREATE TABLE api_keys (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL CHECK (
+        cardinality(scopes) > 0 AND scopes <@ ARRAY['public-read', 'export', 'admin']::TEXT[]
+    ),
+    -- NULL uses rate_limit.key_per_minute
+    rate_limit_per_minute INTEGER CHECK (rate_limit_per_minute > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
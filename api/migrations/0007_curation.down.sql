@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS curation_audit_log;
+DROP TABLE IF EXISTS curation_annotations;
+DROP TABLE IF EXISTS curation_overrides;
@@ -0,0 +1,69 @@
+-- Curated corrections
+--
+-- Overrides replace chain-derived fields of vendor contracts and milestones
+-- when the API reads them; annotations attach operator notes. Both are keyed
+-- by the logical project and milestone identifiers rather than database ids,
+-- so they survive resyncs and reprocessing, which rebuild the chain-derived
+-- rows. Every change is recorded in curation_audit_log.
+
+CREATE TABLE curation_overrides (
+    id SERIAL PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    -- NULL for the vendor contract itself
+    milestone_id TEXT,
+    field TEXT NOT NULL,
+    value TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    author TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (
+        (milestone_id IS NULL AND field IN ('project_name', 'description', 'vendor_name', 'contract_url'))
+        OR (milestone_id IS NOT NULL AND field IN ('label', 'description', 'acceptance_criteria'))
+    )
+);
+
+CREATE UNIQUE INDEX idx_curation_overrides_target
+    ON curation_overrides (project_id, COALESCE(milestone_id, ''), field);
+
+CREATE TABLE curation_annotations (
+    id SERIAL PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    -- NULL for the vendor contract itself
+    milestone_id TEXT,
+    note TEXT NOT NULL,
+    author TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_curation_annotations_project ON curation_annotations (project_id);
+
+CREATE TABLE curation_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    -- set_override, remove_override, add_annotation or remove_annotation
+    action TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    milestone_id TEXT,
+    -- Overridden field; NULL for annotations
+    field TEXT,
+    old_value TEXT,
+    new_value TEXT,
+    reason TEXT,
+    -- Person responsible, as given in the request
+    author TEXT NOT NULL,
+    -- Credential that made the request: an API key prefix or the admin token
+    actor TEXT NOT NULL,
+    at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_curation_audit_log_project ON curation_audit_log (project_id, id);
+
+-- Curated values appear in API responses, so changes invalidate cached ones
+CREATE TRIGGER trg_curation_overrides_generation AFTER INSERT OR UPDATE OR DELETE ON curation_overrides
+    FOR EACH ROW EXECUTE FUNCTION bump_sync_generation();
+CREATE TRIGGER trg_curation_overrides_generation_truncate AFTER TRUNCATE ON curation_overrides
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_sync_generation();
+CREATE TRIGGER trg_curation_annotations_generation AFTER INSERT OR UPDATE OR DELETE ON curation_annotations
+    FOR EACH ROW EXECUTE FUNCTION bump_sync_generation();
+CREATE TRIGGER trg_curation_annotations_generation_truncate AFTER TRUNCATE ON curation_annotations
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_sync_generation();
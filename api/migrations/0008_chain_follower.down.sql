@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS chain_tip;
+DROP TABLE IF EXISTS chain_inputs;
+DROP TABLE IF EXISTS chain_outputs;
+DROP TABLE IF EXISTS chain_transactions;
+DROP TABLE IF EXISTS chain_blocks;
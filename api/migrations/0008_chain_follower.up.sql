@@ -0,0 +1,60 @@
+-- Chain data written by the built-in chain follower
+--
+-- With chain.source = "follower" the sync worker reads blocks itself instead
+-- of relying on an indexer, and keeps only what the event processor needs:
+-- transactions carrying TOM metadata or touching tracked addresses, with all
+-- their inputs and outputs, and the headers of recent blocks so it can find
+-- where to resume and roll back. Rows after a rollback point are deleted
+-- through the cascades. Like the heartbeat, none of this is covered by the
+-- data generation triggers.
+
+CREATE TABLE chain_blocks (
+    slot BIGINT PRIMARY KEY,
+    number BIGINT NOT NULL,
+    hash VARCHAR(64) NOT NULL,
+    -- Unix time
+    block_time BIGINT NOT NULL
+);
+
+CREATE TABLE chain_transactions (
+    tx_hash VARCHAR(64) PRIMARY KEY,
+    slot BIGINT NOT NULL REFERENCES chain_blocks(slot) ON DELETE CASCADE,
+    -- Position in the block
+    block_index INT NOT NULL,
+    treasury_donation BIGINT NOT NULL DEFAULT 0,
+    -- Metadata under label 1694; other labels are not kept
+    tom_metadata JSONB
+);
+
+CREATE INDEX idx_chain_transactions_slot ON chain_transactions (slot);
+CREATE INDEX idx_chain_transactions_tom ON chain_transactions (slot, block_index) WHERE tom_metadata IS NOT NULL;
+
+CREATE TABLE chain_outputs (
+    tx_hash VARCHAR(64) NOT NULL REFERENCES chain_transactions(tx_hash) ON DELETE CASCADE,
+    output_index SMALLINT NOT NULL,
+    address TEXT NOT NULL,
+    lovelace BIGINT NOT NULL,
+    PRIMARY KEY (tx_hash, output_index)
+);
+
+CREATE INDEX idx_chain_outputs_address ON chain_outputs (address);
+
+-- Outputs spent by kept transactions, which need not be kept themselves
+CREATE TABLE chain_inputs (
+    tx_hash VARCHAR(64) NOT NULL,
+    output_index SMALLINT NOT NULL,
+    spent_tx_hash VARCHAR(64) NOT NULL REFERENCES chain_transactions(tx_hash) ON DELETE CASCADE,
+    PRIMARY KEY (tx_hash, output_index)
+);
+
+CREATE INDEX idx_chain_inputs_spent_tx ON chain_inputs (spent_tx_hash);
+
+-- Tip of the chain being followed, as last reported by the node
+CREATE TABLE chain_tip (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    slot BIGINT NOT NULL,
+    number BIGINT NOT NULL,
+    hash VARCHAR(64) NOT NULL,
+    block_time BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,35 @@
+-- Submitted TOM transactions not yet on chain
+--
+-- Read by the sync worker from the submitted-transaction directory
+-- (pending.source_dir) and shown at /api/v1/events/pending. A transaction is
+-- confirmed once its event is processed and expired once it can no longer be
+-- included: its validity interval has passed the scanned slot, or it has been
+-- pending for longer than pending.expire_after_secs.
+
+CREATE TABLE pending_transactions (
+    tx_hash VARCHAR(64) PRIMARY KEY,
+    -- pending, confirmed or expired
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'confirmed', 'expired')),
+    -- Lowercased event name from the metadata
+    event_type TEXT NOT NULL,
+    instance TEXT,
+    project_id TEXT,
+    -- Lovelace paid to non-script addresses, for disbursements and withdrawals
+    amount_lovelace BIGINT,
+    -- First slot the transaction is no longer valid in, if it sets one
+    invalid_hereafter BIGINT,
+    metadata JSONB NOT NULL,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- When the transaction was confirmed or expired
+    resolved_at TIMESTAMPTZ,
+    -- Slot of the confirming event
+    slot BIGINT
+);
+
+CREATE INDEX idx_pending_transactions_status ON pending_transactions (status, submitted_at DESC);
+
+-- Pending transactions appear in API responses, so changes invalidate cached ones
+CREATE TRIGGER trg_pending_transactions_generation AFTER INSERT OR UPDATE OR DELETE ON pending_transactions
+    FOR EACH ROW EXECUTE FUNCTION bump_sync_generation();
+CREATE TRIGGER trg_pending_transactions_generation_truncate AFTER TRUNCATE ON pending_transactions
+    FOR EACH STATEMENT EXECUTE FUNCTION bump_sync_generation();
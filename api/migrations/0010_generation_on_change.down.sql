@@ -0,0 +1,26 @@
+CREATE OR REPLACE FUNCTION update_updated_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY[
+        'treasury_contracts', 'vendors', 'vendor_aliases', 'vendor_contracts', 'milestones',
+        'vendor_contract_revisions', 'evidence_archive', 'milestone_evidence', 'events', 'utxos',
+        'sync_status', 'project_summary', 'treasury_summary', 'curation_overrides',
+        'curation_annotations', 'pending_transactions'
+    ] LOOP
+        EXECUTE format('DROP TRIGGER trg_%1$s_generation_update ON %1$I', tbl);
+        EXECUTE format('DROP TRIGGER trg_%1$s_generation ON %1$I', tbl);
+        EXECUTE format(
+            'CREATE TRIGGER trg_%1$s_generation AFTER INSERT OR UPDATE OR DELETE ON %1$I
+             FOR EACH ROW EXECUTE FUNCTION bump_sync_generation()', tbl);
+    END LOOP;
+END;
+$$;
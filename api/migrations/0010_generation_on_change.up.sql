@@ -0,0 +1,38 @@
+-- Bump the data generation only for updates that change a row
+--
+-- Upserts that rewrite a row with the values it already holds (vendor
+-- resolution runs every sync cycle) fired the row trigger and cleared every
+-- API response cache. The row trigger is split so that updates only bump the
+-- generation when the row differs. updated_at is likewise left alone by
+-- updates that change nothing, or it would make every row differ.
+
+CREATE OR REPLACE FUNCTION update_updated_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW IS DISTINCT FROM OLD THEN
+        NEW.updated_at = NOW();
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY[
+        'treasury_contracts', 'vendors', 'vendor_aliases', 'vendor_contracts', 'milestones',
+        'vendor_contract_revisions', 'evidence_archive', 'milestone_evidence', 'events', 'utxos',
+        'sync_status', 'project_summary', 'treasury_summary', 'curation_overrides',
+        'curation_annotations', 'pending_transactions'
+    ] LOOP
+        EXECUTE format('DROP TRIGGER trg_%1$s_generation ON %1$I', tbl);
+        EXECUTE format(
+            'CREATE TRIGGER trg_%1$s_generation AFTER INSERT OR DELETE ON %1$I
+             FOR EACH ROW EXECUTE FUNCTION bump_sync_generation()', tbl);
+        EXECUTE format(
+            'CREATE TRIGGER trg_%1$s_generation_update AFTER UPDATE ON %1$I
+             FOR EACH ROW WHEN (OLD.* IS DISTINCT FROM NEW.*) EXECUTE FUNCTION bump_sync_generation()', tbl);
+    END LOOP;
+END;
+$$;
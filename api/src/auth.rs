@@ -0,0 +1,266 @@
+//! API key authentication and rate limiting
+//!
+//! Clients may send an API key in the `X-API-Key` header. Requests without
+//! one are anonymous: they can read public data and share a per-IP request
+//! limit. A key identifies the client for rate limiting (its own limit, or
+//! `rate_limit.key_per_minute`) and grants scopes:
+//!
+//! - `public-read`: read endpoints (also open to anonymous clients)
+//! - `export`: bulk exports under `/api/v1/export`
+//! - `admin`: the admin API, in place of the admin bearer token
+//!
+//! An unknown or revoked key is rejected with 401 rather than treated as
+//! anonymous, so a misconfigured client notices. Valid keys are cached for
+//! [`KEY_CACHE_TTL`]; a key revoked through another process (e.g.
+//! `treasury-cli keys revoke`) keeps working until its entry expires. Unknown
+//! keys are not cached, and each database lookup counts against the client
+//! IP's anonymous limit, so keys cannot be guessed faster than anonymous
+//! clients may make requests.
+//!
+//! Every response carries `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+//! `X-RateLimit-Reset` (seconds until the bucket is full again).
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{request::Parts, HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::ApiError;
+use crate::models::v1::ApiScope;
+use crate::rate_limit::{Client, Decision, RateLimiter};
+use crate::services::api_keys;
+use crate::state::AppState;
+
+/// Request header carrying the API key
+pub const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+pub const RATE_LIMIT_LIMIT_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub const RATE_LIMIT_REMAINING_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+pub const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// How long a valid key lookup is reused
+pub const KEY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Above this many cached lookups, expired ones are dropped
+const KEY_CACHE_PRUNE_THRESHOLD: usize = 10_000;
+
+/// An authenticated API key
+#[derive(Debug, Clone)]
+pub struct KeyIdentity {
+    pub id: i32,
+    pub key_prefix: String,
+    pub scopes: Vec<ApiScope>,
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+/// Who is making the request. Handlers take it as an extractor; requests that
+/// bypassed [`authenticate`] are anonymous.
+#[derive(Debug, Clone, Default)]
+pub struct Caller {
+    /// `None` for anonymous requests
+    pub key: Option<KeyIdentity>,
+}
+
+impl Caller {
+    /// Whether the caller may use endpoints requiring `scope`. Anyone may
+    /// read; `admin` implies every other scope.
+    pub fn has_scope(&self, scope: ApiScope) -> bool {
+        if scope == ApiScope::PublicRead {
+            return true;
+        }
+        self.key
+            .as_ref()
+            .is_some_and(|k| k.scopes.contains(&scope) || k.scopes.contains(&ApiScope::Admin))
+    }
+
+    /// Reject callers without `scope`: 401 for anonymous callers, 403 for
+    /// keys lacking it
+    pub fn require(&self, scope: ApiScope) -> Result<(), ApiError> {
+        match self.key {
+            _ if self.has_scope(scope) => Ok(()),
+            None => Err(ApiError::Unauthorized(format!(
+                "An API key with the '{}' scope is required",
+                scope.as_str()
+            ))),
+            Some(_) => Err(ApiError::Forbidden(format!(
+                "API key lacks the '{}' scope",
+                scope.as_str()
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Caller
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<Caller>().cloned().unwrap_or_default())
+    }
+}
+
+/// Valid keys by hash, with when they were looked up
+type KeyCache = HashMap<String, (Instant, KeyIdentity)>;
+
+/// Key lookups and rate limit buckets, shared by all requests
+#[derive(Clone, Default)]
+pub struct Auth {
+    keys: Arc<Mutex<KeyCache>>,
+    limiter: RateLimiter,
+}
+
+impl Auth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A key looked up less than [`KEY_CACHE_TTL`] ago
+    fn cached(&self, key_hash: &str) -> Option<KeyIdentity> {
+        self.keys
+            .lock()
+            .unwrap()
+            .get(key_hash)
+            .filter(|(at, _)| at.elapsed() < KEY_CACHE_TTL)
+            .map(|(_, identity)| identity.clone())
+    }
+
+    /// Look a key up in the database; `None` if it is unknown or revoked
+    async fn lookup(&self, pool: &PgPool, key_hash: String) -> Result<Option<KeyIdentity>, ApiError> {
+        let identity = api_keys::find_by_hash(pool, &key_hash)
+            .await?
+            .filter(|k| k.revoked_at.is_none())
+            .map(|k| KeyIdentity {
+                id: k.id,
+                key_prefix: k.key_prefix,
+                scopes: k.scopes,
+                rate_limit_per_minute: k.rate_limit_per_minute.map(|n| n.max(1) as u32),
+            });
+
+        // Only valid keys are kept, so the cache cannot grow past the key table
+        if let Some(ref identity) = identity {
+            let mut keys = self.keys.lock().unwrap();
+            if keys.len() >= KEY_CACHE_PRUNE_THRESHOLD {
+                keys.retain(|_, (at, _)| at.elapsed() < KEY_CACHE_TTL);
+            }
+            keys.insert(key_hash, (Instant::now(), identity.clone()));
+        }
+        Ok(identity)
+    }
+
+    /// Forget cached lookups, e.g. after a key is revoked
+    pub fn clear_keys(&self) {
+        self.keys.lock().unwrap().clear();
+    }
+}
+
+/// Middleware resolving the caller and enforcing its request limit
+pub async fn authenticate(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(&API_KEY_HEADER)
+        .map(|v| v.to_str().unwrap_or_default().trim().to_string());
+
+    let limits = &state.config.rate_limit;
+    let caller = match presented {
+        None => Caller::default(),
+        Some(key) => {
+            let key_hash = api_keys::hash_key(&key);
+            let identity = match state.auth.cached(&key_hash) {
+                Some(identity) => Some(identity),
+                None => {
+                    // Uncached keys reach the database: charge the client IP first
+                    if state.config.features.rate_limit {
+                        let decision = state.auth.limiter.check(
+                            Client::Ip(client_ip(&request, limits.trust_forwarded_for)),
+                            limits.anonymous_per_minute,
+                        );
+                        if !decision.allowed {
+                            return rate_limited(&decision);
+                        }
+                    }
+                    match state.auth.lookup(&state.pool, key_hash).await {
+                        Ok(identity) => identity,
+                        Err(e) => return e.into_response(),
+                    }
+                }
+            };
+            match identity {
+                Some(identity) => Caller { key: Some(identity) },
+                None => return ApiError::Unauthorized("Invalid or revoked API key".to_string()).into_response(),
+            }
+        }
+    };
+
+    let decision = state.config.features.rate_limit.then(|| match caller.key {
+        Some(ref key) => state.auth.limiter.check(
+            Client::Key(key.id),
+            key.rate_limit_per_minute.unwrap_or(limits.key_per_minute),
+        ),
+        None => state.auth.limiter.check(
+            Client::Ip(client_ip(&request, limits.trust_forwarded_for)),
+            limits.anonymous_per_minute,
+        ),
+    });
+
+    match decision {
+        Some(d) if !d.allowed => rate_limited(&d),
+        _ => {
+            request.extensions_mut().insert(caller);
+            let mut response = next.run(request).await;
+            if let Some(decision) = decision {
+                rate_limit_headers(response.headers_mut(), &decision);
+            }
+            response
+        }
+    }
+}
+
+/// 429 response for a denied request
+fn rate_limited(decision: &Decision) -> Response {
+    let mut response = ApiError::RateLimited(format!(
+        "Rate limit of {} requests per minute exceeded",
+        decision.limit
+    ))
+    .into_response();
+    response
+        .headers_mut()
+        .insert(axum::http::header::RETRY_AFTER, HeaderValue::from(decision.retry_after_secs.max(1)));
+    rate_limit_headers(response.headers_mut(), decision);
+    response
+}
+
+fn rate_limit_headers(headers: &mut HeaderMap, decision: &Decision) {
+    headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(decision.limit));
+    headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(decision.remaining));
+    headers.insert(RATE_LIMIT_RESET_HEADER, HeaderValue::from(decision.reset_secs));
+}
+
+/// The connecting address, or the first `X-Forwarded-For` hop when trusted
+fn client_ip(request: &Request, trust_forwarded_for: bool) -> IpAddr {
+    let forwarded = trust_forwarded_for
+        .then(|| request.headers().get("x-forwarded-for"))
+        .flatten()
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .and_then(|v| v.trim().parse().ok());
+
+    forwarded
+        .or_else(|| {
+            request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip())
+        })
+        // Without connection info (e.g. in tests) all anonymous clients share a bucket
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+}
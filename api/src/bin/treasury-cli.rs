@@ -0,0 +1,468 @@
+//! Treasury database maintenance tool
+//!
+//! Runs against the same database and configuration as `treasury-api`; the
+//! HTTP server does not need to be running.
+
+use clap::{Parser, Subcommand};
+use sqlx::PgPool;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use treasury_api::chain;
+use treasury_api::config::{Config, ConfigArgs};
+use treasury_api::db::{self, migrations, replica, Migrator};
+use treasury_api::models::v1::ApiScope;
+use treasury_api::services::maintenance::{self, ExportEntity, ExportFormat};
+use treasury_api::services::reindex::{self, RowChange};
+use treasury_api::services::vendors::{self, AliasType};
+use treasury_api::services::{api_keys, summaries};
+
+/// Cardano treasury database maintenance
+#[derive(Parser)]
+#[command(name = "treasury-cli", version)]
+struct Cli {
+    #[command(flatten)]
+    config: ConfigArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply, revert or list schema migrations
+    Migrate {
+        #[command(subcommand)]
+        action: Option<MigrateAction>,
+    },
+    /// Drop processed events from a slot onwards and replay them from the indexer
+    Resync {
+        /// First slot to replay (0 replays everything)
+        #[arg(long)]
+        from_slot: i64,
+    },
+    /// Reprocess the TOM event of a single transaction
+    Reprocess {
+        /// Transaction hash
+        #[arg(long)]
+        tx: String,
+    },
+    /// Compare tracked UTXO balances with the indexer (exits 1 on mismatch)
+    Verify,
+    /// Rebuild or check the project and treasury summary tables
+    Summaries {
+        #[command(subcommand)]
+        action: SummariesAction,
+    },
+    /// Export a data set as JSON lines or CSV
+    Export {
+        /// Data set to export
+        entity: ExportEntity,
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// Output file (default: stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Show sync progress against the indexer tip
+    Status,
+    /// Issue, list or revoke API keys
+    Keys {
+        #[command(subcommand)]
+        action: KeysAction,
+    },
+    /// Replay every event into a shadow schema, compare it and swap it in
+    Reindex {
+        #[command(subcommand)]
+        action: ReindexAction,
+    },
+    /// Add, list or remove vendor aliases
+    Aliases {
+        #[command(subcommand)]
+        action: AliasesAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateAction {
+    /// Apply pending migrations (the default)
+    Up {
+        /// Stop at this version instead of the latest
+        #[arg(long)]
+        to: Option<i64>,
+    },
+    /// Revert migrations newer than a version (0 reverts everything)
+    Down {
+        /// Version to revert to
+        #[arg(long)]
+        to: i64,
+    },
+    /// List migrations and whether they are applied
+    Status,
+}
+
+#[derive(Subcommand)]
+enum SummariesAction {
+    /// Recompute every summary row from scratch
+    Rebuild,
+    /// Compare summary rows with a recomputation (exits 1 on mismatch)
+    Check,
+}
+
+#[derive(Subcommand)]
+enum KeysAction {
+    /// Issue a key; it is printed once and cannot be recovered
+    Issue {
+        /// Who or what the key is for
+        #[arg(long)]
+        name: String,
+        /// Scope to grant (repeatable)
+        #[arg(long = "scope", value_enum, required = true)]
+        scopes: Vec<ApiScope>,
+        /// Requests per minute (default: rate_limit.key_per_minute)
+        #[arg(long)]
+        rate_limit: Option<i32>,
+    },
+    /// List issued keys
+    List,
+    /// Revoke a key
+    Revoke {
+        /// Key id, as shown by `keys list`
+        id: i32,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReindexAction {
+    /// Rebuild the shadow schema by replaying every TOM event
+    Build,
+    /// Compare the live schema with the shadow schema
+    Diff {
+        /// Print the full diff as JSON
+        #[arg(long)]
+        json: bool,
+        /// Rows listed per table (text output)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Catch the shadow schema up and make it the live schema
+    Swap,
+    /// Drop the shadow schema
+    Discard,
+}
+
+#[derive(Subcommand)]
+enum AliasesAction {
+    /// Group projects matching a value under a vendor key (replaces an
+    /// existing alias for the same value)
+    Add {
+        /// What the value matches
+        #[arg(value_enum)]
+        alias_type: AliasType,
+        /// Payout address, stake credential or stake address, or vendor name
+        value: String,
+        /// Vendor key to group under, e.g. an existing `stake:...` key
+        #[arg(long)]
+        vendor_key: String,
+        /// Display name for the vendor
+        #[arg(long)]
+        name: Option<String>,
+        /// Why the alias exists
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// List aliases
+    List,
+    /// Remove an alias
+    Remove {
+        /// Alias id, as shown by `aliases list`
+        id: i32,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Logs go to stderr so exports can be piped
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(io::stderr)
+        .init();
+
+    dotenvy::dotenv().ok();
+
+    let cli = Cli::parse();
+    let config = Config::from_args(cli.config)?;
+    let pool = db::connect(&config.database).await?;
+    let chain = chain::open(&config.chain, &config.database, &pool).await?;
+
+    match cli.command {
+        Command::Migrate { action } => {
+            let migrator = Migrator::new(&pool);
+            match action.unwrap_or(MigrateAction::Up { to: None }) {
+                MigrateAction::Up { to } => {
+                    let target = to.unwrap_or_else(migrations::latest_version);
+                    let current = migrator.current_version().await?;
+                    anyhow::ensure!(target >= current, "schema is at version {}; use `migrate down` to revert", current);
+                    let applied = migrator.migrate_to(target).await?;
+                    println!("Applied {} migration(s); schema is at version {}", applied.len(), target);
+                }
+                MigrateAction::Down { to } => {
+                    let current = migrator.current_version().await?;
+                    anyhow::ensure!(to <= current, "schema is at version {}; use `migrate up` to apply", current);
+                    let reverted = migrator.migrate_to(to).await?;
+                    println!("Reverted {} migration(s); schema is at version {}", reverted.len(), to);
+                }
+                MigrateAction::Status => {
+                    for status in migrator.status().await? {
+                        let state = match status.applied {
+                            Some(ref applied) if status.checksum_ok() => {
+                                format!("applied {}", applied.applied_at.format("%Y-%m-%d %H:%M:%S"))
+                            }
+                            Some(_) => "MODIFIED since applied".to_string(),
+                            None => "pending".to_string(),
+                        };
+                        println!("{:>4}  {:<24} {}", status.migration.version, status.migration.name, state);
+                    }
+                    println!("Binary supports up to version {}", migrations::latest_version());
+                }
+            }
+        }
+        Command::Resync { from_slot } => {
+            anyhow::ensure!(from_slot >= 0, "--from-slot must not be negative");
+            let report = maintenance::resync_from_slot(&pool, chain, from_slot, config.sync.batch_size.into()).await?;
+            println!(
+                "Removed {} events from slot {}; replayed {} TOM events",
+                report.deleted_events, from_slot, report.replayed_events
+            );
+        }
+        Command::Reprocess { tx } => {
+            if !maintenance::reprocess_tx(&pool, chain, &tx).await? {
+                anyhow::bail!("No TOM metadata found for transaction {}", tx);
+            }
+            println!("Reprocessed {}", tx);
+        }
+        Command::Verify => {
+            let checks = maintenance::verify_balances(&pool, chain.as_ref()).await?;
+            let mismatches = checks.iter().filter(|c| !c.matches()).count();
+            for check in &checks {
+                println!(
+                    "{:<8} {:<16} tracked {:>20} indexer {:>20}  {}",
+                    if check.matches() { "ok" } else { "MISMATCH" },
+                    check.address_type.as_deref().unwrap_or("-"),
+                    check.tracked_lovelace,
+                    check.indexer_lovelace,
+                    check.address
+                );
+            }
+            if mismatches > 0 {
+                anyhow::bail!("{} of {} address balances differ from the indexer", mismatches, checks.len());
+            }
+            println!("All {} address balances match the indexer", checks.len());
+        }
+        Command::Summaries { action } => match action {
+            SummariesAction::Rebuild => {
+                let report = summaries::rebuild(&pool).await?;
+                println!(
+                    "Rebuilt {} project and {} treasury summaries",
+                    report.projects, report.treasuries
+                );
+            }
+            SummariesAction::Check => {
+                let mismatches = summaries::check(&pool).await?;
+                for mismatch in &mismatches {
+                    println!("MISMATCH {} {}", mismatch.kind, mismatch.id);
+                    println!("  stored:   {}", display(mismatch.stored.as_ref()));
+                    println!("  expected: {}", display(mismatch.expected.as_ref()));
+                }
+                if !mismatches.is_empty() {
+                    anyhow::bail!(
+                        "{} summary rows differ from a recomputation; run `treasury-cli summaries rebuild`",
+                        mismatches.len()
+                    );
+                }
+                println!("All summary rows match a recomputation");
+            }
+        },
+        Command::Export { entity, format, output } => {
+            let mut out: Box<dyn Write> = match output {
+                Some(ref path) => Box::new(BufWriter::new(File::create(path)?)),
+                None => Box::new(BufWriter::new(io::stdout().lock())),
+            };
+            let source = export_pool(&pool, &config).await?;
+            let rows = maintenance::export(&source, entity, format, &mut out).await?;
+            if let Some(path) = output {
+                eprintln!("Wrote {} rows to {}", rows, path.display());
+            }
+        }
+        Command::Status => {
+            let Some(report) = maintenance::sync_report(&pool, chain.as_ref()).await? else {
+                println!("Treasury schema not initialised (run `treasury-cli migrate`)");
+                return Ok(());
+            };
+            let lag = report.indexer_tip_slot.zip(report.last_slot).map(|(tip, last)| (tip - last).max(0));
+            println!("Last synced slot:   {}", display(report.last_slot));
+            println!("Last synced block:  {}", display(report.last_block));
+            println!("Last synced tx:     {}", display(report.last_tx_hash));
+            println!("Last sync at:       {}", display(report.updated_at));
+            println!("Indexer tip slot:   {}", display(report.indexer_tip_slot));
+            println!("Indexer tip block:  {}", display(report.indexer_tip_block));
+            println!("Lag (slots):        {}", display(lag));
+            println!("Treasury contracts: {}", report.treasury_contracts);
+            println!("Vendor contracts:   {}", report.vendor_contracts);
+            println!("Milestones:         {}", report.milestones);
+            println!("Events:             {}", report.events);
+            println!("Unspent UTXOs:      {}", report.unspent_utxos);
+        }
+        Command::Keys { action } => match action {
+            KeysAction::Issue { name, scopes, rate_limit } => {
+                anyhow::ensure!(!name.trim().is_empty(), "--name must not be empty");
+                anyhow::ensure!(rate_limit.is_none_or(|n| n >= 1), "--rate-limit must be at least 1");
+                let (details, key) = api_keys::issue(&pool, name.trim(), &scopes, rate_limit).await?;
+                eprintln!("Issued key {} to '{}'; it will not be shown again", details.id, details.name);
+                println!("{}", key);
+            }
+            KeysAction::List => {
+                for key in api_keys::list(&pool).await? {
+                    let scopes: Vec<&str> = key.scopes.iter().map(|s| s.as_str()).collect();
+                    let state = match key.revoked_at {
+                        Some(at) => format!("revoked {}", at.format("%Y-%m-%d %H:%M:%S")),
+                        None => "active".to_string(),
+                    };
+                    println!(
+                        "{:>4}  {:<14} {:<24} {:<28} {:>8}  {}",
+                        key.id,
+                        key.key_prefix,
+                        key.name,
+                        scopes.join(","),
+                        display(key.rate_limit_per_minute),
+                        state
+                    );
+                }
+            }
+            KeysAction::Revoke { id } => {
+                let Some(key) = api_keys::revoke(&pool, id).await? else {
+                    anyhow::bail!("No API key with id {}", id);
+                };
+                // Running servers cache key lookups briefly
+                println!(
+                    "Revoked key {} ({}); running servers reject it within {} seconds",
+                    key.id,
+                    key.name,
+                    treasury_api::auth::KEY_CACHE_TTL.as_secs()
+                );
+            }
+        },
+        Command::Reindex { action } => match action {
+            ReindexAction::Build => {
+                let report = reindex::build(&pool, &config.database, chain, config.sync.batch_size.into()).await?;
+                println!(
+                    "Replayed {} TOM events into {} (up to slot {}); review with `treasury-cli reindex diff`",
+                    report.replayed_events,
+                    reindex::SHADOW_SCHEMA,
+                    report.last_slot
+                );
+            }
+            ReindexAction::Diff { json, limit } => {
+                let diff = reindex::diff(&pool).await?;
+                if json {
+                    serde_json::to_writer_pretty(io::stdout().lock(), &diff)?;
+                    println!();
+                    return Ok(());
+                }
+                for table in &diff.tables {
+                    println!(
+                        "{:<26} live {:>8}  shadow {:>8}  +{} -{} ~{}",
+                        table.table, table.live_rows, table.shadow_rows, table.added, table.removed, table.changed
+                    );
+                    for row in table.rows.iter().take(limit) {
+                        match row.change {
+                            RowChange::Added => println!("  + {}", row.key),
+                            RowChange::Removed => println!("  - {}", row.key),
+                            RowChange::Changed => {
+                                let columns: Vec<&str> = row.columns.iter().map(|c| c.column.as_str()).collect();
+                                println!("  ~ {} ({})", row.key, columns.join(", "));
+                            }
+                        }
+                    }
+                    if table.rows.len() > limit {
+                        println!("  ... {} more", table.rows.len() - limit);
+                    }
+                }
+                if diff.has_changes() {
+                    println!("The shadow schema differs; `treasury-cli reindex swap` makes it live");
+                } else {
+                    println!("The shadow schema matches the live schema");
+                }
+            }
+            ReindexAction::Swap => {
+                let report = reindex::swap(&pool, &config.database, chain, config.sync.batch_size.into()).await?;
+                println!(
+                    "Swapped {} in after replaying {} new events; the old schema is kept as {}",
+                    reindex::SHADOW_SCHEMA,
+                    report.caught_up_events,
+                    reindex::PREVIOUS_SCHEMA
+                );
+            }
+            ReindexAction::Discard => {
+                if reindex::discard(&pool).await? {
+                    println!("Dropped {}", reindex::SHADOW_SCHEMA);
+                } else {
+                    println!("No {} schema to drop", reindex::SHADOW_SCHEMA);
+                }
+            }
+        },
+        Command::Aliases { action } => match action {
+            AliasesAction::Add { alias_type, value, vendor_key, name, note } => {
+                let alias = vendors::add_alias(&pool, alias_type, &value, &vendor_key, name.as_deref(), note.as_deref()).await?;
+                vendors::resolve_vendors(&pool).await?;
+                println!("Alias {}: {} {} -> {}", alias.id, alias.alias_type, alias.alias_value, alias.vendor_key);
+            }
+            AliasesAction::List => {
+                for alias in vendors::list_aliases(&pool).await? {
+                    println!(
+                        "{:>4}  {:<16} {:<64} {:<32} {:<24} {}",
+                        alias.id,
+                        alias.alias_type,
+                        alias.alias_value,
+                        alias.vendor_key,
+                        display(alias.vendor_name),
+                        alias.note.unwrap_or_default()
+                    );
+                }
+            }
+            AliasesAction::Remove { id } => {
+                let Some(alias) = vendors::remove_alias(&pool, id).await? else {
+                    anyhow::bail!("No vendor alias with id {}", id);
+                };
+                vendors::resolve_vendors(&pool).await?;
+                println!("Removed alias {}: {} {}", alias.id, alias.alias_type, alias.alias_value);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// The read replica if one is configured and within the lag threshold,
+/// otherwise the primary
+async fn export_pool(primary: &PgPool, config: &Config) -> anyhow::Result<PgPool> {
+    let Some(replica) = db::connect_replica(&config.database).await? else {
+        return Ok(primary.clone());
+    };
+    match replica::replica_lag(primary, &replica).await {
+        Ok(lag) if lag <= config.database.replica_max_lag_slots => Ok(replica),
+        Ok(lag) => {
+            tracing::warn!("Read replica is {} slots behind; exporting from primary", lag);
+            Ok(primary.clone())
+        }
+        Err(e) => {
+            tracing::warn!("Read replica check failed: {}; exporting from primary", e);
+            Ok(primary.clone())
+        }
+    }
+}
+
+fn display<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,243 @@
+//! Response cache
+//!
+//! Successful GET responses under `/api/v1` are cached in process, keyed by
+//! path and query. Entries belong to a data generation: a counter in
+//! `treasury.sync_generation` that database triggers bump whenever treasury
+//! data changes. A background task polls the counter and drops every entry
+//! when it moves, so cached data is never older than one poll interval.
+//!
+//! Cached responses carry an `ETag` (hash of the body) and `Last-Modified`
+//! (time of the last data change). Conditional requests that still match are
+//! answered with 304 from the cache, without touching the database.
+//! Handlers whose responses carry `Cache-Control: no-store` are never cached.
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use crate::db::{ReadPool, Replica};
+
+/// Header telling whether a response came from the cache
+pub const CACHE_STATUS_HEADER: HeaderName = HeaderName::from_static("x-cache");
+
+/// Larger responses are passed through uncached
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// Data generation as last read from the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Generation {
+    value: i64,
+    changed_at: DateTime<Utc>,
+}
+
+struct CachedResponse {
+    generation: i64,
+    headers: HeaderMap,
+    body: Bytes,
+    etag: HeaderValue,
+}
+
+/// In-process response cache, shared by all handlers
+#[derive(Clone)]
+pub struct ResponseCache {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// `None` until the first poll succeeds, and while polling fails
+    generation: RwLock<Option<Generation>>,
+    entries: Mutex<HashMap<String, CachedResponse>>,
+    max_entries: usize,
+}
+
+impl ResponseCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                generation: RwLock::new(None),
+                entries: Mutex::new(HashMap::new()),
+                max_entries,
+            }),
+        }
+    }
+
+    fn generation(&self) -> Option<Generation> {
+        *self.inner.generation.read().unwrap()
+    }
+
+    /// Poll the data generation every `interval`, reading from the same pool
+    /// as the handlers so a lagging replica is not cached under a newer
+    /// generation
+    pub async fn watch_generation(self, primary: PgPool, replica: Option<Replica>, interval: Duration) {
+        loop {
+            let ReadPool(pool) = ReadPool::select(&primary, replica.as_ref());
+            let polled = sqlx::query_as::<_, (i64, DateTime<Utc>)>(
+                "SELECT generation, changed_at FROM treasury.sync_generation",
+            )
+            .fetch_one(&pool)
+            .await;
+
+            let next = match polled {
+                Ok((value, changed_at)) => Some(Generation { value, changed_at }),
+                Err(e) => {
+                    if self.generation().is_some() {
+                        tracing::warn!("Data generation poll failed: {}; response cache bypassed", e);
+                    }
+                    None
+                }
+            };
+            if next != self.generation() {
+                self.inner.entries.lock().unwrap().clear();
+                *self.inner.generation.write().unwrap() = next;
+                if let Some(generation) = next {
+                    tracing::debug!("Data generation is now {}; response cache cleared", generation.value);
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    fn get(&self, key: &str, generation: i64) -> Option<(HeaderMap, Bytes, HeaderValue)> {
+        let entries = self.inner.entries.lock().unwrap();
+        entries
+            .get(key)
+            .filter(|entry| entry.generation == generation)
+            .map(|entry| (entry.headers.clone(), entry.body.clone(), entry.etag.clone()))
+    }
+
+    fn insert(&self, key: String, entry: CachedResponse) {
+        let mut entries = self.inner.entries.lock().unwrap();
+        if entries.len() >= self.inner.max_entries && !entries.contains_key(&key) {
+            // Entries from older generations go first, otherwise any one
+            let current = self.generation().map(|g| g.value);
+            entries.retain(|_, e| Some(e.generation) == current);
+            if entries.len() >= self.inner.max_entries {
+                if let Some(evict) = entries.keys().next().cloned() {
+                    entries.remove(&evict);
+                }
+            }
+        }
+        entries.insert(key, entry);
+    }
+}
+
+/// Middleware serving cached responses and filling the cache
+pub async fn cache_responses(State(cache): State<ResponseCache>, request: Request, next: Next) -> Response {
+    let cacheable = request.method() == Method::GET && !request.uri().path().starts_with("/admin");
+    let Some(generation) = cache.generation().filter(|_| cacheable) else {
+        return next.run(request).await;
+    };
+
+    let key = request.uri().to_string();
+    let request_headers = request.headers().clone();
+
+    if let Some((headers, body, etag)) = cache.get(&key, generation.value) {
+        return respond(&request_headers, generation, headers, body, etag, "HIT");
+    }
+
+    let response = next.run(request).await;
+    let fits = response.body().size_hint().upper().is_some_and(|n| n <= MAX_BODY_BYTES);
+    if response.status() != StatusCode::OK || !fits || no_store(response.headers()) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES as usize).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for caching: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let etag = etag_for(&body);
+
+    cache.insert(
+        key,
+        CachedResponse {
+            generation: generation.value,
+            headers: parts.headers.clone(),
+            body: body.clone(),
+            etag: etag.clone(),
+        },
+    );
+    respond(&request_headers, generation, parts.headers, body, etag, "MISS")
+}
+
+/// Full or 304 response with validators
+fn respond(
+    request_headers: &HeaderMap,
+    generation: Generation,
+    headers: HeaderMap,
+    body: Bytes,
+    etag: HeaderValue,
+    cache_status: &'static str,
+) -> Response {
+    let last_modified = http_date(generation.changed_at);
+    let mut response = if not_modified(request_headers, &etag, generation.changed_at) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let mut response = Response::new(Body::from(body));
+        *response.headers_mut() = headers;
+        response
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(header::ETAG, etag);
+    if let Ok(value) = HeaderValue::from_str(&last_modified) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    // Clients may store responses but must revalidate them
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    headers.insert(CACHE_STATUS_HEADER, HeaderValue::from_static(cache_status));
+    response
+}
+
+/// `If-None-Match` takes precedence over `If-Modified-Since` (RFC 9110)
+fn not_modified(request_headers: &HeaderMap, etag: &HeaderValue, changed_at: DateTime<Utc>) -> bool {
+    if let Some(if_none_match) = request_headers.get(header::IF_NONE_MATCH) {
+        let Ok(tags) = if_none_match.to_str() else {
+            return false;
+        };
+        let etag = etag.to_str().unwrap_or_default();
+        return tags
+            .split(',')
+            .map(|t| t.trim().trim_start_matches("W/"))
+            .any(|t| t == "*" || t == etag);
+    }
+
+    request_headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+        // HTTP dates have whole-second precision
+        .is_some_and(|since| changed_at.timestamp() <= since.timestamp())
+}
+
+/// Handlers opt out of caching with `Cache-Control: no-store`, e.g. when the
+/// response reports live values that do not bump the data generation
+fn no_store(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CACHE_CONTROL)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|d| d.trim().eq_ignore_ascii_case("no-store")))
+}
+
+fn etag_for(body: &Bytes) -> HeaderValue {
+    let digest = Sha256::digest(body);
+    HeaderValue::from_str(&format!("\"{}\"", hex::encode(&digest[..16]))).expect("hex is a valid header value")
+}
+
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
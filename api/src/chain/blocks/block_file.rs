@@ -0,0 +1,180 @@
+//! Block file replay
+//!
+//! A block file is a log of chain updates, one JSON object per line: a block
+//! in the file dump format (see `chain::file_dump`), with its `hash`, or a
+//! rollback to an earlier block (`null` for genesis):
+//!
+//! ```text
+//! {"number": 1, "slot": 50, "hash": "b50", "block_time": 1700000050, "transactions": [...]}
+//! {"number": 2, "slot": 100, "hash": "b100", "block_time": 1700000100, "transactions": [...]}
+//! {"rollback": {"slot": 50, "hash": "b50"}}
+//! ```
+//!
+//! The whole file is read when it is opened. Replay resumes after the last
+//! line holding the intersection block, so rollbacks later in the file are
+//! replayed too.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use super::{Block, BlockSource, Output, Point, Step, Tip, Transaction};
+use crate::chain::file_dump::DumpBlock;
+use crate::chain::{OutputRef, TOM_LABEL};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RollbackEntry {
+    rollback: Option<PointEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PointEntry {
+    slot: i64,
+    hash: String,
+}
+
+/// Block source replaying a block file
+#[derive(Debug)]
+pub struct BlockFile {
+    path: PathBuf,
+    steps: Vec<Step>,
+    /// Index of the next step to replay
+    position: usize,
+    tip: Option<Tip>,
+}
+
+impl BlockFile {
+    /// Read a block file
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read block file {}", path.display()))?;
+
+        let mut steps = Vec::new();
+        for (number, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let step = parse_line(line)
+                .with_context(|| format!("invalid block file {} at line {}", path.display(), number + 1))?;
+            steps.push(step);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            tip: final_tip(&steps),
+            steps,
+            position: 0,
+        })
+    }
+}
+
+fn parse_line(line: &str) -> anyhow::Result<Step> {
+    let value: Value = serde_json::from_str(line)?;
+    if value.get("rollback").is_some() {
+        let entry: RollbackEntry = serde_json::from_value(value)?;
+        return Ok(Step::RollBackward(entry.rollback.map(|p| Point {
+            slot: p.slot,
+            hash: p.hash,
+        })));
+    }
+
+    let block: DumpBlock = serde_json::from_value(value)?;
+    let hash = block.hash.ok_or_else(|| anyhow::anyhow!("block at slot {} has no hash", block.slot))?;
+    let transactions = block
+        .transactions
+        .into_iter()
+        .map(|tx| Transaction {
+            inputs: tx
+                .inputs
+                .into_iter()
+                .map(|i| OutputRef {
+                    tx_hash: i.tx_hash,
+                    output_index: i.output_index,
+                })
+                .collect(),
+            outputs: tx
+                .outputs
+                .into_iter()
+                .enumerate()
+                .map(|(index, o)| Output {
+                    index: index as i16,
+                    address: o.address,
+                    lovelace: o.lovelace,
+                })
+                .collect(),
+            treasury_donation: tx.treasury_donation,
+            tom_metadata: tx.metadata.get(&TOM_LABEL.to_string()).cloned(),
+            hash: tx.hash,
+        })
+        .collect();
+
+    Ok(Step::RollForward(Block {
+        point: Point {
+            slot: block.slot,
+            hash,
+        },
+        number: block.number,
+        block_time: block.block_time,
+        transactions,
+    }))
+}
+
+/// The last block on the chain once every step has been applied
+fn final_tip(steps: &[Step]) -> Option<Tip> {
+    let mut chain: Vec<&Block> = Vec::new();
+    for step in steps {
+        match step {
+            Step::RollForward(block) => chain.push(block),
+            Step::RollBackward(point) => {
+                let keep = point.as_ref().map_or(0, |p| chain.partition_point(|b| b.point.slot <= p.slot));
+                chain.truncate(keep);
+            }
+        }
+    }
+    chain.last().map(|b| Tip {
+        point: b.point.clone(),
+        number: b.number,
+        block_time: b.block_time,
+    })
+}
+
+#[async_trait]
+impl BlockSource for BlockFile {
+    fn describe(&self) -> String {
+        format!("block file {}", self.path.display())
+    }
+
+    async fn intersect(&mut self, points: &[Point]) -> anyhow::Result<Option<Point>> {
+        if points.is_empty() {
+            self.position = 0;
+            return Ok(None);
+        }
+        for point in points {
+            let found = self
+                .steps
+                .iter()
+                .rposition(|s| matches!(s, Step::RollForward(b) if b.point == *point));
+            if let Some(index) = found {
+                self.position = index + 1;
+                return Ok(Some(point.clone()));
+            }
+        }
+        anyhow::bail!("none of the {} given blocks is in {}", points.len(), self.path.display())
+    }
+
+    async fn next(&mut self) -> anyhow::Result<Option<Step>> {
+        let step = self.steps.get(self.position).cloned();
+        if step.is_some() {
+            self.position += 1;
+        }
+        Ok(step)
+    }
+
+    fn tip(&self) -> Option<Tip> {
+        self.tip.clone()
+    }
+}
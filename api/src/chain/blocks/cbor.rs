@@ -0,0 +1,858 @@
+//! Decoding of Cardano headers, blocks and submitted transactions
+//!
+//! Only what the follower and the pending feed keep is decoded: block and
+//! slot numbers, hashes, inputs, lovelace outputs, treasury donations,
+//! validity intervals and metadata under [`TOM_LABEL`]. Everything else is skipped without being interpreted, so
+//! new fields in later eras do not break decoding. Byron-era blocks are not
+//! supported; follow from a point after the Byron era.
+//!
+//! Hashes are blake2b-256 of the bytes as they appear on chain, so headers
+//! and transaction bodies are hashed from the input rather than re-encoded.
+
+use bech32::{ToBase32, Variant};
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use minicbor::data::Type;
+use minicbor::decode::Error;
+use minicbor::Decoder;
+use serde_json::{Map, Value};
+
+use super::{Block, Output, Point, Transaction};
+use crate::chain::{OutputRef, TOM_LABEL};
+use crate::config::CardanoNetwork;
+
+/// Era index of Byron in the hard fork combinator's envelope
+const BYRON_ERA: u64 = 0;
+
+/// Hex blake2b-256 of `bytes`
+fn blake2b_256(bytes: &[u8]) -> String {
+    hex::encode(Blake2b::<U32>::digest(bytes))
+}
+
+/// Point and block number of a header, from its bytes
+pub fn decode_header(era: u64, bytes: &[u8]) -> anyhow::Result<(Point, i64)> {
+    if era == BYRON_ERA {
+        anyhow::bail!("Byron-era headers are not supported; start after the Byron era");
+    }
+    let mut d = Decoder::new(bytes);
+    let header = (|| -> Result<(u64, u64), Error> {
+        d.array()?;
+        d.array()?;
+        let number = d.u64()?;
+        let slot = d.u64()?;
+        Ok((number, slot))
+    })();
+    let (number, slot) = header.map_err(|e| anyhow::anyhow!("invalid header: {}", e))?;
+    Ok((
+        Point {
+            slot: slot as i64,
+            hash: blake2b_256(bytes),
+        },
+        number as i64,
+    ))
+}
+
+/// Decode a block wrapped in the hard fork combinator's `[era, block]`
+pub fn decode_block(bytes: &[u8], network: CardanoNetwork) -> anyhow::Result<Block> {
+    let mut d = Decoder::new(bytes);
+    d.array().map_err(invalid_block)?;
+    let era = d.u64().map_err(invalid_block)?;
+    if era == BYRON_ERA {
+        anyhow::bail!("Byron-era blocks are not supported; start after the Byron era");
+    }
+    let body = &bytes[d.position()..];
+    let block = decode_shelley_block(body).map_err(invalid_block)?;
+
+    let (point, number) = decode_header(era, &body[block.header.clone()])?;
+    let transactions = block
+        .bodies
+        .into_iter()
+        .enumerate()
+        .map(|(index, body)| {
+            let metadata = block.metadata.iter().find(|(i, _)| *i == index as u64).map(|(_, m)| m.clone());
+            if block.invalid.contains(&(index as u64)) {
+                body.failed()
+            } else {
+                body.applied(metadata)
+            }
+        })
+        .collect();
+
+    Ok(Block {
+        block_time: network.slot_time(point.slot),
+        point,
+        number,
+        transactions,
+    })
+}
+
+/// Decode a submitted transaction, `[body, witness set, valid, auxiliary
+/// data]` (without `valid` before Alonzo), with the first slot it is no
+/// longer valid in, if it sets one
+pub fn decode_transaction(bytes: &[u8]) -> anyhow::Result<(Transaction, Option<i64>)> {
+    let mut d = Decoder::new(bytes);
+    let tx = (|| -> Result<(TxBody, Option<Value>), Error> {
+        let fields = d.array()?;
+        let start = d.position();
+        let mut body = decode_tx_body(&mut d)?;
+        body.hash = blake2b_256(&d.input()[start..d.position()]);
+        // Witness set
+        d.skip()?;
+        let has_valid = match fields {
+            Some(n) => n > 3,
+            None => d.datatype()? == Type::Bool,
+        };
+        if has_valid {
+            d.bool()?;
+        }
+        let tom = match d.datatype()? {
+            Type::Null => None,
+            _ => decode_auxiliary_data(&mut d)?,
+        };
+        Ok((body, tom))
+    })();
+    let (body, tom) = tx.map_err(|e| anyhow::anyhow!("invalid transaction: {}", e))?;
+    let ttl = body.ttl.map(|slot| slot as i64);
+    Ok((body.applied(tom), ttl))
+}
+
+fn invalid_block(e: Error) -> anyhow::Error {
+    anyhow::anyhow!("invalid block: {}", e)
+}
+
+/// The parts of a Shelley-family block the follower reads
+struct RawBlock {
+    /// Byte range of the header
+    header: std::ops::Range<usize>,
+    bodies: Vec<TxBody>,
+    /// TOM metadata by transaction index
+    metadata: Vec<(u64, Value)>,
+    /// Transactions whose scripts failed
+    invalid: Vec<u64>,
+}
+
+/// `[header, [tx body], [witness set], {index => auxiliary data}, [invalid index]]`;
+/// blocks before Alonzo have no invalid transactions
+fn decode_shelley_block(bytes: &[u8]) -> Result<RawBlock, Error> {
+    let mut d = Decoder::new(bytes);
+    let fields = d.array()?;
+
+    let start = d.position();
+    d.skip()?;
+    let header = start..d.position();
+
+    let mut bodies = Vec::new();
+    let len = d.array()?;
+    each(&mut d, len, |d| {
+        let start = d.position();
+        let mut body = decode_tx_body(d)?;
+        body.hash = blake2b_256(&d.input()[start..d.position()]);
+        bodies.push(body);
+        Ok(())
+    })?;
+
+    // Witness sets
+    d.skip()?;
+
+    let mut metadata = Vec::new();
+    let len = d.map()?;
+    each(&mut d, len, |d| {
+        let index = d.u64()?;
+        if let Some(tom) = decode_auxiliary_data(d)? {
+            metadata.push((index, tom));
+        }
+        Ok(())
+    })?;
+
+    let mut invalid = Vec::new();
+    let has_invalid = match fields {
+        Some(n) => n > 4,
+        None => d.datatype()? != Type::Break,
+    };
+    if has_invalid {
+        let len = d.array()?;
+        each(&mut d, len, |d| {
+            invalid.push(d.u64()?);
+            Ok(())
+        })?;
+    }
+
+    Ok(RawBlock {
+        header,
+        bodies,
+        metadata,
+        invalid,
+    })
+}
+
+/// The parts of a transaction body the follower reads
+#[derive(Default)]
+struct TxBody {
+    hash: String,
+    inputs: Vec<OutputRef>,
+    outputs: Vec<(String, i64)>,
+    collateral: Vec<OutputRef>,
+    collateral_return: Option<(String, i64)>,
+    treasury_donation: i64,
+    /// First slot the transaction is no longer valid in
+    ttl: Option<u64>,
+}
+
+impl TxBody {
+    fn applied(self, tom_metadata: Option<Value>) -> Transaction {
+        Transaction {
+            inputs: self.inputs,
+            outputs: self
+                .outputs
+                .into_iter()
+                .enumerate()
+                .map(|(index, (address, lovelace))| Output {
+                    index: index as i16,
+                    address,
+                    lovelace,
+                })
+                .collect(),
+            treasury_donation: self.treasury_donation,
+            tom_metadata,
+            hash: self.hash,
+        }
+    }
+
+    /// A failed transaction consumes its collateral and creates only its
+    /// collateral return, numbered after the regular outputs
+    fn failed(self) -> Transaction {
+        let index = self.outputs.len() as i16;
+        Transaction {
+            inputs: self.collateral,
+            outputs: self
+                .collateral_return
+                .into_iter()
+                .map(|(address, lovelace)| Output {
+                    index,
+                    address,
+                    lovelace,
+                })
+                .collect(),
+            treasury_donation: 0,
+            tom_metadata: None,
+            hash: self.hash,
+        }
+    }
+}
+
+fn decode_tx_body(d: &mut Decoder) -> Result<TxBody, Error> {
+    let mut body = TxBody::default();
+    let len = d.map()?;
+    each(d, len, |d| {
+        match d.u64()? {
+            0 => body.inputs = decode_inputs(d)?,
+            1 => {
+                let len = d.array()?;
+                each(d, len, |d| {
+                    body.outputs.push(decode_output(d)?);
+                    Ok(())
+                })?;
+            }
+            3 => body.ttl = Some(d.u64()?),
+            13 => body.collateral = decode_inputs(d)?,
+            16 => body.collateral_return = Some(decode_output(d)?),
+            22 => body.treasury_donation = lovelace(d.u64()?)?,
+            _ => d.skip()?,
+        }
+        Ok(())
+    })?;
+    Ok(body)
+}
+
+/// A set of `[tx hash, index]`, tagged 258 from Conway on
+fn decode_inputs(d: &mut Decoder) -> Result<Vec<OutputRef>, Error> {
+    if d.datatype()? == Type::Tag {
+        d.tag()?;
+    }
+    let mut inputs = Vec::new();
+    let len = d.array()?;
+    each(d, len, |d| {
+        d.array()?;
+        let tx_hash = hex::encode(d.bytes()?);
+        let output_index = i16::try_from(d.u64()?).map_err(|_| Error::message("output index out of range"))?;
+        inputs.push(OutputRef { tx_hash, output_index });
+        Ok(())
+    })?;
+    Ok(inputs)
+}
+
+/// Address and lovelace of an output, either `[address, value, ...]` or
+/// `{0: address, 1: value, ...}`
+fn decode_output(d: &mut Decoder) -> Result<(String, i64), Error> {
+    let mut address = None;
+    let mut lovelace = None;
+    match d.datatype()? {
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array()?;
+            let mut position = 0;
+            each(d, len, |d| {
+                match position {
+                    0 => address = Some(encode_address(d.bytes()?)),
+                    1 => lovelace = Some(decode_value(d)?),
+                    _ => d.skip()?,
+                }
+                position += 1;
+                Ok(())
+            })?;
+        }
+        _ => {
+            let len = d.map()?;
+            each(d, len, |d| {
+                match d.u64()? {
+                    0 => address = Some(encode_address(d.bytes()?)),
+                    1 => lovelace = Some(decode_value(d)?),
+                    _ => d.skip()?,
+                }
+                Ok(())
+            })?;
+        }
+    }
+    match (address, lovelace) {
+        (Some(address), Some(lovelace)) => Ok((address, lovelace)),
+        _ => Err(Error::message("output without address or value")),
+    }
+}
+
+/// Lovelace of a value: a coin, or `[coin, multi-asset]`
+fn decode_value(d: &mut Decoder) -> Result<i64, Error> {
+    if matches!(d.datatype()?, Type::Array | Type::ArrayIndef) {
+        let len = d.array()?;
+        let coin = d.u64()?;
+        // Native assets do not count towards TOM amounts
+        d.skip()?;
+        if len.is_none() {
+            end_indefinite(d)?;
+        }
+        lovelace(coin)
+    } else {
+        lovelace(d.u64()?)
+    }
+}
+
+fn lovelace(coin: u64) -> Result<i64, Error> {
+    i64::try_from(coin).map_err(|_| Error::message("lovelace amount out of range"))
+}
+
+/// TOM metadata from auxiliary data: a metadata map (Shelley),
+/// `[metadata, scripts]` (Allegra, Mary) or tag 259 `{0: metadata, ...}`
+fn decode_auxiliary_data(d: &mut Decoder) -> Result<Option<Value>, Error> {
+    match d.datatype()? {
+        Type::Map | Type::MapIndef => decode_metadata(d),
+        Type::Array | Type::ArrayIndef => {
+            let len = d.array()?;
+            let mut tom = None;
+            let mut position = 0;
+            each(d, len, |d| {
+                if position == 0 {
+                    tom = decode_metadata(d)?;
+                } else {
+                    d.skip()?;
+                }
+                position += 1;
+                Ok(())
+            })?;
+            Ok(tom)
+        }
+        Type::Tag => {
+            d.tag()?;
+            let mut tom = None;
+            let len = d.map()?;
+            each(d, len, |d| {
+                if d.u64()? == 0 {
+                    tom = decode_metadata(d)?;
+                } else {
+                    d.skip()?;
+                }
+                Ok(())
+            })?;
+            Ok(tom)
+        }
+        _ => {
+            d.skip()?;
+            Ok(None)
+        }
+    }
+}
+
+/// The metadatum under [`TOM_LABEL`] in a `{label => metadatum}` map
+fn decode_metadata(d: &mut Decoder) -> Result<Option<Value>, Error> {
+    let mut tom = None;
+    let len = d.map()?;
+    each(d, len, |d| {
+        if d.u64()? == TOM_LABEL as u64 {
+            tom = Some(decode_metadatum(d)?);
+        } else {
+            d.skip()?;
+        }
+        Ok(())
+    })?;
+    Ok(tom)
+}
+
+/// A metadatum as JSON, the way indexers present it: byte strings as
+/// `0x`-prefixed hex and map keys as strings
+pub fn decode_metadatum(d: &mut Decoder) -> Result<Value, Error> {
+    Ok(match d.datatype()? {
+        Type::U8 | Type::U16 | Type::U32 | Type::U64 => Value::from(d.u64()?),
+        Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::Int => {
+            let int = i128::from(d.int()?);
+            i64::try_from(int).map_or_else(|_| Value::String(int.to_string()), Value::from)
+        }
+        Type::Bytes | Type::BytesIndef => {
+            let mut bytes = Vec::new();
+            for chunk in d.bytes_iter()? {
+                bytes.extend_from_slice(chunk?);
+            }
+            Value::String(format!("0x{}", hex::encode(bytes)))
+        }
+        Type::String | Type::StringIndef => {
+            let mut text = String::new();
+            for chunk in d.str_iter()? {
+                text.push_str(chunk?);
+            }
+            Value::String(text)
+        }
+        Type::Array | Type::ArrayIndef => {
+            let mut items = Vec::new();
+            let len = d.array()?;
+            each(d, len, |d| {
+                items.push(decode_metadatum(d)?);
+                Ok(())
+            })?;
+            Value::Array(items)
+        }
+        Type::Map | Type::MapIndef => {
+            let mut object = Map::new();
+            let len = d.map()?;
+            each(d, len, |d| {
+                let key = match decode_metadatum(d)? {
+                    Value::String(key) => key,
+                    key => key.to_string(),
+                };
+                object.insert(key, decode_metadatum(d)?);
+                Ok(())
+            })?;
+            Value::Object(object)
+        }
+        other => return Err(Error::type_mismatch(other).with_message("not a metadatum")),
+    })
+}
+
+/// Text form of an address: bech32 for Shelley addresses, base58 for Byron
+pub fn encode_address(bytes: &[u8]) -> String {
+    let Some(header) = bytes.first() else {
+        return String::new();
+    };
+    let mainnet = header & 0x0f == 1;
+    let hrp = match header >> 4 {
+        0..=7 if mainnet => "addr",
+        0..=7 => "addr_test",
+        14 | 15 if mainnet => "stake",
+        14 | 15 => "stake_test",
+        8 => return bs58::encode(bytes).into_string(),
+        _ => return hex::encode(bytes),
+    };
+    bech32::encode(hrp, bytes.to_base32(), Variant::Bech32).unwrap_or_else(|_| hex::encode(bytes))
+}
+
+/// Run `item` for each element of an array, or each entry of a map, whose
+/// header has been read; `len` is `None` for indefinite-length items
+fn each<'b, F>(d: &mut Decoder<'b>, len: Option<u64>, mut item: F) -> Result<(), Error>
+where
+    F: FnMut(&mut Decoder<'b>) -> Result<(), Error>,
+{
+    match len {
+        Some(len) => {
+            for _ in 0..len {
+                item(d)?;
+            }
+        }
+        None => {
+            while d.datatype()? != Type::Break {
+                item(d)?;
+            }
+            end_indefinite(d)?;
+        }
+    }
+    Ok(())
+}
+
+/// Consume the break that ends an indefinite-length item
+fn end_indefinite(d: &mut Decoder) -> Result<(), Error> {
+    if d.datatype()? != Type::Break {
+        return Err(Error::message("expected the end of an indefinite-length item"));
+    }
+    d.set_position(d.position() + 1);
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    //! Blocks are built field by field after each era's CDDL, so every field a
+    //! node sends is present even where the decoder skips it
+    use super::*;
+    use bech32::FromBase32;
+    use minicbor::Encoder;
+    use serde_json::json;
+
+    type Enc<'a> = Encoder<&'a mut Vec<u8>>;
+    type EncResult = Result<(), minicbor::encode::Error<std::convert::Infallible>>;
+
+    /// Payment and stake credentials in a mainnet base address, both scripts
+    pub(crate) const SCRIPT_ADDRESS: [u8; 57] = address(0x31, 0xa1);
+    /// Key payment and stake credentials
+    pub(crate) const KEY_ADDRESS: [u8; 57] = address(0x01, 0xb2);
+
+    const fn address(header: u8, fill: u8) -> [u8; 57] {
+        let mut bytes = [fill; 57];
+        bytes[0] = header;
+        bytes
+    }
+
+    pub(crate) fn encode<F>(build: F) -> Vec<u8>
+    where
+        F: FnOnce(&mut Enc) -> EncResult,
+    {
+        let mut buffer = Vec::new();
+        build(&mut Encoder::new(&mut buffer)).unwrap();
+        buffer
+    }
+
+    /// Append already encoded items
+    fn raw(e: &mut Enc, bytes: &[u8]) {
+        e.writer_mut().extend_from_slice(bytes);
+    }
+
+    /// `[header body, KES signature]` of a Shelley to Mary block
+    fn shelley_header(number: u64, slot: u64) -> Vec<u8> {
+        encode(|e| {
+            e.array(2)?.array(15)?;
+            e.u64(number)?.u64(slot)?.bytes(&[0x11; 32])?;
+            e.bytes(&[0x12; 32])?.bytes(&[0x13; 32])?;
+            e.array(2)?.bytes(&[0x14; 32])?.bytes(&[0x15; 80])?;
+            e.array(2)?.bytes(&[0x16; 32])?.bytes(&[0x17; 80])?;
+            e.u64(1024)?.bytes(&[0x18; 32])?;
+            e.bytes(&[0x19; 32])?.u64(2)?.u64(210)?.bytes(&[0x1a; 64])?;
+            e.u64(2)?.u64(0)?;
+            e.bytes(&[0x1b; 448])?;
+            Ok(())
+        })
+    }
+
+    /// `[header body, KES signature]` of a Babbage or Conway block
+    pub(crate) fn babbage_header(number: u64, slot: u64) -> Vec<u8> {
+        encode(|e| {
+            e.array(2)?.array(10)?;
+            e.u64(number)?.u64(slot)?.bytes(&[0x21; 32])?;
+            e.bytes(&[0x22; 32])?.bytes(&[0x23; 32])?;
+            e.array(2)?.bytes(&[0x24; 32])?.bytes(&[0x25; 80])?;
+            e.u64(2048)?.bytes(&[0x26; 32])?;
+            e.array(4)?.bytes(&[0x27; 32])?.u64(7)?.u64(800)?.bytes(&[0x28; 64])?;
+            e.array(2)?.u64(10)?.u64(0)?;
+            e.bytes(&[0x29; 448])?;
+            Ok(())
+        })
+    }
+
+    /// `[era, [header, [tx body], [witness set], {index => auxiliary data}, invalid?]]`
+    fn block(era: u64, header: &[u8], bodies: &[Vec<u8>], auxiliary: &[(u64, Vec<u8>)], invalid: Option<&[u64]>) -> Vec<u8> {
+        encode(|e| {
+            e.array(2)?.u64(era)?.array(if invalid.is_some() { 5 } else { 4 })?;
+            raw(e, header);
+            e.array(bodies.len() as u64)?;
+            for body in bodies {
+                raw(e, body);
+            }
+            e.array(bodies.len() as u64)?;
+            for _ in bodies {
+                // One vkey witness each
+                e.map(1)?.u8(0)?.array(1)?.array(2)?.bytes(&[0x31; 32])?.bytes(&[0x32; 64])?;
+            }
+            e.map(auxiliary.len() as u64)?;
+            for (index, data) in auxiliary {
+                e.u64(*index)?;
+                raw(e, data);
+            }
+            if let Some(invalid) = invalid {
+                e.array(invalid.len() as u64)?;
+                for index in invalid {
+                    e.u64(*index)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// TOM metadatum of a disbursement, with the map keys indexers see
+    fn encode_tom(e: &mut Enc) -> EncResult {
+        e.map(2)?;
+        e.str("instance")?.bytes(&[0xab; 28])?;
+        e.str("body")?.map(4)?;
+        e.str("event")?.str("disburse")?;
+        e.str("identifier")?.str("EC-0001-25")?;
+        e.str("milestone")?.str("m-0")?;
+        e.str("amount")?.i64(-1)?;
+        Ok(())
+    }
+
+    fn tom_json() -> Value {
+        json!({
+            "instance": format!("0x{}", hex::encode([0xab; 28])),
+            "body": {"event": "disburse", "identifier": "EC-0001-25", "milestone": "m-0", "amount": -1}
+        })
+    }
+
+    /// `{label => metadatum}` with TOM and a CIP-20 message
+    fn encode_metadata(e: &mut Enc) -> EncResult {
+        e.map(2)?.u64(674)?.map(1)?.str("msg")?.array(1)?.str("hello")?;
+        e.u64(TOM_LABEL as u64)?;
+        encode_tom(e)
+    }
+
+    /// A Shelley disbursement paying a vendor, with a TTL
+    fn shelley_body() -> Vec<u8> {
+        encode(|e| {
+            e.map(4)?;
+            e.u8(0)?.array(1)?.array(2)?.bytes(&[0x41; 32])?.u8(1)?;
+            e.u8(1)?.array(2)?;
+            e.array(2)?.bytes(&SCRIPT_ADDRESS)?.u64(40_000_000_000)?;
+            e.array(2)?.bytes(&KEY_ADDRESS)?.u64(10_000_000_000)?;
+            e.u8(2)?.u64(180_000)?;
+            e.u8(3)?.u64(5_000_000)?;
+            Ok(())
+        })
+    }
+
+    /// A Conway disbursement: tagged input set, map-form outputs with native
+    /// assets and an inline datum, and a treasury donation
+    fn conway_body() -> Vec<u8> {
+        encode(|e| {
+            e.map(5)?;
+            e.u8(0)?.tag(minicbor::data::Tag::Unassigned(258))?.array(2)?;
+            e.array(2)?.bytes(&[0x51; 32])?.u8(0)?;
+            e.array(2)?.bytes(&[0x52; 32])?.u16(300)?;
+            e.u8(1)?.array(2)?;
+            e.map(3)?.u8(0)?.bytes(&SCRIPT_ADDRESS)?;
+            e.u8(1)?.array(2)?.u64(90_000_000)?.map(1)?.bytes(&[0x53; 28])?.map(1)?.bytes(b"token")?.u64(1)?;
+            e.u8(2)?.array(2)?.u8(1)?.tag(minicbor::data::Tag::Cbor)?.bytes(&[0xd8, 0x79, 0x80])?;
+            e.array(2)?.bytes(&KEY_ADDRESS)?.u64(25_000_000)?;
+            e.u8(2)?.u64(200_000)?;
+            e.u8(8)?.u64(139_000_000)?;
+            e.u8(22)?.u64(1_000_000)?;
+            Ok(())
+        })
+    }
+
+    /// A Conway transaction whose scripts failed: only its collateral counts
+    fn failed_body() -> Vec<u8> {
+        encode(|e| {
+            e.map(5)?;
+            e.u8(0)?.array(1)?.array(2)?.bytes(&[0x61; 32])?.u8(0)?;
+            e.u8(1)?.array(1)?.array(2)?.bytes(&SCRIPT_ADDRESS)?.u64(5_000_000)?;
+            e.u8(2)?.u64(300_000)?;
+            e.u8(13)?.array(1)?.array(2)?.bytes(&[0x62; 32])?.u8(3)?;
+            e.u8(16)?.array(2)?.bytes(&KEY_ADDRESS)?.u64(4_550_000)?;
+            Ok(())
+        })
+    }
+
+    /// A Conway block holding [`conway_body`] with TOM metadata and
+    /// [`failed_body`]
+    pub(crate) fn conway_block(number: u64, slot: u64) -> Vec<u8> {
+        let auxiliary = encode(|e| {
+            e.tag(minicbor::data::Tag::Unassigned(259))?.map(2)?.u8(0)?;
+            encode_metadata(e)?;
+            e.u8(1)?.array(0)?;
+            Ok(())
+        });
+        block(6, &babbage_header(number, slot), &[conway_body(), failed_body()], &[(0, auxiliary)], Some(&[1]))
+    }
+
+    fn assert_address(address: &str, bytes: &[u8]) {
+        let (hrp, data, _) = bech32::decode(address).unwrap();
+        assert_eq!(hrp, "addr");
+        assert_eq!(Vec::<u8>::from_base32(&data).unwrap(), bytes);
+    }
+
+    #[test]
+    fn blake2b_256_known_answer() {
+        assert_eq!(blake2b_256(b""), "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8");
+    }
+
+    #[test]
+    fn rejects_byron_blocks() {
+        // [era 0, [main block tag 1, [header, body, extra]]]
+        let byron = encode(|e| {
+            e.array(2)?.u8(0)?.array(2)?.u8(1)?.array(3)?;
+            e.array(5)?.u32(764_824_073)?.bytes(&[0x71; 32])?;
+            e.array(4)?.array(2)?.u8(0)?.bytes(&[0x72; 32])?.bytes(&[0x73; 32])?.bytes(&[0x74; 32])?.bytes(&[0x75; 32])?;
+            e.array(4)?.array(2)?.u64(200)?.u64(1000)?.bytes(&[0x76; 64])?.array(1)?.u64(4_490_500)?.array(2)?.u8(0)?.bytes(&[0x77; 64])?;
+            e.array(4)?.array(3)?.u8(0)?.u8(2)?.u8(0)?.array(2)?.str("cardano-sl")?.u8(1)?.map(0)?.bytes(&[0x78; 32])?;
+            e.array(4)?.array(0)?.array(0)?.array(0)?.array(0)?;
+            e.array(1)?.map(0)?;
+            Ok(())
+        });
+        let error = decode_block(&byron, CardanoNetwork::Mainnet).unwrap_err().to_string();
+        assert!(error.contains("Byron"), "{}", error);
+        assert!(decode_header(BYRON_ERA, &byron).is_err());
+    }
+
+    #[test]
+    fn decodes_shelley_block() {
+        let header = shelley_header(4_490_511, 4_492_800);
+        let metadata = encode(encode_metadata);
+        let body = shelley_body();
+        let bytes = block(1, &header, std::slice::from_ref(&body), &[(0, metadata)], None);
+
+        let block = decode_block(&bytes, CardanoNetwork::Mainnet).unwrap();
+        assert_eq!(block.point, Point { slot: 4_492_800, hash: blake2b_256(&header) });
+        assert_eq!(block.number, 4_490_511);
+        assert_eq!(block.block_time, 1_591_566_291 + 4_492_800);
+
+        let [tx] = &block.transactions[..] else { panic!("expected one transaction") };
+        assert_eq!(tx.hash, blake2b_256(&body));
+        assert_eq!(tx.inputs, vec![OutputRef { tx_hash: hex::encode([0x41; 32]), output_index: 1 }]);
+        assert_eq!(tx.outputs.len(), 2);
+        assert!(tx.outputs[0].address.starts_with("addr1x"));
+        assert_address(&tx.outputs[0].address, &SCRIPT_ADDRESS);
+        assert_eq!((tx.outputs[0].index, tx.outputs[0].lovelace), (0, 40_000_000_000));
+        assert_address(&tx.outputs[1].address, &KEY_ADDRESS);
+        assert_eq!((tx.outputs[1].index, tx.outputs[1].lovelace), (1, 10_000_000_000));
+        assert_eq!(tx.treasury_donation, 0);
+        assert_eq!(tx.tom_metadata, Some(tom_json()));
+    }
+
+    #[test]
+    fn decodes_conway_block() {
+        let bytes = conway_block(11_000_000, 140_000_000);
+        let block = decode_block(&bytes, CardanoNetwork::Preprod).unwrap();
+        assert_eq!(block.point, Point { slot: 140_000_000, hash: blake2b_256(&babbage_header(11_000_000, 140_000_000)) });
+        assert_eq!(block.number, 11_000_000);
+        assert_eq!(block.block_time, 1_655_683_200 + 140_000_000);
+
+        let [tx, failed] = &block.transactions[..] else { panic!("expected two transactions") };
+        assert_eq!(tx.hash, blake2b_256(&conway_body()));
+        assert_eq!(
+            tx.inputs,
+            vec![
+                OutputRef { tx_hash: hex::encode([0x51; 32]), output_index: 0 },
+                OutputRef { tx_hash: hex::encode([0x52; 32]), output_index: 300 },
+            ]
+        );
+        assert_address(&tx.outputs[0].address, &SCRIPT_ADDRESS);
+        assert_eq!(tx.outputs[0].lovelace, 90_000_000);
+        assert_address(&tx.outputs[1].address, &KEY_ADDRESS);
+        assert_eq!(tx.outputs[1].lovelace, 25_000_000);
+        assert_eq!(tx.treasury_donation, 1_000_000);
+        assert_eq!(tx.tom_metadata, Some(tom_json()));
+
+        // The failed transaction spends its collateral and creates its
+        // collateral return, numbered after its one regular output
+        assert_eq!(failed.hash, blake2b_256(&failed_body()));
+        assert_eq!(failed.inputs, vec![OutputRef { tx_hash: hex::encode([0x62; 32]), output_index: 3 }]);
+        let [output] = &failed.outputs[..] else { panic!("expected the collateral return") };
+        assert_eq!((output.index, output.lovelace), (1, 4_550_000));
+        assert_eq!(failed.tom_metadata, None);
+    }
+
+    #[test]
+    fn decodes_mary_transaction() {
+        // [body, witness set, auxiliary data as [metadata, scripts]]
+        let body = shelley_body();
+        let bytes = encode(|e| {
+            e.array(3)?;
+            raw(e, &body);
+            e.map(0)?;
+            e.array(2)?;
+            encode_metadata(e)?;
+            e.array(0)?;
+            Ok(())
+        });
+        let (tx, ttl) = decode_transaction(&bytes).unwrap();
+        assert_eq!(tx.hash, blake2b_256(&body));
+        assert_eq!(ttl, Some(5_000_000));
+        assert_eq!(tx.tom_metadata, Some(tom_json()));
+    }
+
+    #[test]
+    fn decodes_conway_transaction_without_metadata() {
+        let body = conway_body();
+        let bytes = encode(|e| {
+            e.array(4)?;
+            raw(e, &body);
+            e.map(0)?.bool(true)?.null()?;
+            Ok(())
+        });
+        let (tx, ttl) = decode_transaction(&bytes).unwrap();
+        assert_eq!(tx.hash, blake2b_256(&body));
+        assert_eq!(ttl, None);
+        assert_eq!(tx.treasury_donation, 1_000_000);
+        assert_eq!(tx.tom_metadata, None);
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        let block = conway_block(11_000_000, 140_000_000);
+        for end in 0..block.len() {
+            assert!(decode_block(&block[..end], CardanoNetwork::Mainnet).is_err(), "block cut at {}", end);
+        }
+        // Only the block number and slot are read from a header; cut before
+        // the end of the slot it is an error
+        let header = babbage_header(11_000_000, 140_000_000);
+        for end in 0..12 {
+            assert!(decode_header(6, &header[..end]).is_err(), "header cut at {}", end);
+        }
+        assert!(decode_header(6, &header[..12]).is_ok());
+        let tx = encode(|e| {
+            e.array(4)?;
+            raw(e, &conway_body());
+            e.map(0)?.bool(true)?.null()?;
+            Ok(())
+        });
+        for end in 0..tx.len() {
+            assert!(decode_transaction(&tx[..end]).is_err(), "transaction cut at {}", end);
+        }
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        assert!(decode_block(&[0xff; 16], CardanoNetwork::Mainnet).is_err());
+        assert!(decode_block(&encode(|e| { e.str("block")?; Ok(()) }), CardanoNetwork::Mainnet).is_err());
+        assert!(decode_transaction(&encode(|e| { e.map(0)?; Ok(()) })).is_err());
+
+        // Transaction bodies that are not maps
+        let bytes = block(6, &babbage_header(1, 2), &[encode(|e| { e.str("body")?; Ok(()) })], &[], Some(&[]));
+        assert!(decode_block(&bytes, CardanoNetwork::Mainnet).is_err());
+
+        // An output without a value
+        let body = encode(|e| {
+            e.map(1)?.u8(1)?.array(1)?.map(1)?.u8(0)?.bytes(&KEY_ADDRESS)?;
+            Ok(())
+        });
+        assert!(decode_block(&block(6, &babbage_header(1, 2), &[body], &[], Some(&[])), CardanoNetwork::Mainnet).is_err());
+
+        // Lovelace and output indexes out of range
+        let body = encode(|e| {
+            e.map(1)?.u8(1)?.array(1)?.array(2)?.bytes(&KEY_ADDRESS)?.u64(u64::MAX)?;
+            Ok(())
+        });
+        assert!(decode_block(&block(6, &babbage_header(1, 2), &[body], &[], Some(&[])), CardanoNetwork::Mainnet).is_err());
+        let body = encode(|e| {
+            e.map(1)?.u8(0)?.array(1)?.array(2)?.bytes(&[0x41; 32])?.u64(70_000)?;
+            Ok(())
+        });
+        assert!(decode_block(&block(6, &babbage_header(1, 2), &[body], &[], Some(&[])), CardanoNetwork::Mainnet).is_err());
+
+        // Metadata holding a float is not a metadatum
+        let auxiliary = encode(|e| {
+            e.map(1)?.u64(TOM_LABEL as u64)?.f64(1.5)?;
+            Ok(())
+        });
+        let bytes = block(6, &babbage_header(1, 2), &[shelley_body()], &[(0, auxiliary)], Some(&[]));
+        assert!(decode_block(&bytes, CardanoNetwork::Mainnet).is_err());
+    }
+}
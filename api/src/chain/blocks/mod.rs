@@ -0,0 +1,106 @@
+//! Raw block sources for the chain follower
+//!
+//! The follower (`services::follower`) reads the chain block by block
+//! through [`BlockSource`], rolling forward and backward as the source
+//! reports, instead of querying an indexer. Two sources exist:
+//!
+//! - [`NodeClient`]: a Cardano node, over node-to-node chain-sync and
+//!   block-fetch
+//! - [`BlockFile`]: a file of blocks and rollbacks, replayed for tests and
+//!   local development
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::OutputRef;
+use crate::config::ChainConfig;
+
+pub mod block_file;
+pub mod cbor;
+pub mod node;
+
+pub use block_file::BlockFile;
+pub use node::NodeClient;
+
+/// A block on the chain, by slot and header hash (hex)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Point {
+    pub slot: i64,
+    pub hash: String,
+}
+
+/// The tip of the chain a source follows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tip {
+    pub point: Point,
+    pub number: i64,
+    /// Unix time of the tip block
+    pub block_time: i64,
+}
+
+/// A block with the transactions the follower may keep
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub point: Point,
+    pub number: i64,
+    /// Unix time of the block
+    pub block_time: i64,
+    pub transactions: Vec<Transaction>,
+}
+
+/// A transaction as it affects the ledger. For a transaction whose scripts
+/// failed, that is its collateral inputs and collateral return output.
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    pub hash: String,
+    pub inputs: Vec<OutputRef>,
+    pub outputs: Vec<Output>,
+    pub treasury_donation: i64,
+    /// Metadata under [`super::TOM_LABEL`]
+    pub tom_metadata: Option<Value>,
+}
+
+/// A transaction output
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub index: i16,
+    pub address: String,
+    pub lovelace: i64,
+}
+
+/// What the chain did next
+#[derive(Debug, Clone)]
+pub enum Step {
+    RollForward(Block),
+    /// Blocks after the point (after genesis for `None`) are no longer on
+    /// the chain
+    RollBackward(Option<Point>),
+}
+
+/// A stream of chain updates
+#[async_trait]
+pub trait BlockSource: Send + Sync {
+    /// What the source reads, for logs
+    fn describe(&self) -> String;
+
+    /// Position the source after the newest of `points` (newest first) it
+    /// has on its chain, or at genesis when `points` is empty. Returns the
+    /// intersection; fails if none of `points` is on the chain.
+    async fn intersect(&mut self, points: &[Point]) -> anyhow::Result<Option<Point>>;
+
+    /// The next update, or `None` when the source has nothing new yet
+    async fn next(&mut self) -> anyhow::Result<Option<Step>>;
+
+    /// The tip of the chain, as last reported
+    fn tip(&self) -> Option<Tip>;
+}
+
+/// Open the block source the configuration names. Validation ensures one of
+/// `chain.node_address` and `chain.block_file` is set.
+pub fn open(config: &ChainConfig) -> anyhow::Result<Box<dyn BlockSource>> {
+    match (&config.node_address, &config.block_file) {
+        (Some(address), _) => Ok(Box::new(NodeClient::new(address, config.network))),
+        (None, Some(path)) => Ok(Box::new(BlockFile::open(path)?)),
+        (None, None) => anyhow::bail!("chain.source = \"follower\" requires chain.node_address or chain.block_file"),
+    }
+}
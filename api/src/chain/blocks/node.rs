@@ -0,0 +1,727 @@
+//! Node-to-node client
+//!
+//! Follows a Cardano node the way a downstream peer does, over one TCP
+//! connection multiplexed between four mini-protocols: handshake, chain-sync
+//! (which announces headers and rollbacks), block-fetch (which serves the
+//! blocks of announced headers) and keep-alive. Headers are collected until
+//! the node reaches its tip or a batch is full, then their blocks are
+//! fetched as one range.
+//!
+//! Each multiplexer segment is an 8-byte header (transmission time in
+//! microseconds, protocol number with the responder bit, payload length)
+//! followed by up to 12288 bytes of payload. Messages are CBOR and may span
+//! segments, so payloads are buffered per protocol until a whole message has
+//! arrived. Segments are read by a separate task, which keeps waiting on the
+//! node cancel-safe.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use minicbor::{Decoder, Encoder};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::{cbor, BlockSource, Point, Step, Tip};
+use crate::config::CardanoNetwork;
+
+const HANDSHAKE: u16 = 0;
+const CHAIN_SYNC: u16 = 2;
+const BLOCK_FETCH: u16 = 3;
+const KEEP_ALIVE: u16 = 8;
+
+/// Node-to-node protocol versions proposed in the handshake. All of them
+/// take `[magic, initiator only, peer sharing, query]`.
+const VERSIONS: std::ops::RangeInclusive<u64> = 11..=14;
+
+/// Largest segment payload
+const MAX_SEGMENT: usize = 12_288;
+
+/// Headers collected before their blocks are fetched
+const FETCH_BATCH: usize = 100;
+
+/// How long the node may take to answer a request
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long `next` waits for a new block once the node is at its tip
+const TIP_WAIT: Duration = Duration::from_secs(1);
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Block source following a Cardano node
+pub struct NodeClient {
+    address: String,
+    network: CardanoNetwork,
+    connection: Option<Connection>,
+    /// Steps read ahead of `next`
+    queue: VecDeque<Step>,
+    tip: Option<Tip>,
+}
+
+impl NodeClient {
+    /// A client for the node at `address` (`host:port`). Nothing is
+    /// connected until [`BlockSource::intersect`].
+    pub fn new(address: &str, network: CardanoNetwork) -> Self {
+        Self {
+            address: address.to_string(),
+            network,
+            connection: None,
+            queue: VecDeque::new(),
+            tip: None,
+        }
+    }
+
+    /// Fetch the blocks of `headers`, which chain-sync announced in order
+    async fn fetch(&mut self, headers: &[Point]) -> anyhow::Result<()> {
+        let (Some(first), Some(last)) = (headers.first(), headers.last()) else {
+            return Ok(());
+        };
+        let connection = self.connection.as_mut().context("not connected")?;
+        connection
+            .send(BLOCK_FETCH, &encode(|e| {
+                e.array(3)?.u8(0)?;
+                encode_point(e, Some(first))?;
+                encode_point(e, Some(last))?;
+                Ok(())
+            }))
+            .await?;
+
+        let reply = connection.receive(BLOCK_FETCH, REPLY_TIMEOUT).await?.context("node did not answer block-fetch")?;
+        match message_tag(&reply)? {
+            2 => {}
+            3 => anyhow::bail!("node no longer has blocks {} to {}", first.slot, last.slot),
+            tag => anyhow::bail!("unexpected block-fetch message {}", tag),
+        }
+
+        let mut expected = headers.iter();
+        loop {
+            let message = connection.receive(BLOCK_FETCH, REPLY_TIMEOUT).await?.context("node stopped sending blocks")?;
+            let mut d = Decoder::new(&message);
+            d.array()?;
+            match d.u64()? {
+                4 => {
+                    d.tag()?;
+                    let block = cbor::decode_block(d.bytes()?, self.network)?;
+                    anyhow::ensure!(
+                        expected.next() == Some(&block.point),
+                        "node sent block {} at slot {}, which chain-sync did not announce",
+                        block.point.hash,
+                        block.point.slot
+                    );
+                    self.queue.push_back(Step::RollForward(block));
+                }
+                5 => break,
+                tag => anyhow::bail!("unexpected block-fetch message {}", tag),
+            }
+        }
+        anyhow::ensure!(expected.next().is_none(), "node sent fewer blocks than announced");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BlockSource for NodeClient {
+    fn describe(&self) -> String {
+        format!("node {}", self.address)
+    }
+
+    async fn intersect(&mut self, points: &[Point]) -> anyhow::Result<Option<Point>> {
+        self.connection = None;
+        self.queue.clear();
+        let mut connection = Connection::open(&self.address, self.network.magic()).await?;
+
+        connection
+            .send(CHAIN_SYNC, &encode(|e| {
+                e.array(2)?.u8(4)?;
+                if points.is_empty() {
+                    e.array(1)?;
+                    encode_point(e, None)?;
+                } else {
+                    e.array(points.len() as u64)?;
+                    for point in points {
+                        encode_point(e, Some(point))?;
+                    }
+                }
+                Ok(())
+            }))
+            .await?;
+        let reply = connection.receive(CHAIN_SYNC, REPLY_TIMEOUT).await?.context("node did not answer chain-sync")?;
+        let mut d = Decoder::new(&reply);
+        d.array()?;
+        let found = match d.u64()? {
+            5 => {
+                let point = decode_point(&mut d)?;
+                self.tip = to_tip(self.network, decode_tip(&mut d)?);
+                point
+            }
+            6 => anyhow::bail!("none of the {} given blocks is on the node's chain", points.len()),
+            tag => anyhow::bail!("unexpected chain-sync message {}", tag),
+        };
+
+        self.connection = Some(connection);
+        Ok(found)
+    }
+
+    async fn next(&mut self) -> anyhow::Result<Option<Step>> {
+        if let Some(step) = self.queue.pop_front() {
+            return Ok(Some(step));
+        }
+        let connection = self.connection.as_mut().context("not connected")?;
+        connection.keep_alive().await?;
+
+        let mut headers = Vec::new();
+        let mut rollback = None;
+        loop {
+            let timeout = if !connection.awaiting {
+                connection.send(CHAIN_SYNC, &encode(|e| {
+                    e.array(1)?.u8(0)?;
+                    Ok(())
+                }))
+                .await?;
+                REPLY_TIMEOUT
+            } else if headers.is_empty() {
+                TIP_WAIT
+            } else {
+                break;
+            };
+
+            let Some(message) = connection.receive(CHAIN_SYNC, timeout).await? else {
+                anyhow::ensure!(connection.awaiting, "node did not answer chain-sync");
+                break;
+            };
+            let mut d = Decoder::new(&message);
+            d.array()?;
+            match d.u64()? {
+                // MsgAwaitReply: the node is at its tip and answers when it
+                // has a new block
+                1 => connection.awaiting = true,
+                // MsgRollForward
+                2 => {
+                    connection.awaiting = false;
+                    d.array()?;
+                    let era = d.u64()?;
+                    anyhow::ensure!(era != 0, "Byron-era headers are not supported; start after the Byron era");
+                    d.tag()?;
+                    let (point, _) = cbor::decode_header(era, d.bytes()?)?;
+                    let tip = decode_tip(&mut d)?;
+                    headers.push(point);
+                    self.tip = to_tip(self.network, tip);
+                    if headers.len() >= FETCH_BATCH {
+                        break;
+                    }
+                }
+                // MsgRollBackward
+                3 => {
+                    connection.awaiting = false;
+                    rollback = Some(decode_point(&mut d)?);
+                    let tip = decode_tip(&mut d)?;
+                    self.tip = to_tip(self.network, tip);
+                    break;
+                }
+                tag => anyhow::bail!("unexpected chain-sync message {}", tag),
+            }
+        }
+
+        self.fetch(&headers).await?;
+        if let Some(point) = rollback {
+            self.queue.push_back(Step::RollBackward(point));
+        }
+        Ok(self.queue.pop_front())
+    }
+
+    fn tip(&self) -> Option<Tip> {
+        self.tip.clone()
+    }
+}
+
+/// A multiplexed connection to a node, past the handshake
+struct Connection {
+    writer: OwnedWriteHalf,
+    segments: mpsc::Receiver<std::io::Result<(u16, Vec<u8>)>>,
+    reader: JoinHandle<()>,
+    /// Payload received per protocol that does not form a whole message yet
+    buffers: HashMap<u16, Vec<u8>>,
+    started: Instant,
+    /// Chain-sync is waiting for the node's next update
+    awaiting: bool,
+    last_keep_alive: Instant,
+    keep_alive_pending: bool,
+    cookie: u16,
+}
+
+impl Connection {
+    async fn open(address: &str, magic: u64) -> anyhow::Result<Self> {
+        let stream = tokio::time::timeout(REPLY_TIMEOUT, TcpStream::connect(address))
+            .await
+            .map_err(|_| anyhow::anyhow!("timed out connecting to node {}", address))?
+            .with_context(|| format!("failed to connect to node {}", address))?;
+        stream.set_nodelay(true)?;
+        let (reader, writer) = stream.into_split();
+        let (sender, segments) = mpsc::channel(64);
+
+        let mut connection = Self {
+            writer,
+            segments,
+            reader: tokio::spawn(read_segments(reader, sender)),
+            buffers: HashMap::new(),
+            started: Instant::now(),
+            awaiting: false,
+            last_keep_alive: Instant::now(),
+            keep_alive_pending: false,
+            cookie: 0,
+        };
+        connection.handshake(magic).await.context("node-to-node handshake failed")?;
+        Ok(connection)
+    }
+
+    async fn handshake(&mut self, magic: u64) -> anyhow::Result<()> {
+        self.send(HANDSHAKE, &encode(|e| {
+            e.array(2)?.u8(0)?.map(VERSIONS.count() as u64)?;
+            for version in VERSIONS {
+                // Initiator only, no peer sharing, not a query
+                e.u64(version)?.array(4)?.u64(magic)?.bool(true)?.u8(0)?.bool(false)?;
+            }
+            Ok(())
+        }))
+        .await?;
+
+        let reply = self.receive(HANDSHAKE, REPLY_TIMEOUT).await?.context("node did not answer")?;
+        let mut d = Decoder::new(&reply);
+        d.array()?;
+        match d.u64()? {
+            // MsgAcceptVersion [1, version, [magic, ...]]
+            1 => {
+                let version = d.u64()?;
+                d.array()?;
+                let accepted = d.u64()?;
+                anyhow::ensure!(
+                    accepted == magic,
+                    "node accepted version {} for network magic {}, not {}",
+                    version,
+                    accepted,
+                    magic
+                );
+                Ok(())
+            }
+            // MsgRefuse [2, reason]
+            2 => anyhow::bail!("node refused versions {}..={} (reason {})", VERSIONS.start(), VERSIONS.end(), hex::encode(&reply)),
+            tag => anyhow::bail!("unexpected handshake message {}", tag),
+        }
+    }
+
+    /// Send a message as the initiator, split into segments
+    async fn send(&mut self, protocol: u16, message: &[u8]) -> anyhow::Result<()> {
+        for chunk in message.chunks(MAX_SEGMENT) {
+            let mut segment = Vec::with_capacity(8 + chunk.len());
+            segment.extend_from_slice(&(self.started.elapsed().as_micros() as u32).to_be_bytes());
+            segment.extend_from_slice(&protocol.to_be_bytes());
+            segment.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+            segment.extend_from_slice(chunk);
+            self.writer.write_all(&segment).await?;
+        }
+        Ok(())
+    }
+
+    /// The next whole message of `protocol`, or `None` if none arrives within
+    /// `timeout`
+    async fn receive(&mut self, protocol: u16, timeout: Duration) -> anyhow::Result<Option<Vec<u8>>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(buffer) = self.buffers.get_mut(&protocol) {
+                if let Some(message) = take_message(buffer)? {
+                    return Ok(Some(message));
+                }
+            }
+            let segment = match tokio::time::timeout_at(deadline, self.segments.recv()).await {
+                Err(_) => return Ok(None),
+                Ok(None) => anyhow::bail!("connection closed"),
+                Ok(Some(segment)) => segment.context("connection to node failed")?,
+            };
+            match segment {
+                // Keep-alive responses only matter as a sign of life
+                (KEEP_ALIVE, _) => self.keep_alive_pending = false,
+                (protocol, payload) => self.buffers.entry(protocol).or_default().extend_from_slice(&payload),
+            }
+        }
+    }
+
+    /// Send a keep-alive when one is due and the last one was answered
+    async fn keep_alive(&mut self) -> anyhow::Result<()> {
+        if self.keep_alive_pending || self.last_keep_alive.elapsed() < KEEP_ALIVE_INTERVAL {
+            return Ok(());
+        }
+        self.cookie = self.cookie.wrapping_add(1);
+        let cookie = self.cookie;
+        self.send(KEEP_ALIVE, &encode(|e| {
+            e.array(2)?.u8(0)?.u16(cookie)?;
+            Ok(())
+        }))
+        .await?;
+        self.keep_alive_pending = true;
+        self.last_keep_alive = Instant::now();
+        Ok(())
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Forward segments from the node until the connection fails
+async fn read_segments(mut reader: OwnedReadHalf, sender: mpsc::Sender<std::io::Result<(u16, Vec<u8>)>>) {
+    loop {
+        let segment = async {
+            let mut header = [0u8; 8];
+            reader.read_exact(&mut header).await?;
+            // The high bit marks segments sent by the responder
+            let protocol = u16::from_be_bytes([header[4], header[5]]) & 0x7fff;
+            let mut payload = vec![0u8; usize::from(u16::from_be_bytes([header[6], header[7]]))];
+            reader.read_exact(&mut payload).await?;
+            Ok((protocol, payload))
+        }
+        .await;
+        let failed = segment.is_err();
+        if sender.send(segment).await.is_err() || failed {
+            return;
+        }
+    }
+}
+
+/// Split the first whole CBOR item off `buffer`
+fn take_message(buffer: &mut Vec<u8>) -> anyhow::Result<Option<Vec<u8>>> {
+    if buffer.is_empty() {
+        return Ok(None);
+    }
+    let mut d = Decoder::new(buffer);
+    match d.skip() {
+        Ok(()) => {
+            let end = d.position();
+            let rest = buffer.split_off(end);
+            Ok(Some(std::mem::replace(buffer, rest)))
+        }
+        Err(e) if e.is_end_of_input() => Ok(None),
+        Err(e) => Err(anyhow::anyhow!("invalid message from node: {}", e)),
+    }
+}
+
+/// Tag of a `[tag, ...]` message
+fn message_tag(message: &[u8]) -> anyhow::Result<u64> {
+    let mut d = Decoder::new(message);
+    d.array()?;
+    Ok(d.u64()?)
+}
+
+/// `[]` for genesis or `[slot, hash]`
+fn encode_point(e: &mut Encoder<&mut Vec<u8>>, point: Option<&Point>) -> Result<(), minicbor::encode::Error<std::convert::Infallible>> {
+    match point {
+        None => {
+            e.array(0)?;
+        }
+        Some(point) => {
+            e.array(2)?.u64(point.slot as u64)?.bytes(&hex::decode(&point.hash).unwrap_or_default())?;
+        }
+    }
+    Ok(())
+}
+
+fn decode_point(d: &mut Decoder) -> anyhow::Result<Option<Point>> {
+    match d.array()? {
+        Some(0) => Ok(None),
+        Some(2) => {
+            let slot = d.u64()? as i64;
+            let hash = hex::encode(d.bytes()?);
+            Ok(Some(Point { slot, hash }))
+        }
+        _ => anyhow::bail!("invalid point"),
+    }
+}
+
+/// `[point, block number]`; `None` while the node's chain is empty
+fn decode_tip(d: &mut Decoder) -> anyhow::Result<Option<(Point, i64)>> {
+    d.array()?;
+    let point = decode_point(d)?;
+    let number = d.u64()? as i64;
+    Ok(point.map(|p| (p, number)))
+}
+
+fn to_tip(network: CardanoNetwork, tip: Option<(Point, i64)>) -> Option<Tip> {
+    tip.map(|(point, number)| Tip {
+        block_time: network.slot_time(point.slot),
+        point,
+        number,
+    })
+}
+
+/// Encode a message into a buffer
+fn encode<F>(build: F) -> Vec<u8>
+where
+    F: FnOnce(&mut Encoder<&mut Vec<u8>>) -> Result<(), minicbor::encode::Error<std::convert::Infallible>>,
+{
+    let mut buffer = Vec::new();
+    build(&mut Encoder::new(&mut buffer)).expect("encoding into a Vec cannot fail");
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::blocks::cbor::tests::{babbage_header, conway_block};
+    use minicbor::data::Tag;
+    use tokio::net::TcpListener;
+
+    const NETWORK: CardanoNetwork = CardanoNetwork::Preview;
+
+    /// The node side of a connection
+    struct FakeNode {
+        stream: TcpStream,
+    }
+
+    impl FakeNode {
+        /// The next message from the client, which fits one segment
+        async fn receive(&mut self) -> (u16, Vec<u8>) {
+            let mut header = [0u8; 8];
+            self.stream.read_exact(&mut header).await.unwrap();
+            let protocol = u16::from_be_bytes([header[4], header[5]]);
+            assert_eq!(protocol & 0x8000, 0, "client set the responder bit");
+            let mut payload = vec![0u8; usize::from(u16::from_be_bytes([header[6], header[7]]))];
+            self.stream.read_exact(&mut payload).await.unwrap();
+            (protocol, payload)
+        }
+
+        /// Send a message in segments of at most `segment` bytes
+        async fn send(&mut self, protocol: u16, message: &[u8], segment: usize) {
+            for chunk in message.chunks(segment) {
+                let mut bytes = vec![0, 0, 0, 0];
+                bytes.extend_from_slice(&(protocol | 0x8000).to_be_bytes());
+                bytes.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+                bytes.extend_from_slice(chunk);
+                self.stream.write_all(&bytes).await.unwrap();
+            }
+        }
+
+        /// Check the client's version proposal and answer it
+        async fn handshake(&mut self, reply: &[u8]) {
+            let (protocol, proposal) = self.receive().await;
+            assert_eq!(protocol, HANDSHAKE);
+            let mut d = Decoder::new(&proposal);
+            assert_eq!(d.array().unwrap(), Some(2));
+            assert_eq!(d.u64().unwrap(), 0);
+            assert_eq!(d.map().unwrap(), Some(4));
+            for version in VERSIONS {
+                assert_eq!(d.u64().unwrap(), version);
+                assert_eq!(d.array().unwrap(), Some(4));
+                assert_eq!(d.u64().unwrap(), NETWORK.magic());
+                assert!(d.bool().unwrap());
+                d.skip().unwrap();
+                d.skip().unwrap();
+            }
+            self.send(HANDSHAKE, reply, MAX_SEGMENT).await;
+        }
+
+        /// The next message from the client, which must be for `expected`
+        async fn request(&mut self, expected: u16) -> Vec<u8> {
+            let (protocol, message) = self.receive().await;
+            assert_eq!(protocol, expected);
+            message
+        }
+    }
+
+    async fn listen() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        (listener, address)
+    }
+
+    fn accept_version() -> Vec<u8> {
+        encode(|e| {
+            e.array(3)?.u8(1)?.u64(14)?.array(4)?.u64(NETWORK.magic())?.bool(false)?.u8(0)?.bool(false)?;
+            Ok(())
+        })
+    }
+
+    fn point(slot: i64, fill: u8) -> Point {
+        Point { slot, hash: hex::encode([fill; 32]) }
+    }
+
+    fn encode_tip(e: &mut Encoder<&mut Vec<u8>>, tip: &Point, number: u64) -> Result<(), minicbor::encode::Error<std::convert::Infallible>> {
+        e.array(2)?;
+        encode_point(e, Some(tip))?;
+        e.u64(number)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn follows_roll_forward_and_rollback() {
+        let (listener, address) = listen().await;
+        let start = point(1_000, 0x01);
+        let tip = point(3_000, 0x03);
+        let header = babbage_header(70, 2_000);
+        let block = conway_block(70, 2_000);
+        let announced = cbor::decode_header(6, &header).unwrap().0;
+
+        let node = {
+            let (start, tip, announced) = (start.clone(), tip.clone(), announced.clone());
+            tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut node = FakeNode { stream };
+                node.handshake(&accept_version()).await;
+
+                // MsgFindIntersect [4, [point]] -> MsgIntersectFound
+                let find = node.request(CHAIN_SYNC).await;
+                let mut d = Decoder::new(&find);
+                d.array().unwrap();
+                assert_eq!(d.u64().unwrap(), 4);
+                assert_eq!(d.array().unwrap(), Some(1));
+                assert_eq!(decode_point(&mut d).unwrap(), Some(start.clone()));
+                node.send(CHAIN_SYNC, &encode(|e| {
+                    e.array(3)?.u8(5)?;
+                    encode_point(e, Some(&start))?;
+                    encode_tip(e, &tip, 80)
+                }), MAX_SEGMENT).await;
+
+                // MsgRequestNext -> MsgRollForward with the header
+                assert_eq!(node.request(CHAIN_SYNC).await, [0x81, 0x00]);
+                node.send(CHAIN_SYNC, &encode(|e| {
+                    e.array(3)?.u8(2)?.array(2)?.u8(6)?.tag(Tag::Cbor)?.bytes(&header)?;
+                    encode_tip(e, &tip, 80)
+                }), MAX_SEGMENT).await;
+
+                // MsgRequestNext -> MsgRollBackward to the start
+                assert_eq!(node.request(CHAIN_SYNC).await, [0x81, 0x00]);
+                node.send(CHAIN_SYNC, &encode(|e| {
+                    e.array(3)?.u8(3)?;
+                    encode_point(e, Some(&start))?;
+                    encode_tip(e, &start, 69)
+                }), MAX_SEGMENT).await;
+
+                // MsgRequestRange for the announced header -> the block, split
+                // over several segments
+                let range = node.request(BLOCK_FETCH).await;
+                let mut d = Decoder::new(&range);
+                d.array().unwrap();
+                assert_eq!(d.u64().unwrap(), 0);
+                assert_eq!(decode_point(&mut d).unwrap(), Some(announced.clone()));
+                assert_eq!(decode_point(&mut d).unwrap(), Some(announced));
+                node.send(BLOCK_FETCH, &encode(|e| { e.array(1)?.u8(2)?; Ok(()) }), MAX_SEGMENT).await;
+                node.send(BLOCK_FETCH, &encode(|e| {
+                    e.array(2)?.u8(4)?.tag(Tag::Cbor)?.bytes(&block)?;
+                    Ok(())
+                }), 100).await;
+                node.send(BLOCK_FETCH, &encode(|e| { e.array(1)?.u8(5)?; Ok(()) }), MAX_SEGMENT).await;
+                node
+            })
+        };
+
+        let mut client = NodeClient::new(&address, NETWORK);
+        assert_eq!(client.intersect(std::slice::from_ref(&start)).await.unwrap(), Some(start.clone()));
+        assert_eq!(client.tip(), Some(Tip { point: tip.clone(), number: 80, block_time: NETWORK.slot_time(3_000) }));
+
+        match client.next().await.unwrap() {
+            Some(Step::RollForward(block)) => {
+                assert_eq!(block.point, announced);
+                assert_eq!(block.number, 70);
+                assert_eq!(block.transactions.len(), 2);
+            }
+            other => panic!("expected the announced block, got {:?}", other),
+        }
+        match client.next().await.unwrap() {
+            Some(Step::RollBackward(point)) => assert_eq!(point, Some(start.clone())),
+            other => panic!("expected a rollback, got {:?}", other),
+        }
+        assert_eq!(client.tip().map(|t| t.point), Some(start));
+        node.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn refused_handshake_is_an_error() {
+        let (listener, address) = listen().await;
+        let node = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut node = FakeNode { stream };
+            // MsgRefuse [2, VersionMismatch [0, [versions]]]
+            node.handshake(&encode(|e| {
+                e.array(2)?.u8(2)?.array(2)?.u8(0)?.array(1)?.u8(7)?;
+                Ok(())
+            }))
+            .await;
+        });
+
+        let error = NodeClient::new(&address, NETWORK).intersect(&[]).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("refused"), "{:#}", error);
+        node.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn other_network_is_an_error() {
+        let (listener, address) = listen().await;
+        let node = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut node = FakeNode { stream };
+            node.handshake(&encode(|e| {
+                e.array(3)?.u8(1)?.u64(14)?.array(4)?.u64(CardanoNetwork::Mainnet.magic())?.bool(false)?.u8(0)?.bool(false)?;
+                Ok(())
+            }))
+            .await;
+        });
+
+        let error = NodeClient::new(&address, NETWORK).intersect(&[]).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("network magic"), "{:#}", error);
+        node.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn intersect_not_found_is_an_error() {
+        let (listener, address) = listen().await;
+        let node = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut node = FakeNode { stream };
+            node.handshake(&accept_version()).await;
+            let find = node.request(CHAIN_SYNC).await;
+            // Genesis is sent as the empty point
+            assert_eq!(find, [0x82, 0x04, 0x81, 0x80]);
+            node.send(CHAIN_SYNC, &encode(|e| {
+                e.array(2)?.u8(6)?;
+                encode_tip(e, &point(5, 0x05), 1)
+            }), MAX_SEGMENT).await;
+        });
+
+        assert!(NodeClient::new(&address, NETWORK).intersect(&[]).await.is_err());
+        node.await.unwrap();
+    }
+
+    #[test]
+    fn take_message_waits_for_whole_messages() {
+        let message = encode(|e| {
+            e.array(2)?.u8(4)?.bytes(&[0x42; 40])?;
+            Ok(())
+        });
+        let mut buffer = message[..20].to_vec();
+        assert_eq!(take_message(&mut buffer).unwrap(), None);
+        buffer.extend_from_slice(&message[20..]);
+        buffer.extend_from_slice(&[0x81, 0x05]);
+        assert_eq!(take_message(&mut buffer).unwrap(), Some(message));
+        assert_eq!(take_message(&mut buffer).unwrap(), Some(vec![0x81, 0x05]));
+        assert_eq!(take_message(&mut buffer).unwrap(), None);
+
+        // Additional information 28 is reserved
+        let mut garbage = vec![0x1c, 0x00];
+        assert!(take_message(&mut garbage).is_err());
+    }
+
+    #[test]
+    fn decodes_points_and_tips() {
+        let bytes = encode(|e| encode_tip(e, &point(42, 0x2a), 7));
+        let (tip, number) = decode_tip(&mut Decoder::new(&bytes)).unwrap().unwrap();
+        assert_eq!((tip, number), (point(42, 0x2a), 7));
+
+        // An empty chain's tip is [[], 0]
+        assert_eq!(decode_tip(&mut Decoder::new(&[0x82, 0x80, 0x00])).unwrap(), None);
+        assert!(decode_point(&mut Decoder::new(&[0x81, 0x01])).is_err());
+        assert!(decode_point(&mut Decoder::new(&[0x82, 0x01])).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+//! cardano-db-sync chain source
+//!
+//! Reads `block`, `tx`, `tx_metadata`, `tx_out` and `tx_in` from a db-sync
+//! database, through a pool whose search path is `chain.db_sync_schema`.
+//! db-sync stores hashes as `bytea`; they are exchanged as lowercase hex, the
+//! form YACI Store uses. TOM accounting is in lovelace, so native assets
+//! (`ma_tx_out`) on an output are ignored and only `tx_out.value` counts.
+//!
+//! Unlike YACI Store, db-sync indexes every output, so the queries rely on
+//! db-sync's own indexes (on `tx.hash`, `tx_out.address`, `block.slot_no`
+//! and the foreign keys).
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{BlockInfo, ChainError, ChainSource, OutputRef, RawTomEvent, Spend, TxInput, TxOutput};
+
+/// db-sync tables the source reads
+const TABLES: &[&str] = &["block", "tx", "tx_metadata", "tx_out", "tx_in"];
+
+/// Chain source backed by a cardano-db-sync schema
+#[derive(Clone)]
+pub struct DbSync {
+    pool: PgPool,
+}
+
+impl DbSync {
+    /// `pool` must resolve unqualified names to the db-sync schema (see
+    /// `db::connect_db_sync`)
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Raw bytes of a hex transaction hash; `None` if it is not hex, so it
+/// cannot be in db-sync
+fn hash_bytes(tx_hash: &str) -> Option<Vec<u8>> {
+    hex::decode(tx_hash).ok()
+}
+
+#[async_trait]
+impl ChainSource for DbSync {
+    fn name(&self) -> &'static str {
+        "db_sync"
+    }
+
+    async fn check(&self) -> Result<String, ChainError> {
+        let missing: Vec<String> = sqlx::query_scalar(
+            "SELECT t FROM UNNEST($1::text[]) AS t WHERE to_regclass(quote_ident(t)) IS NULL"
+        )
+        .bind(TABLES)
+        .fetch_all(&self.pool)
+        .await?;
+        if !missing.is_empty() {
+            return Err(ChainError::Unavailable(format!("missing db-sync tables: {}", missing.join(", "))));
+        }
+        let schema: String = sqlx::query_scalar("SELECT current_schema()")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(format!("tables present in schema {}", schema))
+    }
+
+    async fn tip(&self) -> Result<Option<BlockInfo>, ChainError> {
+        let tip = sqlx::query_as::<_, BlockInfo>(
+            r#"
+            SELECT block_no::bigint AS number, slot_no::bigint AS slot, EXTRACT(EPOCH FROM time)::bigint AS block_time
+            FROM block
+            WHERE slot_no IS NOT NULL AND block_no IS NOT NULL
+            ORDER BY slot_no DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(tip)
+    }
+
+    async fn block_at_or_before(&self, slot: i64) -> Result<Option<BlockInfo>, ChainError> {
+        let block = sqlx::query_as::<_, BlockInfo>(
+            r#"
+            SELECT block_no::bigint AS number, slot_no::bigint AS slot, EXTRACT(EPOCH FROM time)::bigint AS block_time
+            FROM block
+            WHERE slot_no <= $1 AND block_no IS NOT NULL
+            ORDER BY slot_no DESC
+            LIMIT 1
+            "#
+        )
+        .bind(slot)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(block)
+    }
+
+    async fn metadata_since(&self, label: i64, after_slot: i64, limit: Option<i64>)
+        -> Result<Vec<RawTomEvent>, ChainError>
+    {
+        let events = sqlx::query_as::<_, RawTomEvent>(
+            r#"
+            SELECT
+                encode(tx.hash, 'hex') AS tx_hash,
+                b.slot_no::bigint AS slot,
+                m.json AS body,
+                b.block_no::bigint AS block_number,
+                EXTRACT(EPOCH FROM b.time)::bigint AS block_time
+            FROM tx_metadata m
+            JOIN tx ON tx.id = m.tx_id
+            JOIN block b ON b.id = tx.block_id
+            WHERE m.key = $1::numeric AND b.slot_no > $2
+            ORDER BY b.slot_no, tx.block_index
+            LIMIT $3
+            "#
+        )
+        .bind(label)
+        .bind(after_slot)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(events)
+    }
+
+    async fn tx_metadata(&self, label: i64, tx_hash: &str) -> Result<Option<RawTomEvent>, ChainError> {
+        let Some(hash) = hash_bytes(tx_hash) else {
+            return Ok(None);
+        };
+        let event = sqlx::query_as::<_, RawTomEvent>(
+            r#"
+            SELECT
+                encode(tx.hash, 'hex') AS tx_hash,
+                b.slot_no::bigint AS slot,
+                m.json AS body,
+                b.block_no::bigint AS block_number,
+                EXTRACT(EPOCH FROM b.time)::bigint AS block_time
+            FROM tx_metadata m
+            JOIN tx ON tx.id = m.tx_id
+            JOIN block b ON b.id = tx.block_id
+            WHERE m.key = $1::numeric AND tx.hash = $2
+            "#
+        )
+        .bind(label)
+        .bind(hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(event)
+    }
+
+    async fn tx_outputs(&self, tx_hash: &str) -> Result<Vec<TxOutput>, ChainError> {
+        let Some(hash) = hash_bytes(tx_hash) else {
+            return Ok(Vec::new());
+        };
+        let outputs = sqlx::query_as::<_, TxOutput>(
+            r#"
+            SELECT encode(tx.hash, 'hex') AS tx_hash, o.index::smallint AS output_index, o.address,
+                   o.value::bigint AS lovelace, b.slot_no::bigint AS slot, b.block_no::bigint AS block_number
+            FROM tx
+            JOIN tx_out o ON o.tx_id = tx.id
+            JOIN block b ON b.id = tx.block_id
+            WHERE tx.hash = $1
+            ORDER BY o.index
+            "#
+        )
+        .bind(hash)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(outputs)
+    }
+
+    async fn tx_output_refs(&self, tx_hash: &str) -> Result<Vec<OutputRef>, ChainError> {
+        // db-sync has the address of every output
+        let refs = self
+            .tx_outputs(tx_hash)
+            .await?
+            .into_iter()
+            .map(|o| OutputRef {
+                tx_hash: o.tx_hash,
+                output_index: o.output_index,
+            })
+            .collect();
+        Ok(refs)
+    }
+
+    async fn tx_inputs(&self, tx_hash: &str) -> Result<Vec<TxInput>, ChainError> {
+        let Some(hash) = hash_bytes(tx_hash) else {
+            return Ok(Vec::new());
+        };
+        let inputs = sqlx::query_as::<_, TxInput>(
+            r#"
+            SELECT encode(src.hash, 'hex') AS tx_hash, i.tx_out_index::smallint AS output_index,
+                   o.address, b.slot_no::bigint AS spent_slot
+            FROM tx
+            JOIN block b ON b.id = tx.block_id
+            JOIN tx_in i ON i.tx_in_id = tx.id
+            JOIN tx src ON src.id = i.tx_out_id
+            LEFT JOIN tx_out o ON o.tx_id = i.tx_out_id AND o.index = i.tx_out_index
+            WHERE tx.hash = $1
+            ORDER BY 1, 2
+            "#
+        )
+        .bind(hash)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(inputs)
+    }
+
+    async fn treasury_donation(&self, tx_hash: &str) -> Result<i64, ChainError> {
+        let Some(hash) = hash_bytes(tx_hash) else {
+            return Ok(0);
+        };
+        let donation: Option<i64> = sqlx::query_scalar(
+            "SELECT treasury_donation::bigint FROM tx WHERE hash = $1"
+        )
+        .bind(hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+        Ok(donation.unwrap_or(0))
+    }
+
+    async fn address_utxos(&self, address: &str) -> Result<Vec<TxOutput>, ChainError> {
+        let utxos = sqlx::query_as::<_, TxOutput>(
+            r#"
+            SELECT encode(tx.hash, 'hex') AS tx_hash, o.index::smallint AS output_index, o.address,
+                   o.value::bigint AS lovelace, b.slot_no::bigint AS slot, b.block_no::bigint AS block_number
+            FROM tx_out o
+            JOIN tx ON tx.id = o.tx_id
+            JOIN block b ON b.id = tx.block_id
+            WHERE o.address = $1
+            "#
+        )
+        .bind(address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(utxos)
+    }
+
+    async fn spends(&self, outputs: &[OutputRef]) -> Result<Vec<Spend>, ChainError> {
+        let (hashes, indexes): (Vec<Vec<u8>>, Vec<i16>) = outputs
+            .iter()
+            .filter_map(|o| Some((hash_bytes(&o.tx_hash)?, o.output_index)))
+            .unzip();
+        let spends = sqlx::query_as::<_, Spend>(
+            r#"
+            SELECT encode(src.hash, 'hex') AS tx_hash, i.tx_out_index::smallint AS output_index,
+                   encode(spender.hash, 'hex') AS spent_tx_hash, b.slot_no::bigint AS spent_slot
+            FROM UNNEST($1::bytea[], $2::smallint[]) AS o(hash, output_index)
+            JOIN tx src ON src.hash = o.hash
+            JOIN tx_in i ON i.tx_out_id = src.id AND i.tx_out_index = o.output_index
+            JOIN tx spender ON spender.id = i.tx_in_id
+            JOIN block b ON b.id = spender.block_id
+            "#
+        )
+        .bind(&hashes)
+        .bind(&indexes)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(spends)
+    }
+}
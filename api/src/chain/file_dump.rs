@@ -0,0 +1,330 @@
+//! File dump chain source
+//!
+//! Serves chain data from a JSON file holding blocks and their
+//! transactions, read into memory once. Tests and local development use it
+//! to run the event processor without an indexer:
+//!
+//! ```json
+//! {
+//!   "blocks": [
+//!     {
+//!       "number": 1001,
+//!       "slot": 1000,
+//!       "block_time": 1700001000,
+//!       "transactions": [
+//!         {
+//!           "hash": "tx_fund",
+//!           "inputs": [{ "tx_hash": "tx_seed", "output_index": 0 }],
+//!           "outputs": [{ "address": "addr1x...", "lovelace": 1000000000 }],
+//!           "treasury_donation": 0,
+//!           "metadata": { "1694": { "body": { "event": "fund" } } }
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Outputs are indexed by their position. Inputs may spend outputs that are
+//! not in the dump; their address is then unknown.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::{BlockInfo, ChainError, ChainSource, OutputRef, RawTomEvent, Spend, TxInput, TxOutput};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Dump {
+    blocks: Vec<DumpBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct DumpBlock {
+    pub(super) number: i64,
+    pub(super) slot: i64,
+    pub(super) block_time: i64,
+    #[serde(default)]
+    pub(super) hash: Option<String>,
+    #[serde(default)]
+    pub(super) transactions: Vec<DumpTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct DumpTransaction {
+    pub(super) hash: String,
+    #[serde(default)]
+    pub(super) inputs: Vec<DumpInput>,
+    #[serde(default)]
+    pub(super) outputs: Vec<DumpOutput>,
+    #[serde(default)]
+    pub(super) treasury_donation: i64,
+    /// Metadata by label
+    #[serde(default)]
+    pub(super) metadata: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct DumpInput {
+    pub(super) tx_hash: String,
+    pub(super) output_index: i16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct DumpOutput {
+    pub(super) address: String,
+    pub(super) lovelace: i64,
+}
+
+/// A transaction with the block it is in
+#[derive(Debug)]
+struct Transaction {
+    block: BlockInfo,
+    inputs: Vec<OutputRef>,
+    outputs: Vec<TxOutput>,
+    treasury_donation: i64,
+    metadata: BTreeMap<String, Value>,
+}
+
+/// Chain source reading a JSON dump of blocks
+#[derive(Debug)]
+pub struct FileDump {
+    path: PathBuf,
+    /// Ordered by slot
+    blocks: Vec<BlockInfo>,
+    /// Transaction hashes in chain order
+    order: Vec<String>,
+    transactions: HashMap<String, Transaction>,
+    outputs_by_address: HashMap<String, Vec<OutputRef>>,
+    spent_by: HashMap<OutputRef, (String, i64)>,
+}
+
+impl FileDump {
+    /// Read and index a dump file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read chain dump {}", path.display()))?;
+        let dump: Dump = serde_json::from_str(&contents)
+            .with_context(|| format!("invalid chain dump {}", path.display()))?;
+        Self::from_dump(path, dump).with_context(|| format!("invalid chain dump {}", path.display()))
+    }
+
+    fn from_dump(path: &Path, mut dump: Dump) -> anyhow::Result<Self> {
+        dump.blocks.sort_by_key(|b| b.slot);
+
+        let mut source = Self {
+            path: path.to_path_buf(),
+            blocks: Vec::with_capacity(dump.blocks.len()),
+            order: Vec::new(),
+            transactions: HashMap::new(),
+            outputs_by_address: HashMap::new(),
+            spent_by: HashMap::new(),
+        };
+
+        for block in dump.blocks {
+            if source.blocks.last().is_some_and(|b| b.slot == block.slot) {
+                anyhow::bail!("two blocks at slot {} ({})", block.slot, block.hash.unwrap_or_default());
+            }
+            let info = BlockInfo {
+                number: block.number,
+                slot: block.slot,
+                block_time: block.block_time,
+            };
+            source.blocks.push(info);
+
+            for tx in block.transactions {
+                if source.transactions.contains_key(&tx.hash) {
+                    anyhow::bail!("transaction {} appears twice", tx.hash);
+                }
+
+                let inputs: Vec<OutputRef> = tx
+                    .inputs
+                    .into_iter()
+                    .map(|i| OutputRef {
+                        tx_hash: i.tx_hash,
+                        output_index: i.output_index,
+                    })
+                    .collect();
+                for input in &inputs {
+                    source.spent_by.insert(input.clone(), (tx.hash.clone(), info.slot));
+                }
+
+                let outputs: Vec<TxOutput> = tx
+                    .outputs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, o)| TxOutput {
+                        tx_hash: tx.hash.clone(),
+                        output_index: index as i16,
+                        address: o.address,
+                        lovelace: o.lovelace,
+                        slot: Some(info.slot),
+                        block_number: Some(info.number),
+                    })
+                    .collect();
+                for output in &outputs {
+                    source
+                        .outputs_by_address
+                        .entry(output.address.clone())
+                        .or_default()
+                        .push(OutputRef {
+                            tx_hash: output.tx_hash.clone(),
+                            output_index: output.output_index,
+                        });
+                }
+
+                source.order.push(tx.hash.clone());
+                source.transactions.insert(
+                    tx.hash,
+                    Transaction {
+                        block: info,
+                        inputs,
+                        outputs,
+                        treasury_donation: tx.treasury_donation,
+                        metadata: tx.metadata,
+                    },
+                );
+            }
+        }
+
+        Ok(source)
+    }
+
+    fn output(&self, output: &OutputRef) -> Option<&TxOutput> {
+        self.transactions
+            .get(&output.tx_hash)?
+            .outputs
+            .get(usize::try_from(output.output_index).ok()?)
+    }
+
+    fn event(&self, label: i64, tx_hash: &str, tx: &Transaction) -> Option<RawTomEvent> {
+        let body = tx.metadata.get(&label.to_string())?;
+        Some(RawTomEvent {
+            tx_hash: tx_hash.to_string(),
+            slot: Some(tx.block.slot),
+            body: Some(body.clone()),
+            block_number: Some(tx.block.number),
+            block_time: Some(tx.block.block_time),
+        })
+    }
+}
+
+#[async_trait]
+impl ChainSource for FileDump {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    async fn check(&self) -> Result<String, ChainError> {
+        Ok(format!(
+            "{} blocks, {} transactions from {}",
+            self.blocks.len(),
+            self.transactions.len(),
+            self.path.display()
+        ))
+    }
+
+    async fn tip(&self) -> Result<Option<BlockInfo>, ChainError> {
+        Ok(self.blocks.last().copied())
+    }
+
+    async fn block_at_or_before(&self, slot: i64) -> Result<Option<BlockInfo>, ChainError> {
+        let after = self.blocks.partition_point(|b| b.slot <= slot);
+        Ok(after.checked_sub(1).map(|i| self.blocks[i]))
+    }
+
+    async fn metadata_since(&self, label: i64, after_slot: i64, limit: Option<i64>)
+        -> Result<Vec<RawTomEvent>, ChainError>
+    {
+        let limit = limit.map_or(usize::MAX, |l| usize::try_from(l).unwrap_or(0));
+        let events = self
+            .order
+            .iter()
+            .filter_map(|hash| {
+                let tx = &self.transactions[hash];
+                (tx.block.slot > after_slot).then(|| self.event(label, hash, tx)).flatten()
+            })
+            .take(limit)
+            .collect();
+        Ok(events)
+    }
+
+    async fn tx_metadata(&self, label: i64, tx_hash: &str) -> Result<Option<RawTomEvent>, ChainError> {
+        Ok(self.transactions.get(tx_hash).and_then(|tx| self.event(label, tx_hash, tx)))
+    }
+
+    async fn tx_outputs(&self, tx_hash: &str) -> Result<Vec<TxOutput>, ChainError> {
+        Ok(self.transactions.get(tx_hash).map(|tx| tx.outputs.clone()).unwrap_or_default())
+    }
+
+    async fn tx_output_refs(&self, tx_hash: &str) -> Result<Vec<OutputRef>, ChainError> {
+        let refs = self
+            .transactions
+            .get(tx_hash)
+            .into_iter()
+            .flat_map(|tx| &tx.outputs)
+            .map(|o| OutputRef {
+                tx_hash: o.tx_hash.clone(),
+                output_index: o.output_index,
+            })
+            .collect();
+        Ok(refs)
+    }
+
+    async fn tx_inputs(&self, tx_hash: &str) -> Result<Vec<TxInput>, ChainError> {
+        let Some(tx) = self.transactions.get(tx_hash) else {
+            return Ok(Vec::new());
+        };
+        let mut inputs: Vec<TxInput> = tx
+            .inputs
+            .iter()
+            .map(|input| TxInput {
+                tx_hash: input.tx_hash.clone(),
+                output_index: input.output_index,
+                address: self.output(input).map(|o| o.address.clone()),
+                spent_slot: Some(tx.block.slot),
+            })
+            .collect();
+        inputs.sort_by(|a, b| (&a.tx_hash, a.output_index).cmp(&(&b.tx_hash, b.output_index)));
+        Ok(inputs)
+    }
+
+    async fn treasury_donation(&self, tx_hash: &str) -> Result<i64, ChainError> {
+        Ok(self.transactions.get(tx_hash).map_or(0, |tx| tx.treasury_donation))
+    }
+
+    async fn address_utxos(&self, address: &str) -> Result<Vec<TxOutput>, ChainError> {
+        let utxos = self
+            .outputs_by_address
+            .get(address)
+            .into_iter()
+            .flatten()
+            .filter_map(|o| self.output(o).cloned())
+            .collect();
+        Ok(utxos)
+    }
+
+    async fn spends(&self, outputs: &[OutputRef]) -> Result<Vec<Spend>, ChainError> {
+        let spends = outputs
+            .iter()
+            .filter_map(|o| {
+                let (spent_tx_hash, spent_slot) = self.spent_by.get(o)?;
+                Some(Spend {
+                    tx_hash: o.tx_hash.clone(),
+                    output_index: o.output_index,
+                    spent_tx_hash: spent_tx_hash.clone(),
+                    spent_slot: Some(*spent_slot),
+                })
+            })
+            .collect();
+        Ok(spends)
+    }
+}
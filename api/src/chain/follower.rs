@@ -0,0 +1,204 @@
+//! Chain follower source
+//!
+//! Reads the `chain_*` tables the built-in chain follower
+//! (`services::follower`) writes into the treasury schema. They only hold
+//! transactions the follower kept, with all their inputs and outputs, so
+//! lookups of other transactions come back empty as with a filtering
+//! indexer. The tip is the last block the follower stored, not the node's:
+//! events are only visible once their block is stored.
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{BlockInfo, ChainError, ChainSource, OutputRef, RawTomEvent, Spend, TxInput, TxOutput, TOM_LABEL};
+
+/// Chain source backed by the chain follower's tables
+#[derive(Clone)]
+pub struct FollowerStore {
+    pool: PgPool,
+}
+
+impl FollowerStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ChainSource for FollowerStore {
+    fn name(&self) -> &'static str {
+        "follower"
+    }
+
+    async fn check(&self) -> Result<String, ChainError> {
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass('chain_blocks') IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(ChainError::Unavailable("chain_blocks does not exist; run migrations".to_string()));
+        }
+        let stored = self.tip().await?;
+        let node_tip: Option<i64> = sqlx::query_scalar("SELECT slot FROM chain_tip")
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(match (stored, node_tip) {
+            (None, _) => "no blocks followed yet".to_string(),
+            (Some(block), Some(tip)) => format!("followed to slot {} of {}", block.slot, tip),
+            (Some(block), None) => format!("followed to slot {}", block.slot),
+        })
+    }
+
+    async fn tip(&self) -> Result<Option<BlockInfo>, ChainError> {
+        let tip = sqlx::query_as::<_, BlockInfo>(
+            "SELECT number, slot, block_time FROM chain_blocks ORDER BY slot DESC LIMIT 1"
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(tip)
+    }
+
+    async fn block_at_or_before(&self, slot: i64) -> Result<Option<BlockInfo>, ChainError> {
+        let block = sqlx::query_as::<_, BlockInfo>(
+            "SELECT number, slot, block_time FROM chain_blocks WHERE slot <= $1 ORDER BY slot DESC LIMIT 1"
+        )
+        .bind(slot)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(block)
+    }
+
+    async fn metadata_since(&self, label: i64, after_slot: i64, limit: Option<i64>)
+        -> Result<Vec<RawTomEvent>, ChainError>
+    {
+        // Only TOM metadata is kept
+        if label != TOM_LABEL {
+            return Ok(Vec::new());
+        }
+        let events = sqlx::query_as::<_, RawTomEvent>(
+            r#"
+            SELECT t.tx_hash, t.slot, t.tom_metadata as body, b.number as block_number, b.block_time
+            FROM chain_transactions t
+            JOIN chain_blocks b ON b.slot = t.slot
+            WHERE t.tom_metadata IS NOT NULL AND t.slot > $1
+            ORDER BY t.slot, t.block_index
+            LIMIT $2
+            "#
+        )
+        .bind(after_slot)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(events)
+    }
+
+    async fn tx_metadata(&self, label: i64, tx_hash: &str) -> Result<Option<RawTomEvent>, ChainError> {
+        if label != TOM_LABEL {
+            return Ok(None);
+        }
+        let event = sqlx::query_as::<_, RawTomEvent>(
+            r#"
+            SELECT t.tx_hash, t.slot, t.tom_metadata as body, b.number as block_number, b.block_time
+            FROM chain_transactions t
+            JOIN chain_blocks b ON b.slot = t.slot
+            WHERE t.tom_metadata IS NOT NULL AND t.tx_hash = $1
+            "#
+        )
+        .bind(tx_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(event)
+    }
+
+    async fn tx_outputs(&self, tx_hash: &str) -> Result<Vec<TxOutput>, ChainError> {
+        let outputs = sqlx::query_as::<_, TxOutput>(
+            r#"
+            SELECT o.tx_hash, o.output_index, o.address, o.lovelace, t.slot, b.number as block_number
+            FROM chain_outputs o
+            JOIN chain_transactions t ON t.tx_hash = o.tx_hash
+            JOIN chain_blocks b ON b.slot = t.slot
+            WHERE o.tx_hash = $1
+            ORDER BY o.output_index
+            "#
+        )
+        .bind(tx_hash)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(outputs)
+    }
+
+    async fn tx_output_refs(&self, tx_hash: &str) -> Result<Vec<OutputRef>, ChainError> {
+        // Every output of a kept transaction is stored
+        let refs: Vec<(String, i16)> = sqlx::query_as(
+            "SELECT tx_hash, output_index FROM chain_outputs WHERE tx_hash = $1 ORDER BY output_index"
+        )
+        .bind(tx_hash)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(refs
+            .into_iter()
+            .map(|(tx_hash, output_index)| OutputRef { tx_hash, output_index })
+            .collect())
+    }
+
+    async fn tx_inputs(&self, tx_hash: &str) -> Result<Vec<TxInput>, ChainError> {
+        let inputs = sqlx::query_as::<_, TxInput>(
+            r#"
+            SELECT i.tx_hash, i.output_index, o.address, t.slot as spent_slot
+            FROM chain_inputs i
+            JOIN chain_transactions t ON t.tx_hash = i.spent_tx_hash
+            LEFT JOIN chain_outputs o ON o.tx_hash = i.tx_hash AND o.output_index = i.output_index
+            WHERE i.spent_tx_hash = $1
+            ORDER BY i.tx_hash, i.output_index
+            "#
+        )
+        .bind(tx_hash)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(inputs)
+    }
+
+    async fn treasury_donation(&self, tx_hash: &str) -> Result<i64, ChainError> {
+        let donation: Option<i64> = sqlx::query_scalar(
+            "SELECT treasury_donation FROM chain_transactions WHERE tx_hash = $1"
+        )
+        .bind(tx_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(donation.unwrap_or(0))
+    }
+
+    async fn address_utxos(&self, address: &str) -> Result<Vec<TxOutput>, ChainError> {
+        let utxos = sqlx::query_as::<_, TxOutput>(
+            r#"
+            SELECT o.tx_hash, o.output_index, o.address, o.lovelace, t.slot, b.number as block_number
+            FROM chain_outputs o
+            JOIN chain_transactions t ON t.tx_hash = o.tx_hash
+            JOIN chain_blocks b ON b.slot = t.slot
+            WHERE o.address = $1
+            "#
+        )
+        .bind(address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(utxos)
+    }
+
+    async fn spends(&self, outputs: &[OutputRef]) -> Result<Vec<Spend>, ChainError> {
+        let (tx_hashes, indexes): (Vec<&str>, Vec<i16>) =
+            outputs.iter().map(|o| (o.tx_hash.as_str(), o.output_index)).unzip();
+        let spends = sqlx::query_as::<_, Spend>(
+            r#"
+            SELECT i.tx_hash, i.output_index, i.spent_tx_hash, t.slot as spent_slot
+            FROM chain_inputs i
+            JOIN UNNEST($1::text[], $2::smallint[]) AS o(tx_hash, output_index)
+                ON i.tx_hash = o.tx_hash AND i.output_index = o.output_index
+            JOIN chain_transactions t ON t.tx_hash = i.spent_tx_hash
+            "#
+        )
+        .bind(&tx_hashes)
+        .bind(&indexes)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(spends)
+    }
+}
@@ -0,0 +1,181 @@
+//! Chain data sources
+//!
+//! The event processor and the sync loop read blocks, TOM metadata and
+//! transaction outputs through [`ChainSource`], so they do not depend on a
+//! particular indexer. `chain.source` selects the implementation:
+//!
+//! - `yaci_store`: the tables YACI Store maintains in the treasury database
+//! - `db_sync`: a cardano-db-sync schema, in the treasury database or its
+//!   own (see [`db_sync`])
+//! - `file`: a JSON dump of blocks and transactions, read into memory at
+//!   startup (see [`file_dump`])
+//! - `follower`: the tables the built-in chain follower fills from a node or
+//!   a block file (see [`follower`] and [`blocks`])
+
+use anyhow::Context;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::config::{ChainConfig, ChainSourceKind, DatabaseConfig};
+use crate::db;
+use crate::error::ApiError;
+
+pub mod blocks;
+pub mod db_sync;
+pub mod file_dump;
+pub mod follower;
+pub mod yaci_store;
+
+pub use db_sync::DbSync;
+pub use file_dump::FileDump;
+pub use follower::FollowerStore;
+pub use yaci_store::YaciStore;
+
+/// Transaction metadata label of TOM events
+pub const TOM_LABEL: i64 = 1694;
+
+/// Chain source error
+#[derive(Debug, thiserror::Error)]
+pub enum ChainError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    /// The source cannot serve chain data, e.g. its schema is missing
+    #[error("{0}")]
+    Unavailable(String),
+}
+
+impl From<ChainError> for ApiError {
+    fn from(e: ChainError) -> Self {
+        match e {
+            ChainError::Database(e) => e.into(),
+            ChainError::Unavailable(detail) => Self::Unavailable(detail),
+        }
+    }
+}
+
+/// A block header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::FromRow)]
+pub struct BlockInfo {
+    pub number: i64,
+    pub slot: i64,
+    /// Unix time of the block
+    pub block_time: i64,
+}
+
+/// Transaction metadata under [`TOM_LABEL`], with its block
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RawTomEvent {
+    pub tx_hash: String,
+    pub slot: Option<i64>,
+    pub body: Option<serde_json::Value>,
+    pub block_number: Option<i64>,
+    pub block_time: Option<i64>,
+}
+
+/// Reference to a transaction output
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutputRef {
+    pub tx_hash: String,
+    pub output_index: i16,
+}
+
+/// A transaction output with the address it pays to
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TxOutput {
+    pub tx_hash: String,
+    pub output_index: i16,
+    pub address: String,
+    pub lovelace: i64,
+    pub slot: Option<i64>,
+    pub block_number: Option<i64>,
+}
+
+/// An output spent by a transaction. `address` is `None` when the source
+/// does not know the spent output.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TxInput {
+    pub tx_hash: String,
+    pub output_index: i16,
+    pub address: Option<String>,
+    pub spent_slot: Option<i64>,
+}
+
+/// The transaction that spent an output
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Spend {
+    pub tx_hash: String,
+    pub output_index: i16,
+    pub spent_tx_hash: String,
+    pub spent_slot: Option<i64>,
+}
+
+/// Read access to indexed chain data. Lookups of unknown transactions return
+/// empty results rather than errors, as an indexer that filters the chain
+/// may not hold every transaction.
+#[async_trait]
+pub trait ChainSource: Send + Sync {
+    /// Short name for logs and health checks
+    fn name(&self) -> &'static str;
+
+    /// Check the source can serve chain data, describing what it holds
+    async fn check(&self) -> Result<String, ChainError>;
+
+    /// The latest block
+    async fn tip(&self) -> Result<Option<BlockInfo>, ChainError>;
+
+    /// The last block at or before `slot`
+    async fn block_at_or_before(&self, slot: i64) -> Result<Option<BlockInfo>, ChainError>;
+
+    /// Metadata under `label` in blocks after `after_slot`, oldest first, at
+    /// most `limit` entries when given
+    async fn metadata_since(&self, label: i64, after_slot: i64, limit: Option<i64>)
+        -> Result<Vec<RawTomEvent>, ChainError>;
+
+    /// Metadata under `label` of a single transaction
+    async fn tx_metadata(&self, label: i64, tx_hash: &str) -> Result<Option<RawTomEvent>, ChainError>;
+
+    /// Outputs of a transaction whose address is known, by output index
+    async fn tx_outputs(&self, tx_hash: &str) -> Result<Vec<TxOutput>, ChainError>;
+
+    /// Every output of a transaction, including outputs `tx_outputs` leaves
+    /// out because their address is not indexed
+    async fn tx_output_refs(&self, tx_hash: &str) -> Result<Vec<OutputRef>, ChainError>;
+
+    /// Outputs a transaction spends, ordered by output reference
+    async fn tx_inputs(&self, tx_hash: &str) -> Result<Vec<TxInput>, ChainError>;
+
+    /// Lovelace a transaction donates to the Cardano treasury
+    async fn treasury_donation(&self, tx_hash: &str) -> Result<i64, ChainError>;
+
+    /// Every output paying to `address`, spent or not
+    async fn address_utxos(&self, address: &str) -> Result<Vec<TxOutput>, ChainError>;
+
+    /// Which of `outputs` have been spent, and by which transaction
+    async fn spends(&self, outputs: &[OutputRef]) -> Result<Vec<Spend>, ChainError>;
+}
+
+/// Open the configured chain source. `pool` is the treasury database, which
+/// YACI Store and the follower's tables are read from; db-sync gets its own
+/// pool, on `database` settings.
+pub async fn open(config: &ChainConfig, database: &DatabaseConfig, pool: &PgPool)
+    -> anyhow::Result<Arc<dyn ChainSource>>
+{
+    Ok(match config.source {
+        ChainSourceKind::YaciStore => Arc::new(YaciStore::new(pool.clone())),
+        ChainSourceKind::DbSync => {
+            let pool = db::connect_db_sync(database, config)
+                .await
+                .context("failed to connect to the db-sync database")?;
+            Arc::new(DbSync::new(pool))
+        }
+        ChainSourceKind::File => {
+            let path = config
+                .dump_path
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("chain.source = \"file\" requires chain.dump_path"))?;
+            Arc::new(FileDump::load(path)?)
+        }
+        ChainSourceKind::Follower => Arc::new(FollowerStore::new(pool.clone())),
+    })
+}
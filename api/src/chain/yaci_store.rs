@@ -0,0 +1,210 @@
+//! YACI Store chain source
+//!
+//! Reads the tables YACI Store maintains in the `yaci_store` schema of the
+//! treasury database.
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+use super::{BlockInfo, ChainError, ChainSource, OutputRef, RawTomEvent, Spend, TxInput, TxOutput};
+
+/// Chain source backed by the `yaci_store` schema
+#[derive(Clone)]
+pub struct YaciStore {
+    pool: PgPool,
+}
+
+impl YaciStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ChainSource for YaciStore {
+    fn name(&self) -> &'static str {
+        "yaci_store"
+    }
+
+    async fn check(&self) -> Result<String, ChainError> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = 'yaci_store')")
+                .fetch_one(&self.pool)
+                .await?;
+        if exists {
+            Ok("schema present".to_string())
+        } else {
+            Err(ChainError::Unavailable("schema yaci_store does not exist".to_string()))
+        }
+    }
+
+    async fn tip(&self) -> Result<Option<BlockInfo>, ChainError> {
+        let tip = sqlx::query_as::<_, BlockInfo>(
+            "SELECT number, slot, block_time FROM yaci_store.block ORDER BY slot DESC LIMIT 1"
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(tip)
+    }
+
+    async fn block_at_or_before(&self, slot: i64) -> Result<Option<BlockInfo>, ChainError> {
+        let block = sqlx::query_as::<_, BlockInfo>(
+            "SELECT number, slot, block_time FROM yaci_store.block WHERE slot <= $1 ORDER BY slot DESC LIMIT 1"
+        )
+        .bind(slot)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(block)
+    }
+
+    async fn metadata_since(&self, label: i64, after_slot: i64, limit: Option<i64>)
+        -> Result<Vec<RawTomEvent>, ChainError>
+    {
+        let events = sqlx::query_as::<_, RawTomEvent>(
+            r#"
+            SELECT
+                m.tx_hash,
+                m.slot,
+                m.body::jsonb as body,
+                b.number as block_number,
+                b.block_time
+            FROM yaci_store.transaction_metadata m
+            JOIN yaci_store.block b ON b.slot = m.slot
+            LEFT JOIN yaci_store.transaction t ON t.tx_hash = m.tx_hash
+            WHERE m.label = $1 AND m.slot > $2
+            ORDER BY m.slot ASC, t.tx_index ASC
+            LIMIT $3
+            "#
+        )
+        .bind(label.to_string())
+        .bind(after_slot)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(events)
+    }
+
+    async fn tx_metadata(&self, label: i64, tx_hash: &str) -> Result<Option<RawTomEvent>, ChainError> {
+        let event = sqlx::query_as::<_, RawTomEvent>(
+            r#"
+            SELECT
+                m.tx_hash,
+                m.slot,
+                m.body::jsonb as body,
+                b.number as block_number,
+                b.block_time
+            FROM yaci_store.transaction_metadata m
+            JOIN yaci_store.block b ON b.slot = m.slot
+            WHERE m.label = $1 AND m.tx_hash = $2
+            "#
+        )
+        .bind(label.to_string())
+        .bind(tx_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(event)
+    }
+
+    async fn tx_outputs(&self, tx_hash: &str) -> Result<Vec<TxOutput>, ChainError> {
+        let outputs = sqlx::query_as::<_, TxOutput>(
+            r#"
+            SELECT tx_hash, output_index::smallint, owner_addr as address, COALESCE(lovelace_amount, 0) as lovelace,
+                   slot, block as block_number
+            FROM yaci_store.address_utxo
+            WHERE tx_hash = $1 AND owner_addr IS NOT NULL
+            ORDER BY output_index
+            "#
+        )
+        .bind(tx_hash)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(outputs)
+    }
+
+    async fn tx_output_refs(&self, tx_hash: &str) -> Result<Vec<OutputRef>, ChainError> {
+        // address_utxo only holds outputs YACI Store's filters kept; the raw
+        // output list of the transaction has all of them
+        let raw_outputs: Option<serde_json::Value> = sqlx::query_scalar(
+            "SELECT outputs::jsonb FROM yaci_store.transaction WHERE tx_hash = $1"
+        )
+        .bind(tx_hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+
+        let refs = raw_outputs
+            .as_ref()
+            .and_then(|o| o.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|output| {
+                Some(OutputRef {
+                    tx_hash: output.get("tx_hash")?.as_str()?.to_string(),
+                    output_index: output.get("output_index")?.as_i64()? as i16,
+                })
+            })
+            .collect();
+        Ok(refs)
+    }
+
+    async fn tx_inputs(&self, tx_hash: &str) -> Result<Vec<TxInput>, ChainError> {
+        let inputs = sqlx::query_as::<_, TxInput>(
+            r#"
+            SELECT i.tx_hash, i.output_index::smallint, au.owner_addr as address, i.spent_at_slot as spent_slot
+            FROM yaci_store.tx_input i
+            LEFT JOIN yaci_store.address_utxo au ON au.tx_hash = i.tx_hash AND au.output_index = i.output_index
+            WHERE i.spent_tx_hash = $1
+            ORDER BY i.tx_hash, i.output_index
+            "#
+        )
+        .bind(tx_hash)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(inputs)
+    }
+
+    async fn treasury_donation(&self, tx_hash: &str) -> Result<i64, ChainError> {
+        let donation: Option<i64> = sqlx::query_scalar(
+            "SELECT treasury_donation FROM yaci_store.transaction WHERE tx_hash = $1"
+        )
+        .bind(tx_hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+        Ok(donation.unwrap_or(0))
+    }
+
+    async fn address_utxos(&self, address: &str) -> Result<Vec<TxOutput>, ChainError> {
+        let utxos = sqlx::query_as::<_, TxOutput>(
+            r#"
+            SELECT tx_hash, output_index::smallint, owner_addr as address, COALESCE(lovelace_amount, 0) as lovelace,
+                   slot, block as block_number
+            FROM yaci_store.address_utxo
+            WHERE owner_addr = $1
+            "#
+        )
+        .bind(address)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(utxos)
+    }
+
+    async fn spends(&self, outputs: &[OutputRef]) -> Result<Vec<Spend>, ChainError> {
+        let (tx_hashes, indexes): (Vec<&str>, Vec<i16>) =
+            outputs.iter().map(|o| (o.tx_hash.as_str(), o.output_index)).unzip();
+        let spends = sqlx::query_as::<_, Spend>(
+            r#"
+            SELECT i.tx_hash, i.output_index::smallint, i.spent_tx_hash, i.spent_at_slot as spent_slot
+            FROM yaci_store.tx_input i
+            JOIN UNNEST($1::text[], $2::smallint[]) AS o(tx_hash, output_index)
+                ON i.tx_hash = o.tx_hash AND i.output_index = o.output_index
+            WHERE i.spent_tx_hash IS NOT NULL
+            "#
+        )
+        .bind(&tx_hashes)
+        .bind(&indexes)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(spends)
+    }
+}
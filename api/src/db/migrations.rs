@@ -0,0 +1,372 @@
+// Versioned schema migrations
+//
+// Migrations live in `api/migrations/NNNN_name.{up,down}.sql` and are embedded
+// at compile time. Scripts use unqualified names; the runner sets search_path
+// to the target schema, so the same scripts can build a copy of the schema
+// under another name. Each applied version is recorded in
+// `<schema>.schema_migrations` with the SHA-256 of its up script.
+
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, Executor, PgConnection, PgPool};
+use std::time::Instant;
+
+/// Schema the API reads from
+pub const DEFAULT_SCHEMA: &str = "treasury";
+
+/// An embedded migration
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// SHA-256 of the up script (hex)
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+/// All migrations, in version order. Append only: released migrations must
+/// not be edited, since their checksums are recorded in deployed databases.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "baseline",
+        up: include_str!("../../migrations/0001_baseline.up.sql"),
+        down: include_str!("../../migrations/0001_baseline.down.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "data_generation",
+        up: include_str!("../../migrations/0002_data_generation.up.sql"),
+        down: include_str!("../../migrations/0002_data_generation.down.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "summary_tables",
+        up: include_str!("../../migrations/0003_summary_tables.up.sql"),
+        down: include_str!("../../migrations/0003_summary_tables.down.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "sync_heartbeat",
+        up: include_str!("../../migrations/0004_sync_heartbeat.up.sql"),
+        down: include_str!("../../migrations/0004_sync_heartbeat.down.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "sync_heartbeat_rate",
+        up: include_str!("../../migrations/0005_sync_heartbeat_rate.up.sql"),
+        down: include_str!("../../migrations/0005_sync_heartbeat_rate.down.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "api_keys",
+        up: include_str!("../../migrations/0006_api_keys.up.sql"),
+        down: include_str!("../../migrations/0006_api_keys.down.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "curation",
+        up: include_str!("../../migrations/0007_curation.up.sql"),
+        down: include_str!("../../migrations/0007_curation.down.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "chain_follower",
+        up: include_str!("../../migrations/0008_chain_follower.up.sql"),
+        down: include_str!("../../migrations/0008_chain_follower.down.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "pending_transactions",
+        up: include_str!("../../migrations/0009_pending_transactions.up.sql"),
+        down: include_str!("../../migrations/0009_pending_transactions.down.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "generation_on_change",
+        up: include_str!("../../migrations/0010_generation_on_change.up.sql"),
+        down: include_str!("../../migrations/0010_generation_on_change.down.sql"),
+    },
+];
+
+/// Newest schema version this binary understands
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Migration error
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("database schema version {database} is newer than this binary supports ({supported}); upgrade the binary")]
+    NewerSchema { database: i64, supported: i64 },
+    #[error("migration {version} ({name}) was modified after it was applied (checksum mismatch)")]
+    ChecksumMismatch { version: i64, name: String },
+    #[error("unknown target version {0}")]
+    UnknownVersion(i64),
+    #[error("invalid schema name '{0}'")]
+    InvalidSchema(String),
+    #[error("migration {version} ({name}) failed: {source}")]
+    Failed {
+        version: i64,
+        name: &'static str,
+        #[source]
+        source: sqlx::Error,
+    },
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// A migration as recorded in the database
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: chrono::DateTime<chrono::Utc>,
+    pub execution_ms: i64,
+}
+
+/// State of one known migration
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub migration: &'static Migration,
+    pub applied: Option<AppliedMigration>,
+}
+
+impl MigrationStatus {
+    /// Whether the recorded checksum matches the embedded script
+    pub fn checksum_ok(&self) -> bool {
+        self.applied
+            .as_ref()
+            .is_none_or(|a| a.checksum == self.migration.checksum())
+    }
+}
+
+/// Applies and reverts migrations for one schema
+pub struct Migrator<'a> {
+    pool: &'a PgPool,
+    schema: String,
+}
+
+impl<'a> Migrator<'a> {
+    /// Migrator for the `treasury` schema
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self {
+            pool,
+            schema: DEFAULT_SCHEMA.to_string(),
+        }
+    }
+
+    /// Migrator for another schema (e.g. a shadow copy)
+    pub fn for_schema(pool: &'a PgPool, schema: &str) -> Result<Self, MigrationError> {
+        let valid = !schema.is_empty()
+            && schema.len() <= 63
+            && schema.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+            && schema.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(MigrationError::InvalidSchema(schema.to_string()));
+        }
+        Ok(Self {
+            pool,
+            schema: schema.to_string(),
+        })
+    }
+
+    /// Highest applied version (0 for an empty schema)
+    pub async fn current_version(&self) -> Result<i64, MigrationError> {
+        let mut conn = self.pool.acquire().await?;
+        self.ensure_table(&mut conn).await?;
+        let applied = self.applied(&mut conn).await?;
+        Ok(applied.last().map_or(0, |a| a.version))
+    }
+
+    /// Every known migration with its recorded state
+    pub async fn status(&self) -> Result<Vec<MigrationStatus>, MigrationError> {
+        let mut conn = self.pool.acquire().await?;
+        self.ensure_table(&mut conn).await?;
+        let applied = self.applied(&mut conn).await?;
+        Ok(MIGRATIONS
+            .iter()
+            .map(|migration| MigrationStatus {
+                migration,
+                applied: applied.iter().find(|a| a.version == migration.version).cloned(),
+            })
+            .collect())
+    }
+
+    /// Apply all pending migrations, returning the versions applied
+    pub async fn migrate(&self) -> Result<Vec<i64>, MigrationError> {
+        self.migrate_to(latest_version()).await
+    }
+
+    /// Move the schema to `target`, applying or reverting as needed. Returns
+    /// the versions applied (ascending) or reverted (descending).
+    pub async fn migrate_to(&self, target: i64) -> Result<Vec<i64>, MigrationError> {
+        if target != 0 && !MIGRATIONS.iter().any(|m| m.version == target) {
+            return Err(MigrationError::UnknownVersion(target));
+        }
+
+        let mut conn = self.pool.acquire().await?;
+
+        // Serialise runners (several API instances may start together)
+        sqlx::query("SELECT pg_advisory_lock(hashtext($1))")
+            .bind(self.lock_key())
+            .execute(&mut *conn)
+            .await?;
+        let result = self.migrate_locked(&mut conn, target).await;
+        sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+            .bind(self.lock_key())
+            .execute(&mut *conn)
+            .await?;
+        result
+    }
+
+    async fn migrate_locked(&self, conn: &mut PgConnection, target: i64) -> Result<Vec<i64>, MigrationError> {
+        self.ensure_table(conn).await?;
+        let applied = self.applied(conn).await?;
+        self.check(&applied)?;
+
+        let current = applied.last().map_or(0, |a| a.version);
+        let mut changed = Vec::new();
+
+        if target >= current {
+            for migration in MIGRATIONS.iter().filter(|m| m.version <= target) {
+                if applied.iter().any(|a| a.version == migration.version) {
+                    continue;
+                }
+                self.apply(conn, migration).await?;
+                changed.push(migration.version);
+            }
+        } else {
+            for migration in MIGRATIONS.iter().rev().filter(|m| m.version > target) {
+                if !applied.iter().any(|a| a.version == migration.version) {
+                    continue;
+                }
+                self.revert(conn, migration).await?;
+                changed.push(migration.version);
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Refuse newer, unknown or edited migrations
+    fn check(&self, applied: &[AppliedMigration]) -> Result<(), MigrationError> {
+        for record in applied {
+            let Some(migration) = MIGRATIONS.iter().find(|m| m.version == record.version) else {
+                return Err(MigrationError::NewerSchema {
+                    database: applied.last().map_or(record.version, |a| a.version),
+                    supported: latest_version(),
+                });
+            };
+            if record.checksum != migration.checksum() {
+                return Err(MigrationError::ChecksumMismatch {
+                    version: record.version,
+                    name: record.name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn apply(&self, conn: &mut PgConnection, migration: &'static Migration) -> Result<(), MigrationError> {
+        tracing::info!("Applying migration {} ({}) to {}", migration.version, migration.name, self.schema);
+        let started = Instant::now();
+        let failed = |source| MigrationError::Failed {
+            version: migration.version,
+            name: migration.name,
+            source,
+        };
+
+        let mut tx = conn.begin().await?;
+        tx.execute(self.search_path().as_str()).await.map_err(failed)?;
+        tx.execute(migration.up).await.map_err(failed)?;
+        sqlx::query(&format!(
+            "INSERT INTO {}.schema_migrations (version, name, checksum, execution_ms) VALUES ($1, $2, $3, $4)",
+            self.schema
+        ))
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(migration.checksum())
+        .bind(started.elapsed().as_millis() as i64)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn revert(&self, conn: &mut PgConnection, migration: &'static Migration) -> Result<(), MigrationError> {
+        tracing::info!("Reverting migration {} ({}) in {}", migration.version, migration.name, self.schema);
+        let failed = |source| MigrationError::Failed {
+            version: migration.version,
+            name: migration.name,
+            source,
+        };
+
+        let mut tx = conn.begin().await?;
+        tx.execute(self.search_path().as_str()).await.map_err(failed)?;
+        tx.execute(migration.down).await.map_err(failed)?;
+        sqlx::query(&format!("DELETE FROM {}.schema_migrations WHERE version = $1", self.schema))
+            .bind(migration.version)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn ensure_table(&self, conn: &mut PgConnection) -> Result<(), MigrationError> {
+        conn.execute(format!("CREATE SCHEMA IF NOT EXISTS {}", self.schema).as_str()).await?;
+        conn.execute(
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {}.schema_migrations (
+                    version BIGINT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    checksum VARCHAR(64) NOT NULL,
+                    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    execution_ms BIGINT NOT NULL DEFAULT 0
+                )
+                "#,
+                self.schema
+            )
+            .as_str(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn applied(&self, conn: &mut PgConnection) -> Result<Vec<AppliedMigration>, MigrationError> {
+        let rows = sqlx::query_as::<_, AppliedMigration>(&format!(
+            "SELECT version, name, checksum, applied_at, execution_ms FROM {}.schema_migrations ORDER BY version",
+            self.schema
+        ))
+        .fetch_all(&mut *conn)
+        .await?;
+        Ok(rows)
+    }
+
+    fn search_path(&self) -> String {
+        format!("SET LOCAL search_path TO {}, public", self.schema)
+    }
+
+    fn lock_key(&self) -> String {
+        format!("treasury-migrations:{}", self.schema)
+    }
+}
+
+/// Bring the treasury schema up to date. Fails without changing anything if
+/// the database has migrations this binary does not know about.
+pub async fn migrate(pool: &PgPool) -> Result<(), MigrationError> {
+    let applied = Migrator::new(pool).migrate().await?;
+    if applied.is_empty() {
+        tracing::info!("Treasury schema is up to date (version {})", latest_version());
+    } else {
+        tracing::info!("Applied migrations {:?}; schema is at version {}", applied, latest_version());
+    }
+    Ok(())
+}
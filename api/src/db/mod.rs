@@ -1,6 +1,10 @@
 // Database connection and query utilities
 
 pub mod connection;
+pub mod migrations;
 pub mod queries;
+pub mod replica;
 
-pub use connection::init_treasury_schema;
+pub use connection::{connect, connect_db_sync, connect_replica, connect_schema};
+pub use migrations::{migrate, Migrator};
+pub use replica::{ReadPool, Replica};
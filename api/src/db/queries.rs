@@ -1 +1,39 @@
 // Database query functions
+
+// ============================================================================
+// FULL-TEXT SEARCH DOCUMENTS
+// ============================================================================
+//
+// The search queries must use the same expressions as the GIN indexes created
+// in migrations/0001_baseline.up.sql so the planner can match them. `prefix`
+// is the table alias with its dot (e.g. "vc.").
+// Weights: A = identifiers and titles, B = descriptions, C = secondary text.
+
+/// Search document for treasury.vendor_contracts
+pub fn project_document(prefix: &str) -> String {
+    format!(
+        "(setweight(to_tsvector('english', COALESCE({p}project_id, '') || ' ' || COALESCE({p}project_name, '')), 'A') || \
+         setweight(to_tsvector('english', COALESCE({p}description, '')), 'B') || \
+         setweight(to_tsvector('english', COALESCE({p}vendor_name, '')), 'C'))",
+        p = prefix
+    )
+}
+
+/// Search document for treasury.milestones, including completion evidence strings
+pub fn milestone_document(prefix: &str) -> String {
+    format!(
+        "(setweight(to_tsvector('english', COALESCE({p}label, '')), 'A') || \
+         setweight(to_tsvector('english', COALESCE({p}description, '') || ' ' || COALESCE({p}acceptance_criteria, '') || ' ' || COALESCE({p}complete_description, '')), 'B') || \
+         setweight(jsonb_to_tsvector('english', COALESCE({p}evidence, '[]'::jsonb), '[\"string\"]'), 'C'))",
+        p = prefix
+    )
+}
+
+/// Search document for treasury.events: reason plus metadata strings
+pub fn event_document(prefix: &str) -> String {
+    format!(
+        "(setweight(to_tsvector('english', COALESCE({p}reason, '')), 'A') || \
+         setweight(jsonb_to_tsvector('english', COALESCE({p}metadata, '{{}}'::jsonb), '[\"string\"]'), 'C'))",
+        p = prefix
+    )
+}
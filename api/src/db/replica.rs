@@ -0,0 +1,85 @@
+// Read replica routing
+//
+// Read-only handlers take a `ReadPool`, which is the replica while it is fresh
+// and the primary otherwise. Freshness is decided by a background monitor that
+// compares the replica's sync cursor with the primary's.
+
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Pool for read-only queries
+#[derive(Clone)]
+pub struct ReadPool(pub PgPool);
+
+impl ReadPool {
+    /// The replica while it is fresh, otherwise the primary
+    pub fn select(primary: &PgPool, replica: Option<&Replica>) -> Self {
+        match replica {
+            Some(replica) if replica.is_fresh() => Self(replica.pool.clone()),
+            _ => Self(primary.clone()),
+        }
+    }
+}
+
+/// A read replica and whether it is currently fresh enough to serve reads
+#[derive(Clone)]
+pub struct Replica {
+    pool: PgPool,
+    fresh: Arc<AtomicBool>,
+}
+
+impl Replica {
+    /// Starts stale, so reads go to the primary until the first check passes
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            fresh: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh.load(Ordering::Relaxed)
+    }
+
+    /// Compare the replica's sync cursor with the primary's every `interval`,
+    /// routing reads to the primary while the replica is more than `max_lag`
+    /// slots behind or unreachable
+    pub async fn monitor(self, primary: PgPool, max_lag: i64, interval: Duration) {
+        loop {
+            let fresh = match replica_lag(&primary, &self.pool).await {
+                Ok(lag) if lag <= max_lag => true,
+                Ok(lag) => {
+                    if self.is_fresh() {
+                        tracing::warn!("Read replica is {} slots behind; reading from primary", lag);
+                    }
+                    false
+                }
+                Err(e) => {
+                    if self.is_fresh() {
+                        tracing::warn!("Read replica check failed: {}; reading from primary", e);
+                    }
+                    false
+                }
+            };
+            if fresh && !self.is_fresh() {
+                tracing::info!("Read replica is in sync; routing reads to replica");
+            }
+            self.fresh.store(fresh, Ordering::Relaxed);
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+/// Slots the replica's sync cursor trails the primary's
+pub async fn replica_lag(primary: &PgPool, replica: &PgPool) -> Result<i64, sqlx::Error> {
+    const QUERY: &str = "SELECT COALESCE(MAX(last_slot), 0) FROM treasury.sync_status WHERE sync_type = 'events'";
+    let primary_slot: i64 = sqlx::query_scalar(QUERY).fetch_one(primary).await?;
+    let replica_slot: i64 = sqlx::query_scalar(QUERY).fetch_one(replica).await?;
+    Ok(primary_slot - replica_slot)
+}
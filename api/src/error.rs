@@ -0,0 +1,175 @@
+//! API error model
+//!
+//! Handlers return `Result<_, ApiError>`. Errors render as an [`ErrorResponse`]
+//! envelope (same shape as `ApiResponse`, with `error` in place of `data`)
+//! carrying a machine-readable code, a message and the request's correlation
+//! id. The correlation id is taken from the `X-Request-Id` request header, or
+//! generated, and echoed back in the `X-Request-Id` response header so client
+//! reports can be matched to server logs.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+
+use crate::models::v1::{ErrorBody, ErrorResponse, ResponseMeta};
+
+/// Request/response header carrying the correlation id
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+/// API error
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// The requested resource does not exist
+    #[error("{0}")]
+    NotFound(String),
+    /// A path or query parameter is invalid
+    #[error("{parameter}: {message}")]
+    InvalidParameter { parameter: String, message: String },
+    /// Missing or invalid credentials
+    #[error("{0}")]
+    Unauthorized(String),
+    /// Valid credentials without the required scope
+    #[error("{0}")]
+    Forbidden(String),
+    /// The client exceeded its request limit
+    #[error("{0}")]
+    RateLimited(String),
+    /// The request conflicts with the current state
+    #[error("{0}")]
+    Conflict(String),
+    /// A dependency (e.g. the database) is temporarily unavailable
+    #[error("{0}")]
+    Unavailable(String),
+    /// Unexpected failure; the detail is logged, not returned
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn invalid_parameter(parameter: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::InvalidParameter {
+            parameter: parameter.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::InvalidParameter { .. } => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::InvalidParameter { .. } => "invalid_parameter",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::RateLimited(_) => "rate_limited",
+            Self::Conflict(_) => "conflict",
+            Self::Unavailable(_) => "unavailable",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+                Self::Unavailable(format!("Database unavailable: {}", e))
+            }
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+                tracing::debug!("Unique violation: {}", e);
+                Self::Conflict("A record with the same key already exists".to_string())
+            }
+            _ => Self::Internal(format!("Database query error: {}", e)),
+        }
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Internal(format!("I/O error: {}", e))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let correlation_id = current_correlation_id();
+        let status = self.status();
+
+        // Server-side failures keep their detail in the log only
+        let (message, parameter) = match self {
+            Self::Unavailable(ref detail) => {
+                tracing::warn!(correlation_id = %correlation_id, "{}", detail);
+                ("Service temporarily unavailable".to_string(), None)
+            }
+            Self::Internal(ref detail) => {
+                tracing::error!(correlation_id = %correlation_id, "{}", detail);
+                ("Internal server error".to_string(), None)
+            }
+            Self::InvalidParameter { ref parameter, ref message } => (message.clone(), Some(parameter.clone())),
+            Self::NotFound(ref message)
+            | Self::Unauthorized(ref message)
+            | Self::Forbidden(ref message)
+            | Self::RateLimited(ref message)
+            | Self::Conflict(ref message) => (message.clone(), None),
+        };
+
+        let body = ErrorResponse {
+            error: ErrorBody {
+                code: self.code().to_string(),
+                message,
+                parameter,
+                correlation_id,
+            },
+            meta: ResponseMeta::default(),
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Correlation id of the request being handled
+pub fn current_correlation_id() -> String {
+    CORRELATION_ID
+        .try_with(|id| id.clone())
+        .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string())
+}
+
+/// Middleware assigning each request a correlation id
+pub async fn correlation_id(request: Request, next: Next) -> Response {
+    // Accept a caller-supplied id if it is a reasonable header token
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty() && v.len() <= 128 && v.bytes().all(|b| b.is_ascii_graphic()))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let mut response = CORRELATION_ID.scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -0,0 +1,89 @@
+//! Stale data signalling
+//!
+//! A background task polls sync progress (see `services::heartbeat`). While
+//! data is stale (no recent sync heartbeat, or sync more than
+//! `health.max_lag_slots` behind the indexer tip) every `/api/v1` response
+//! carries `Retry-After` with the estimated seconds until sync catches up, so
+//! clients know to come back for fresher data.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use sqlx::PgPool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::chain::ChainSource;
+use crate::config::HealthConfig;
+use crate::db::{ReadPool, Replica};
+use crate::services::heartbeat;
+
+/// Longest `Retry-After` sent, however far behind sync is
+const MAX_RETRY_AFTER_SECS: u64 = 3600;
+
+/// Latest staleness verdict, shared by all handlers
+#[derive(Clone, Default)]
+pub struct LagMonitor {
+    /// Seconds to advertise in `Retry-After`; 0 while data is fresh
+    retry_after: Arc<AtomicU64>,
+}
+
+impl LagMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `Retry-After` seconds while data is stale
+    pub fn retry_after(&self) -> Option<u64> {
+        Some(self.retry_after.load(Ordering::Relaxed)).filter(|secs| *secs > 0)
+    }
+
+    /// Re-check staleness every `interval`, reading from the same pool as the
+    /// handlers. A failed check clears the verdict rather than guessing.
+    pub async fn watch(
+        self,
+        primary: PgPool,
+        replica: Option<Replica>,
+        chain: Arc<dyn ChainSource>,
+        health: HealthConfig,
+        interval: Duration,
+    ) {
+        loop {
+            let ReadPool(pool) = ReadPool::select(&primary, replica.as_ref());
+            let retry_after = match heartbeat::progress(&pool, chain.as_ref()).await {
+                Ok(progress) if progress.is_stale(&health) => {
+                    // Without a usable estimate, check back after one interval
+                    let estimate = progress.estimated_catch_up_secs(&health).map_or(0, |s| s.max(0) as u64);
+                    estimate.clamp(interval.as_secs().max(1), MAX_RETRY_AFTER_SECS)
+                }
+                Ok(_) => 0,
+                Err(e) => {
+                    tracing::warn!("Sync lag check failed: {}", e);
+                    0
+                }
+            };
+
+            let previous = self.retry_after.swap(retry_after, Ordering::Relaxed);
+            if previous == 0 && retry_after > 0 {
+                tracing::warn!("Data is stale; advertising Retry-After: {}", retry_after);
+            } else if previous > 0 && retry_after == 0 {
+                tracing::info!("Data is current again");
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+/// Middleware adding `Retry-After` to responses while data is stale
+pub async fn retry_after_when_stale(State(monitor): State<LagMonitor>, request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    if let Some(secs) = monitor.retry_after() {
+        response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    }
+    response
+}
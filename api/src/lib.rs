@@ -0,0 +1,18 @@
+//! Cardano treasury API
+//!
+//! Shared by the `treasury-api` server and the `treasury-cli` maintenance tool.
+
+pub mod auth;
+pub mod cache;
+pub mod chain;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod freshness;
+pub mod metrics;
+pub mod models;
+pub mod openapi;
+pub mod rate_limit;
+pub mod routes;
+pub mod services;
+pub mod state;
@@ -0,0 +1,172 @@
+//! Prometheus metrics
+//!
+//! Metrics live in a process-wide registry so the sync loop and the event
+//! processor can record without threading a handle through every call. They
+//! are served in the Prometheus text format at `/metrics`.
+//!
+//! Sync metrics are only recorded by the process holding sync leadership;
+//! alert on `treasury_sync_last_success_timestamp_seconds` going stale across
+//! all instances to catch a stalled sync loop.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use prometheus::{
+    exponential_buckets, Encoder, Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use sqlx::PgPool;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use crate::state::AppState;
+
+/// Event types reported individually; anything else is labelled `unknown`
+const EVENT_TYPES: &[&str] = &[
+    "publish", "initialize", "fund", "complete", "disburse", "withdraw", "pause", "resume", "modify", "cancel",
+    "sweep", "reorganize",
+];
+
+/// Process-wide metrics
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    registry: Registry,
+    /// 1 while this process holds sync leadership
+    pub sync_leader: IntGauge,
+    /// Slots between the scanned slot and the indexer tip
+    pub sync_lag_slots: IntGauge,
+    /// Block time between the scanned slot and the indexer tip
+    pub sync_lag_seconds: IntGauge,
+    /// Unix time of the last sync cycle that completed without error
+    pub sync_last_success: IntGauge,
+    /// Sync cycles by result (`ok` / `error`)
+    pub sync_cycles: IntCounterVec,
+    /// TOM events applied, by event type
+    pub events_processed: IntCounterVec,
+    /// TOM events that failed to apply, by event type
+    pub events_failed: IntCounterVec,
+    /// Time to fetch and apply one batch of events
+    pub sync_batch_duration: Histogram,
+    /// Time to sync UTXOs of tracked addresses
+    pub utxo_sync_duration: Histogram,
+    /// Pooled database connections by pool and state
+    db_pool_connections: IntGaugeVec,
+    /// HTTP requests by method, route and status
+    http_requests: IntCounterVec,
+    /// HTTP request latency by method and route
+    http_request_duration: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("treasury".to_string()), None).expect("valid registry prefix");
+        let seconds = |name: &str, help: &str| HistogramOpts::new(name, help).buckets(exponential_buckets(0.005, 2.0, 14).unwrap());
+
+        let metrics = Self {
+            sync_leader: IntGauge::new("sync_leader", "1 while this process holds sync leadership").unwrap(),
+            sync_lag_slots: IntGauge::new("sync_lag_slots", "Slots between the scanned slot and the indexer tip").unwrap(),
+            sync_lag_seconds: IntGauge::new("sync_lag_seconds", "Seconds of block time between the scanned slot and the indexer tip").unwrap(),
+            sync_last_success: IntGauge::new("sync_last_success_timestamp_seconds", "Unix time of the last sync cycle that completed without error").unwrap(),
+            sync_cycles: IntCounterVec::new(Opts::new("sync_cycles_total", "Sync cycles by result"), &["result"]).unwrap(),
+            events_processed: IntCounterVec::new(Opts::new("events_processed_total", "TOM events applied"), &["event_type"]).unwrap(),
+            events_failed: IntCounterVec::new(Opts::new("events_failed_total", "TOM events that failed to apply"), &["event_type"]).unwrap(),
+            sync_batch_duration: Histogram::with_opts(seconds("sync_batch_duration_seconds", "Time to fetch and apply one batch of events")).unwrap(),
+            utxo_sync_duration: Histogram::with_opts(seconds("utxo_sync_duration_seconds", "Time to sync UTXOs of tracked addresses")).unwrap(),
+            db_pool_connections: IntGaugeVec::new(Opts::new("db_pool_connections", "Pooled database connections"), &["pool", "state"]).unwrap(),
+            http_requests: IntCounterVec::new(Opts::new("http_requests_total", "HTTP requests"), &["method", "route", "status"]).unwrap(),
+            http_request_duration: HistogramVec::new(seconds("http_request_duration_seconds", "HTTP request latency"), &["method", "route"]).unwrap(),
+            registry,
+        };
+
+        let registry = &metrics.registry;
+        registry.register(Box::new(metrics.sync_leader.clone())).unwrap();
+        registry.register(Box::new(metrics.sync_lag_slots.clone())).unwrap();
+        registry.register(Box::new(metrics.sync_lag_seconds.clone())).unwrap();
+        registry.register(Box::new(metrics.sync_last_success.clone())).unwrap();
+        registry.register(Box::new(metrics.sync_cycles.clone())).unwrap();
+        registry.register(Box::new(metrics.events_processed.clone())).unwrap();
+        registry.register(Box::new(metrics.events_failed.clone())).unwrap();
+        registry.register(Box::new(metrics.sync_batch_duration.clone())).unwrap();
+        registry.register(Box::new(metrics.utxo_sync_duration.clone())).unwrap();
+        registry.register(Box::new(metrics.db_pool_connections.clone())).unwrap();
+        registry.register(Box::new(metrics.http_requests.clone())).unwrap();
+        registry.register(Box::new(metrics.http_request_duration.clone())).unwrap();
+        metrics
+    }
+
+    /// Count an applied (or failed) TOM event
+    pub fn record_event(&self, event_type: &str, ok: bool) {
+        let label = EVENT_TYPES.iter().find(|t| **t == event_type).copied().unwrap_or("unknown");
+        let counter = if ok { &self.events_processed } else { &self.events_failed };
+        counter.with_label_values(&[label]).inc();
+    }
+
+    /// Count a finished sync cycle
+    pub fn record_sync_cycle(&self, ok: bool) {
+        self.sync_cycles.with_label_values(&[if ok { "ok" } else { "error" }]).inc();
+        if ok {
+            self.sync_last_success.set(chrono::Utc::now().timestamp());
+        }
+    }
+
+    fn record_pool(&self, name: &str, pool: &PgPool) {
+        let size = i64::from(pool.size());
+        let idle = pool.num_idle() as i64;
+        self.db_pool_connections.with_label_values(&[name, "idle"]).set(idle);
+        self.db_pool_connections.with_label_values(&[name, "in_use"]).set(size - idle);
+        self.db_pool_connections
+            .with_label_values(&[name, "max"])
+            .set(i64::from(pool.options().get_max_connections()));
+    }
+
+    /// Prometheus text exposition of every metric
+    fn render(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+/// Serve metrics in the Prometheus text format
+pub async fn serve_metrics(State(state): State<AppState>) -> Response {
+    METRICS.record_pool("primary", &state.pool);
+    if let Some(ref replica) = state.replica {
+        METRICS.record_pool("replica", replica.pool());
+    }
+
+    match METRICS.render() {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to encode metrics: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Middleware recording request count and latency per route. Routes are
+/// labelled by their pattern (`/api/v1/milestones/:id`), not the raw path.
+pub async fn track_http(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = request.method().to_string();
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    METRICS
+        .http_request_duration
+        .with_label_values(&[&method, &route])
+        .observe(started.elapsed().as_secs_f64());
+    METRICS
+        .http_requests
+        .with_label_values(&[&method, &route, response.status().as_str()])
+        .inc();
+    response
+}
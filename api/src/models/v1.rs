@@ -71,6 +71,30 @@ impl Pagination {
     }
 }
 
+/// Error response envelope
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Error details
+    pub error: ErrorBody,
+    /// Response metadata
+    pub meta: ResponseMeta,
+}
+
+/// Error details
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+    /// Machine-readable error code (not_found/invalid_parameter/conflict/unavailable/internal_error)
+    #[schema(example = "not_found")]
+    pub code: String,
+    /// Human-readable message
+    pub message: String,
+    /// Offending parameter (invalid_parameter only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<String>,
+    /// Request correlation id (also returned in the X-Request-Id header)
+    pub correlation_id: String,
+}
+
 /// Response metadata
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ResponseMeta {
@@ -127,6 +151,73 @@ pub struct StatusResponse {
     pub total_events: i64,
     /// Total vendor contracts
     pub total_vendor_contracts: i64,
+    /// How far sync trails the indexer and the wall clock
+    pub sync_lag: SyncLag,
+}
+
+/// Sync position relative to the indexer tip and the wall clock
+///
+/// Fields are null when unknown: before the first sync cycle, or while the
+/// indexer has no blocks.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SyncLag {
+    /// Latest slot in the chain source
+    pub indexer_tip_slot: Option<i64>,
+    /// Slot up to which sync has scanned for TOM events
+    pub scanned_slot: Option<i64>,
+    /// Slots between the scanned slot and the indexer tip
+    pub slots_behind: Option<i64>,
+    /// Block time between the scanned slot and the indexer tip
+    pub seconds_behind: Option<i64>,
+    /// Wall-clock seconds since the block at the scanned slot (indexer lag plus sync lag)
+    pub seconds_behind_now: Option<i64>,
+    /// Wall-clock seconds since the indexer's latest block
+    pub indexer_lag_seconds: Option<i64>,
+    /// Seconds since the last completed sync cycle
+    pub heartbeat_age_seconds: Option<i64>,
+    /// Estimated seconds until sync reaches the indexer tip; null if sync is not gaining on it
+    pub estimated_catch_up_seconds: Option<i64>,
+    /// Data is stale: no recent sync cycle, or too far behind the tip
+    pub stale: bool,
+}
+
+/// Outcome of a health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    Fail,
+}
+
+/// A single readiness check
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthCheck {
+    pub status: HealthStatus,
+    /// What was observed, or why the check failed
+    pub detail: String,
+}
+
+/// Readiness checks, one per dependency
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthChecks {
+    /// The primary database answers queries
+    pub database: HealthCheck,
+    /// The configured chain source can serve chain data
+    pub chain_source: HealthCheck,
+    /// A sync worker completed a cycle within `health.max_heartbeat_age_secs`
+    pub sync_heartbeat: HealthCheck,
+    /// Sync trails the indexer tip by at most `health.max_lag_slots`
+    pub sync_lag: HealthCheck,
+}
+
+/// Liveness or readiness probe response
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthResponse {
+    /// `ok` only if every check passed
+    pub status: HealthStatus,
+    /// Per-check results (readiness only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checks: Option<HealthChecks>,
 }
 
 // ============================================================================
@@ -146,7 +237,7 @@ pub struct TreasuryResponse {
     pub stake_credential: Option<String>,
     /// Human-readable name
     pub name: Option<String>,
-    /// Contract status (active/paused)
+    /// Contract status (active/paused/swept)
     pub status: Option<String>,
     /// Publish transaction hash
     pub publish_tx_hash: Option<String>,
@@ -275,11 +366,13 @@ pub struct VendorContractSummary {
     pub vendor_name: Option<String>,
     /// Vendor payment address
     pub vendor_address: Option<String>,
+    /// Vendor ID (see /api/v1/vendors/{id})
+    pub vendor_id: Option<i32>,
     /// Contract URL (link to agreement)
     pub contract_url: Option<String>,
     /// PSSC script address
     pub contract_address: Option<String>,
-    /// Contract status (active/paused/completed/cancelled)
+    /// Contract status (active/paused/completed/cancelled/swept)
     pub status: Option<String>,
     /// Fund transaction hash
     pub fund_tx_hash: String,
@@ -299,6 +392,8 @@ pub struct VendorContractSummary {
     pub last_event_time: Option<i64>,
     /// Total event count
     pub event_count: Option<i64>,
+    /// Operator corrections and notes; null if there are none
+    pub curation: Option<Curation>,
 }
 
 /// Vendor contract detail (full response)
@@ -318,11 +413,13 @@ pub struct VendorContractDetail {
     pub vendor_name: Option<String>,
     /// Vendor payment address
     pub vendor_address: Option<String>,
+    /// Vendor ID (see /api/v1/vendors/{id})
+    pub vendor_id: Option<i32>,
     /// Contract URL (link to agreement)
     pub contract_url: Option<String>,
     /// PSSC script address
     pub contract_address: Option<String>,
-    /// Contract status (active/paused/completed/cancelled)
+    /// Contract status (active/paused/completed/cancelled/swept)
     pub status: Option<String>,
     /// Fund transaction hash
     pub fund_tx_hash: String,
@@ -346,6 +443,8 @@ pub struct VendorContractDetail {
     pub created_at: Option<DateTime<Utc>>,
     /// Record updated at
     pub updated_at: Option<DateTime<Utc>>,
+    /// Operator corrections and notes; null if there are none
+    pub curation: Option<Curation>,
 }
 
 /// Milestones summary counts
@@ -423,6 +522,7 @@ pub struct VendorContractSummaryRow {
     pub utxo_count: Option<i64>,
     pub last_event_time: Option<i64>,
     pub event_count: Option<i64>,
+    pub vendor_id: Option<i32>,
 }
 
 impl From<VendorContractSummaryRow> for VendorContractSummary {
@@ -443,6 +543,7 @@ impl From<VendorContractSummaryRow> for VendorContractSummary {
             description: row.description,
             vendor_name: row.vendor_name,
             vendor_address: row.vendor_address,
+            vendor_id: row.vendor_id,
             contract_url: row.contract_url,
             contract_address: row.contract_address,
             status: row.status,
@@ -472,6 +573,7 @@ impl From<VendorContractSummaryRow> for VendorContractSummary {
             },
             last_event_time: row.last_event_time,
             event_count: row.event_count,
+            curation: None,
         }
     }
 }
@@ -495,6 +597,7 @@ impl From<VendorContractSummaryRow> for VendorContractDetail {
             description: row.description,
             vendor_name: row.vendor_name,
             vendor_address: row.vendor_address,
+            vendor_id: row.vendor_id,
             contract_url: row.contract_url,
             contract_address: row.contract_address,
             status: row.status,
@@ -526,6 +629,135 @@ impl From<VendorContractSummaryRow> for VendorContractDetail {
             event_count: row.event_count,
             created_at: row.created_at,
             updated_at: row.updated_at,
+            curation: None,
+        }
+    }
+}
+
+// ============================================================================
+// VENDORS
+// ============================================================================
+
+/// Vendor summary (all projects paid to the same vendor)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VendorSummary {
+    /// Vendor ID
+    pub id: i32,
+    /// Grouping key (stake:<credential>, addr:<address> or a manual alias key)
+    pub vendor_key: String,
+    /// Vendor name (from the most recent project or alias override)
+    pub name: Option<String>,
+    /// Most recent payout address
+    pub payout_address: Option<String>,
+    /// Stake credential of the payout address (hex)
+    pub stake_credential: Option<String>,
+    /// Number of projects
+    pub project_count: i64,
+    /// Number of active projects
+    pub active_project_count: i64,
+    /// Financial totals across projects
+    pub financials: VendorFinancials,
+    /// Milestone delivery across projects
+    pub milestones: VendorMilestoneStats,
+    /// First project fund time (Unix timestamp)
+    pub first_fund_time: Option<i64>,
+    /// Latest project fund time (Unix timestamp)
+    pub last_fund_time: Option<i64>,
+}
+
+/// Vendor detail with projects
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VendorDetail {
+    /// Vendor summary
+    #[serde(flatten)]
+    pub vendor: VendorSummary,
+    /// Vendor's projects
+    pub projects: Vec<VendorContractSummary>,
+    /// Record created at
+    pub created_at: Option<DateTime<Utc>>,
+    /// Record updated at
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Milestone delivery statistics for a vendor
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VendorMilestoneStats {
+    /// Total milestones (excluding removed)
+    pub total: i64,
+    /// Milestones with a completion event
+    pub completed: i64,
+    /// Milestones with a maturity that are completed or past due
+    pub due: i64,
+    /// Due milestones completed on or before their maturity
+    pub on_time: i64,
+    /// on_time / due (null when no milestone has a maturity yet)
+    pub on_time_rate: Option<f64>,
+}
+
+/// Database row for vendor summary
+#[derive(Debug, FromRow)]
+pub struct VendorSummaryRow {
+    pub id: i32,
+    pub vendor_key: String,
+    pub name: Option<String>,
+    pub payout_address: Option<String>,
+    pub stake_credential: Option<String>,
+    pub project_count: Option<i64>,
+    pub active_project_count: Option<i64>,
+    pub total_allocated_lovelace: Option<i64>,
+    pub total_disbursed_lovelace: Option<i64>,
+    pub current_balance_lovelace: Option<i64>,
+    pub utxo_count: Option<i64>,
+    pub total_milestones: Option<i64>,
+    pub completed_milestones: Option<i64>,
+    pub due_milestones: Option<i64>,
+    pub on_time_milestones: Option<i64>,
+    pub first_fund_time: Option<i64>,
+    pub last_fund_time: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<VendorSummaryRow> for VendorSummary {
+    fn from(row: VendorSummaryRow) -> Self {
+        let allocated = row.total_allocated_lovelace.unwrap_or(0);
+        let disbursed = row.total_disbursed_lovelace.unwrap_or(0);
+        let balance = row.current_balance_lovelace.unwrap_or(0);
+        let disbursement_pct = if allocated > 0 {
+            (disbursed as f64 / allocated as f64) * 100.0
+        } else {
+            0.0
+        };
+        let due = row.due_milestones.unwrap_or(0);
+        let on_time = row.on_time_milestones.unwrap_or(0);
+
+        Self {
+            id: row.id,
+            vendor_key: row.vendor_key,
+            name: row.name,
+            payout_address: row.payout_address,
+            stake_credential: row.stake_credential,
+            project_count: row.project_count.unwrap_or(0),
+            active_project_count: row.active_project_count.unwrap_or(0),
+            financials: VendorFinancials {
+                total_allocated_lovelace: allocated,
+                total_allocated_ada: lovelace_to_ada(allocated),
+                total_disbursed_lovelace: disbursed,
+                total_disbursed_ada: lovelace_to_ada(disbursed),
+                current_balance_lovelace: balance,
+                current_balance_ada: lovelace_to_ada(balance),
+                disbursement_percentage: disbursement_pct,
+                utxo_count: row.utxo_count.unwrap_or(0),
+            },
+            milestones: VendorMilestoneStats {
+                total: row.total_milestones.unwrap_or(0),
+                completed: row.completed_milestones.unwrap_or(0),
+                due,
+                on_time,
+                on_time_rate: if due > 0 { Some(on_time as f64 / due as f64) } else { None },
+            },
+            first_fund_time: row.first_fund_time,
+            last_fund_time: row.last_fund_time,
         }
     }
 }
@@ -553,7 +785,9 @@ pub struct MilestoneResponse {
     pub amount_lovelace: Option<i64>,
     /// Allocated amount in ADA
     pub amount_ada: Option<f64>,
-    /// Milestone status (pending/completed/disbursed)
+    /// Scheduled completion time (Unix timestamp, from milestone maturity)
+    pub maturity: Option<i64>,
+    /// Milestone status (pending/completed/disbursed/withdrawn/removed)
     pub status: String,
     /// Completion details
     pub completion: Option<MilestoneCompletion>,
@@ -561,6 +795,8 @@ pub struct MilestoneResponse {
     pub disbursement: Option<MilestoneDisbursement>,
     /// Project reference
     pub project: ProjectReference,
+    /// Operator corrections and notes; null if there are none
+    pub curation: Option<Curation>,
 }
 
 /// Milestone completion details
@@ -610,6 +846,7 @@ pub struct MilestoneRow {
     pub description: Option<String>,
     pub acceptance_criteria: Option<String>,
     pub amount_lovelace: Option<i64>,
+    pub maturity: Option<i64>,
     pub status: String,
     pub complete_tx_hash: Option<String>,
     pub complete_time: Option<i64>,
@@ -647,6 +884,7 @@ impl From<MilestoneRow> for MilestoneResponse {
             acceptance_criteria: row.acceptance_criteria,
             amount_lovelace: row.amount_lovelace,
             amount_ada: row.amount_lovelace.map(lovelace_to_ada),
+            maturity: row.maturity,
             status: row.status,
             completion,
             disbursement,
@@ -654,10 +892,158 @@ impl From<MilestoneRow> for MilestoneResponse {
                 project_id: row.project_id,
                 project_name: row.project_name,
             },
+            curation: None,
         }
     }
 }
 
+// ============================================================================
+// EVIDENCE
+// ============================================================================
+
+/// Completion evidence entry, normalised from the milestone's evidence JSON
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EvidenceResponse {
+    /// Position in the original evidence list
+    pub position: i32,
+    /// Evidence label
+    pub label: Option<String>,
+    /// Evidence URL as published (http(s):// or ipfs://)
+    pub url: Option<String>,
+    /// IPFS CID (from an ipfs:// URL, a gateway URL or a bare CID)
+    pub ipfs_cid: Option<String>,
+    /// Content hash declared in the metadata (hex)
+    pub declared_hash: Option<String>,
+    /// Algorithm of the declared hash (sha256/blake2b-256)
+    pub hash_algorithm: Option<String>,
+    /// Archive status (pending/archived/verified/mismatch/unavailable/unresolvable)
+    pub status: String,
+    /// Archived copy, once fetched
+    pub archive: Option<EvidenceArchiveInfo>,
+    /// Number of fetch attempts
+    pub fetch_attempts: i32,
+    /// Last fetch attempt (Unix timestamp)
+    pub last_checked_at: Option<i64>,
+}
+
+/// Archived copy of an evidence document
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EvidenceArchiveInfo {
+    /// SHA-256 of the archived content (hex)
+    pub content_hash: String,
+    /// Size in bytes
+    pub size_bytes: i64,
+    /// Media type
+    pub media_type: Option<String>,
+    /// Time the copy was archived
+    pub archived_at: Option<DateTime<Utc>>,
+    /// API path serving the archived content
+    pub content_url: String,
+}
+
+/// Database row for milestone evidence
+#[derive(Debug, FromRow)]
+pub struct EvidenceRow {
+    pub milestone_id: i32,
+    pub position: i32,
+    pub label: Option<String>,
+    pub url: Option<String>,
+    pub ipfs_cid: Option<String>,
+    pub declared_hash: Option<String>,
+    pub hash_algorithm: Option<String>,
+    pub status: String,
+    pub fetch_attempts: i32,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub content_hash: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub media_type: Option<String>,
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+impl From<EvidenceRow> for EvidenceResponse {
+    fn from(row: EvidenceRow) -> Self {
+        let archive = row.content_hash.map(|hash| EvidenceArchiveInfo {
+            content_url: format!("/api/v1/milestones/{}/evidence/{}", row.milestone_id, hash),
+            content_hash: hash,
+            size_bytes: row.size_bytes.unwrap_or(0),
+            media_type: row.media_type,
+            archived_at: row.archived_at,
+        });
+
+        Self {
+            position: row.position,
+            label: row.label,
+            url: row.url,
+            ipfs_cid: row.ipfs_cid,
+            declared_hash: row.declared_hash,
+            hash_algorithm: row.hash_algorithm,
+            status: row.status,
+            archive,
+            fetch_attempts: row.fetch_attempts,
+            last_checked_at: row.last_checked_at.map(|t| t.timestamp()),
+        }
+    }
+}
+
+// ============================================================================
+// CONTRACT REVISIONS
+// ============================================================================
+
+/// Vendor contract revision (fund or modify) with changes from the previous revision
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ContractRevisionResponse {
+    /// Revision number (1 = original fund terms)
+    pub revision: i32,
+    /// Event type that produced this revision (fund/modify)
+    pub event_type: String,
+    /// Transaction hash
+    pub tx_hash: String,
+    /// Slot number
+    pub slot: Option<i64>,
+    /// Block time (Unix timestamp)
+    pub block_time: Option<i64>,
+    /// Reason given in the modify metadata
+    pub reason: Option<String>,
+    /// Contract-level field changes
+    pub changes: Vec<FieldChange>,
+    /// Milestone schedule changes
+    pub milestone_changes: Vec<MilestoneChange>,
+}
+
+/// A single changed field between two revisions
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FieldChange {
+    /// Field name
+    pub field: String,
+    /// Value in the previous revision
+    pub previous: Option<serde_json::Value>,
+    /// Value in this revision
+    pub current: Option<serde_json::Value>,
+}
+
+/// Milestone change between two revisions
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MilestoneChange {
+    /// Logical milestone identifier
+    pub milestone_id: String,
+    /// Change kind (added/removed/modified)
+    pub change: String,
+    /// Changed milestone fields
+    pub fields: Vec<FieldChange>,
+}
+
+/// Database row for contract revision
+#[derive(Debug, FromRow)]
+pub struct ContractRevisionRow {
+    pub revision: i32,
+    pub event_type: String,
+    pub tx_hash: String,
+    pub slot: Option<i64>,
+    pub block_time: Option<i64>,
+    pub reason: Option<String>,
+    pub snapshot: serde_json::Value,
+}
+
 // ============================================================================
 // EVENTS
 // ============================================================================
@@ -795,6 +1181,70 @@ impl From<EventWithContextRow> for EventResponse {
     }
 }
 
+/// Submitted TOM transaction and whether it has reached the chain
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PendingEventResponse {
+    /// Transaction hash
+    pub tx_hash: String,
+    /// pending, confirmed or expired
+    pub status: String,
+    /// Event type (publish/initialize/fund/complete/disburse/etc.)
+    pub event_type: String,
+    /// Treasury contract instance named in the metadata
+    pub treasury_instance: Option<String>,
+    /// Project ID named in the metadata
+    pub project_id: Option<String>,
+    /// Amount in lovelace (disburse and withdraw only)
+    pub amount_lovelace: Option<i64>,
+    /// Amount in ADA (disburse and withdraw only)
+    pub amount_ada: Option<f64>,
+    /// First slot the transaction is no longer valid in
+    pub invalid_hereafter: Option<i64>,
+    /// Slot of the confirming event
+    pub slot: Option<i64>,
+    /// Raw metadata
+    pub metadata_raw: serde_json::Value,
+    /// When the transaction was first seen
+    pub submitted_at: DateTime<Utc>,
+    /// When the transaction was confirmed or expired
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Database row for a pending transaction
+#[derive(Debug, FromRow)]
+pub struct PendingTransactionRow {
+    pub tx_hash: String,
+    pub status: String,
+    pub event_type: String,
+    pub instance: Option<String>,
+    pub project_id: Option<String>,
+    pub amount_lovelace: Option<i64>,
+    pub invalid_hereafter: Option<i64>,
+    pub metadata: serde_json::Value,
+    pub submitted_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub slot: Option<i64>,
+}
+
+impl From<PendingTransactionRow> for PendingEventResponse {
+    fn from(row: PendingTransactionRow) -> Self {
+        Self {
+            tx_hash: row.tx_hash,
+            status: row.status,
+            event_type: row.event_type,
+            treasury_instance: row.instance,
+            project_id: row.project_id,
+            amount_lovelace: row.amount_lovelace,
+            amount_ada: row.amount_lovelace.map(lovelace_to_ada),
+            invalid_hereafter: row.invalid_hereafter,
+            slot: row.slot,
+            metadata_raw: row.metadata,
+            submitted_at: row.submitted_at,
+            resolved_at: row.resolved_at,
+        }
+    }
+}
+
 // ============================================================================
 // UTXOS
 // ============================================================================
@@ -847,6 +1297,71 @@ impl From<UtxoRow> for UtxoResponse {
     }
 }
 
+// ============================================================================
+// SEARCH
+// ============================================================================
+
+/// Full-text search hit (project, milestone or event)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchResult {
+    /// Result type (project/milestone/event)
+    pub result_type: String,
+    /// Internal database ID of the matched record
+    pub id: i32,
+    /// Relevance score (higher is better)
+    pub rank: f32,
+    /// Title of the matched record (project name, milestone label or event type)
+    pub title: Option<String>,
+    /// Matching fragments with terms wrapped in <mark></mark>
+    pub highlight: Option<String>,
+    /// Project ID the result belongs to
+    pub project_id: Option<String>,
+    /// Project name
+    pub project_name: Option<String>,
+    /// Logical milestone identifier (milestone results)
+    pub milestone_id: Option<String>,
+    /// Transaction hash (event results)
+    pub tx_hash: Option<String>,
+    /// Event type (event results)
+    pub event_type: Option<String>,
+    /// Time of the record (fund, completion or block time; Unix timestamp)
+    pub time: Option<i64>,
+}
+
+/// Database row for search hit
+#[derive(Debug, FromRow)]
+pub struct SearchResultRow {
+    pub result_type: String,
+    pub id: i32,
+    pub rank: f32,
+    pub title: Option<String>,
+    pub highlight: Option<String>,
+    pub project_id: Option<String>,
+    pub project_name: Option<String>,
+    pub milestone_id: Option<String>,
+    pub tx_hash: Option<String>,
+    pub event_type: Option<String>,
+    pub time: Option<i64>,
+}
+
+impl From<SearchResultRow> for SearchResult {
+    fn from(row: SearchResultRow) -> Self {
+        Self {
+            result_type: row.result_type,
+            id: row.id,
+            rank: row.rank,
+            title: row.title,
+            highlight: row.highlight,
+            project_id: row.project_id,
+            project_name: row.project_name,
+            milestone_id: row.milestone_id,
+            tx_hash: row.tx_hash,
+            event_type: row.event_type,
+            time: row.time,
+        }
+    }
+}
+
 // ============================================================================
 // STATISTICS
 // ============================================================================
@@ -892,6 +1407,8 @@ pub struct ProjectStats {
     pub paused_count: i64,
     /// Cancelled projects
     pub cancelled_count: i64,
+    /// Projects swept back to the treasury
+    pub swept_count: i64,
 }
 
 /// Milestone statistics
@@ -942,6 +1459,197 @@ pub struct SyncStats {
     pub last_block: Option<i64>,
     /// Last sync time
     pub last_updated: Option<DateTime<Utc>>,
+    /// How far sync trails the indexer and the wall clock
+    pub lag: SyncLag,
+}
+
+// ============================================================================
+// API KEYS
+// ============================================================================
+
+/// What an API key may do. Every caller, with or without a key, has
+/// `public-read`; `admin` implies every other scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiScope {
+    /// Read the public API
+    PublicRead,
+    /// Download bulk exports
+    Export,
+    /// Use the admin API, including key management
+    Admin,
+}
+
+impl ApiScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PublicRead => "public-read",
+            Self::Export => "export",
+            Self::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "public-read" => Some(Self::PublicRead),
+            "export" => Some(Self::Export),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// An issued API key (the key itself is never stored)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: i32,
+    /// Who or what the key was issued to
+    pub name: String,
+    /// First characters of the key, to recognise it
+    pub key_prefix: String,
+    pub scopes: Vec<ApiScope>,
+    /// Requests per minute; null uses the default for keys
+    pub rate_limit_per_minute: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    /// Set once revoked; revoked keys are rejected
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Request to issue an API key
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IssueApiKeyRequest {
+    /// Who or what the key is for
+    pub name: String,
+    /// Scopes to grant (at least one)
+    pub scopes: Vec<ApiScope>,
+    /// Requests per minute, overriding the default for keys
+    pub rate_limit_per_minute: Option<i32>,
+}
+
+/// A newly issued API key
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IssuedApiKeyResponse {
+    /// The key, shown only this once; send it in the `X-API-Key` header
+    pub key: String,
+    #[serde(flatten)]
+    pub details: ApiKeyResponse,
+}
+
+// ============================================================================
+// CURATION
+// ============================================================================
+
+/// Operator corrections to a vendor contract or milestone. Overridden fields
+/// carry the curated value in place of the chain-derived one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct Curation {
+    /// Fields replaced by an override
+    pub overrides: Vec<CuratedField>,
+    /// Operator notes
+    pub annotations: Vec<AnnotationResponse>,
+}
+
+/// An overridden field
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CuratedField {
+    /// Field name, e.g. "project_name"
+    pub field: String,
+    /// Value derived from chain metadata
+    pub chain_value: Option<String>,
+    /// Why the value was corrected
+    pub reason: String,
+    pub author: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A field override
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, FromRow)]
+pub struct OverrideResponse {
+    pub id: i32,
+    /// Logical project identifier
+    pub project_id: String,
+    /// Logical milestone identifier; null for the vendor contract itself
+    pub milestone_id: Option<String>,
+    pub field: String,
+    /// Value returned in place of the chain-derived one
+    pub value: String,
+    pub reason: String,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An operator note on a vendor contract or milestone
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, FromRow)]
+pub struct AnnotationResponse {
+    pub id: i32,
+    /// Logical project identifier
+    pub project_id: String,
+    /// Logical milestone identifier; null for the vendor contract itself
+    pub milestone_id: Option<String>,
+    pub note: String,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A change to overrides or annotations
+#[derive(Debug, Serialize, Deserialize, ToSchema, FromRow)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    /// set_override, remove_override, add_annotation or remove_annotation
+    pub action: String,
+    pub project_id: String,
+    pub milestone_id: Option<String>,
+    /// Overridden field (overrides only)
+    pub field: Option<String>,
+    /// Value or note before the change
+    pub old_value: Option<String>,
+    /// Value or note after the change
+    pub new_value: Option<String>,
+    pub reason: Option<String>,
+    /// Person responsible, as given in the request
+    pub author: String,
+    /// Credential used: "key:<prefix>" or "admin-token"
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Request to set (or replace) a field override
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetOverrideRequest {
+    /// Logical project identifier
+    pub project_id: String,
+    /// Logical milestone identifier; omit to override a vendor contract field
+    pub milestone_id: Option<String>,
+    /// Vendor contracts: project_name, description, vendor_name or
+    /// contract_url. Milestones: label, description or acceptance_criteria.
+    pub field: String,
+    pub value: String,
+    /// Why the chain value is wrong
+    pub reason: String,
+    /// Person responsible for the change
+    pub author: String,
+}
+
+/// Request to add an annotation
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddAnnotationRequest {
+    /// Logical project identifier
+    pub project_id: String,
+    /// Logical milestone identifier; omit to annotate the vendor contract
+    pub milestone_id: Option<String>,
+    pub note: String,
+    /// Person responsible for the note
+    pub author: String,
+}
+
+/// Request to remove an override or annotation
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RemoveCurationRequest {
+    /// Why it is removed
+    pub reason: String,
+    /// Person responsible for the change
+    pub author: String,
 }
 
 // ============================================================================
@@ -951,41 +1659,322 @@ pub struct SyncStats {
 fn default_page() -> u32 { 1 }
 fn default_limit() -> u32 { 50 }
 
+/// Largest accepted `limit`
+pub const MAX_LIMIT: u32 = 100;
+
+/// Largest accepted `hours` for recent events (one week)
+pub const MAX_RECENT_HOURS: u32 = 168;
+
+/// Sort order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// Vendor contract (project) status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatus {
+    Active,
+    Paused,
+    Completed,
+    Cancelled,
+    Swept,
+}
+
+impl ProjectStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Paused => "paused",
+            Self::Completed => "completed",
+            Self::Cancelled => "cancelled",
+            Self::Swept => "swept",
+        }
+    }
+}
+
+/// Milestone status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneStatus {
+    Pending,
+    Completed,
+    Disbursed,
+    Withdrawn,
+    Removed,
+}
+
+impl MilestoneStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Completed => "completed",
+            Self::Disbursed => "disbursed",
+            Self::Withdrawn => "withdrawn",
+            Self::Removed => "removed",
+        }
+    }
+}
+
+/// TOM event type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    Publish,
+    Initialize,
+    Fund,
+    Complete,
+    Disburse,
+    Withdraw,
+    Pause,
+    Resume,
+    Modify,
+    Cancel,
+    Sweep,
+    Reorganize,
+}
+
+impl EventType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Publish => "publish",
+            Self::Initialize => "initialize",
+            Self::Fund => "fund",
+            Self::Complete => "complete",
+            Self::Disburse => "disburse",
+            Self::Withdraw => "withdraw",
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Modify => "modify",
+            Self::Cancel => "cancel",
+            Self::Sweep => "sweep",
+            Self::Reorganize => "reorganize",
+        }
+    }
+}
+
+/// Status of a submitted transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingStatus {
+    #[default]
+    Pending,
+    Confirmed,
+    Expired,
+}
+
+impl PendingStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Confirmed => "confirmed",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+/// Vendor contract sort field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VendorContractSort {
+    #[default]
+    FundTime,
+    ProjectId,
+    ProjectName,
+    InitialAmount,
+}
+
+impl VendorContractSort {
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            Self::FundTime => "fund_block_time",
+            Self::ProjectId => "project_id",
+            Self::ProjectName => "project_name",
+            Self::InitialAmount => "initial_amount_lovelace",
+        }
+    }
+}
+
+/// Vendor sort field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VendorSort {
+    Name,
+    ProjectCount,
+    #[default]
+    TotalAllocated,
+    LastFundTime,
+}
+
+impl VendorSort {
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::ProjectCount => "project_count",
+            Self::TotalAllocated => "total_allocated_lovelace",
+            Self::LastFundTime => "last_fund_time",
+        }
+    }
+}
+
+/// Milestone sort field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneSort {
+    /// Project, then milestone order
+    #[default]
+    MilestoneOrder,
+    CompleteTime,
+    DisburseTime,
+    Amount,
+}
+
+impl MilestoneSort {
+    /// ORDER BY clause; `order` applies to the sort field (the default
+    /// milestone order is ascending, the others descending)
+    pub fn as_sql(self, order: Option<SortOrder>) -> String {
+        match self {
+            Self::MilestoneOrder => {
+                let order = order.unwrap_or(SortOrder::Asc).as_sql();
+                format!("vc.project_id {0}, m.milestone_order {0}", order)
+            }
+            Self::CompleteTime => format!("m.complete_time {} NULLS LAST", order.unwrap_or_default().as_sql()),
+            Self::DisburseTime => format!("m.disburse_time {} NULLS LAST", order.unwrap_or_default().as_sql()),
+            Self::Amount => format!("m.amount_lovelace {} NULLS LAST", order.unwrap_or_default().as_sql()),
+        }
+    }
+}
+
+/// Search result type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchResultType {
+    Project,
+    Milestone,
+    Event,
+}
+
+impl SearchResultType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Project => "project",
+            Self::Milestone => "milestone",
+            Self::Event => "event",
+        }
+    }
+}
+
 /// Vendor contracts query parameters
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
 pub struct VendorContractsQuery {
     /// Page number (1-indexed)
     #[serde(default = "default_page")]
+    #[param(minimum = 1)]
     pub page: u32,
     /// Items per page
     #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
     pub limit: u32,
-    /// Filter by status (active/paused/completed/cancelled)
-    pub status: Option<String>,
+    /// Filter by status
+    #[param(inline)]
+    pub status: Option<ProjectStatus>,
     /// Search in project_id, project_name, description, vendor_name
     pub search: Option<String>,
-    /// Sort field (fund_time, project_id, project_name)
-    pub sort: Option<String>,
-    /// Sort order (asc/desc, default: desc)
-    pub order: Option<String>,
+    /// Sort field (default: fund_time)
+    #[param(inline)]
+    pub sort: Option<VendorContractSort>,
+    /// Sort order (default: desc)
+    #[param(inline)]
+    pub order: Option<SortOrder>,
     /// Filter by fund time (Unix timestamp, from)
     pub from_time: Option<i64>,
     /// Filter by fund time (Unix timestamp, to)
     pub to_time: Option<i64>,
 }
 
+/// Vendors query parameters
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct VendorsQuery {
+    /// Page number (1-indexed)
+    #[serde(default = "default_page")]
+    #[param(minimum = 1)]
+    pub page: u32,
+    /// Items per page
+    #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
+    pub limit: u32,
+    /// Search in vendor name, payout address, stake credential
+    pub search: Option<String>,
+    /// Sort field (default: total_allocated)
+    #[param(inline)]
+    pub sort: Option<VendorSort>,
+    /// Sort order (default: desc)
+    #[param(inline)]
+    pub order: Option<SortOrder>,
+}
+
+/// Curation audit log query parameters
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct AuditLogQuery {
+    /// Page number (1-indexed)
+    #[serde(default = "default_page")]
+    #[param(minimum = 1)]
+    pub page: u32,
+    /// Items per page
+    #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
+    pub limit: u32,
+    /// Only changes to this project and its milestones
+    pub project_id: Option<String>,
+}
+
+/// Search query parameters
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct SearchQuery {
+    /// Search terms (web search syntax: quoted phrases, OR, -exclude)
+    pub q: String,
+    /// Restrict to one result type
+    #[serde(rename = "type")]
+    #[param(inline)]
+    pub result_type: Option<SearchResultType>,
+    /// Page number (1-indexed)
+    #[serde(default = "default_page")]
+    #[param(minimum = 1)]
+    pub page: u32,
+    /// Items per page
+    #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
+    pub limit: u32,
+}
+
 /// Events query parameters
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
 pub struct EventsQuery {
     /// Page number (1-indexed)
     #[serde(default = "default_page")]
+    #[param(minimum = 1)]
     pub page: u32,
     /// Items per page
     #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
     pub limit: u32,
     /// Filter by event type
     #[serde(rename = "type")]
-    pub event_type: Option<String>,
+    #[param(inline)]
+    pub event_type: Option<EventType>,
     /// Filter by project ID
     pub project_id: Option<String>,
     /// Filter by time (Unix timestamp, from)
@@ -999,32 +1988,61 @@ pub struct EventsQuery {
 pub struct RecentEventsQuery {
     /// Hours to look back (default: 24)
     #[serde(default = "default_hours")]
+    #[param(minimum = 1, maximum = 168)]
     pub hours: u32,
     /// Maximum number of events (default: 50)
     #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
     pub limit: u32,
     /// Filter by event type
     #[serde(rename = "type")]
-    pub event_type: Option<String>,
+    #[param(inline)]
+    pub event_type: Option<EventType>,
 }
 
 fn default_hours() -> u32 { 24 }
 
+/// Pending events query parameters
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct PendingEventsQuery {
+    /// Page number (1-indexed)
+    #[serde(default = "default_page")]
+    #[param(minimum = 1)]
+    pub page: u32,
+    /// Items per page
+    #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
+    pub limit: u32,
+    /// Filter by status (default: pending)
+    #[serde(default)]
+    #[param(inline)]
+    pub status: PendingStatus,
+    /// Filter by project ID
+    pub project_id: Option<String>,
+}
+
 /// Milestones query parameters
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
 pub struct MilestonesQuery {
     /// Page number (1-indexed)
     #[serde(default = "default_page")]
+    #[param(minimum = 1)]
     pub page: u32,
     /// Items per page
     #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
     pub limit: u32,
-    /// Filter by status (pending/completed/disbursed)
-    pub status: Option<String>,
+    /// Filter by status
+    #[param(inline)]
+    pub status: Option<MilestoneStatus>,
     /// Filter by project ID
     pub project_id: Option<String>,
-    /// Sort field (milestone_order, complete_time, disburse_time)
-    pub sort: Option<String>,
+    /// Sort field (default: milestone_order)
+    #[param(inline)]
+    pub sort: Option<MilestoneSort>,
+    /// Sort order (default: asc for milestone_order, desc otherwise)
+    #[param(inline)]
+    pub order: Option<SortOrder>,
 }
 
 /// Project events query parameters
@@ -1032,11 +2050,14 @@ pub struct MilestonesQuery {
 pub struct ProjectEventsQuery {
     /// Page number (1-indexed)
     #[serde(default = "default_page")]
+    #[param(minimum = 1)]
     pub page: u32,
     /// Items per page
     #[serde(default = "default_limit")]
+    #[param(minimum = 1, maximum = 100)]
     pub limit: u32,
     /// Filter by event type
     #[serde(rename = "type")]
-    pub event_type: Option<String>,
+    #[param(inline)]
+    pub event_type: Option<EventType>,
 }
@@ -3,17 +3,25 @@
 use utoipa::OpenApi;
 
 use crate::models::v1::{
-    ApiResponse, EventMilestoneContext, EventProjectContext, EventResponse, EventStats,
-    EventTreasuryContext, EventsQuery, FinancialStats, MilestoneCompletion, MilestoneDisbursement,
-    MilestoneResponse, MilestoneStats, MilestonesSummary, MilestonesQuery, PaginatedResponse,
-    Pagination, ProjectEventsQuery, ProjectReference, ProjectStats, RecentEventsQuery,
-    ResponseMeta, StatisticsResponse, StatusResponse, SyncStats, TreasuryFinancials,
-    TreasuryReference, TreasuryResponse, TreasuryStatistics, TreasuryStats, UtxoResponse,
-    VendorContractDetail, VendorContractSummary, VendorContractsQuery, VendorFinancials,
+    AddAnnotationRequest, AnnotationResponse, ApiKeyResponse, ApiResponse, ApiScope, AuditLogEntry,
+    AuditLogQuery, CuratedField, Curation, ContractRevisionResponse, ErrorBody, ErrorResponse, EventMilestoneContext,
+    EventProjectContext, EventResponse, EventsQuery, EventStats, EventTreasuryContext, EventType,
+    EvidenceArchiveInfo, EvidenceResponse, FieldChange, FinancialStats, HealthCheck, HealthChecks,
+    HealthResponse, HealthStatus, IssueApiKeyRequest, IssuedApiKeyResponse, MilestoneChange,
+    MilestoneCompletion, MilestoneDisbursement, MilestoneResponse, MilestoneSort, MilestonesQuery,
+    MilestonesSummary, MilestoneStats, MilestoneStatus, OverrideResponse, PaginatedResponse, Pagination,
+    PendingEventResponse, PendingEventsQuery, PendingStatus, ProjectEventsQuery, ProjectReference, ProjectStats,
+    ProjectStatus, RecentEventsQuery,
+    RemoveCurationRequest, ResponseMeta, SearchQuery, SearchResult, SearchResultType, SortOrder, StatisticsResponse,
+    SetOverrideRequest, StatusResponse, SyncLag, SyncStats, TreasuryFinancials, TreasuryReference, TreasuryResponse,
+    TreasuryStatistics, TreasuryStats, UtxoResponse, VendorContractDetail, VendorContractSort,
+    VendorContractsQuery, VendorContractSummary, VendorDetail, VendorFinancials,
+    VendorMilestoneStats, VendorSort, VendorsQuery, VendorSummary,
 };
 
+use crate::routes::health;
 use crate::routes::v1::{
-    events, milestones, statistics, status, treasury, vendor_contracts,
+    admin, curation, events, export, milestones, search, statistics, status, treasury, vendor_contracts, vendors,
 };
 
 #[derive(OpenApi)]
@@ -21,7 +29,7 @@ use crate::routes::v1::{
     info(
         title = "Cardano Treasury API",
         version = "1.0.0",
-        description = "REST API for tracking Cardano treasury contracts and fund disbursements.\n\n## Overview\n\nThis API provides access to treasury contract data, vendor contracts (projects), milestones, and event history for the Cardano treasury system.\n\n## Key Concepts\n\n- **Treasury Contract (TRSC)**: The root treasury reserve contract that holds funds\n- **Vendor Contract (PSSC)**: Project-specific contracts that receive funding from the treasury\n- **Milestone**: Individual deliverables within a vendor contract\n- **Event**: Audit log of all treasury operations (fund, complete, disburse, etc.)\n\n## Response Format\n\nAll responses use a consistent envelope format:\n\n```json\n{\n  \"data\": { ... },\n  \"pagination\": { ... },  // Only for paginated endpoints\n  \"meta\": {\n    \"timestamp\": \"2026-01-28T10:30:00Z\"\n  }\n}\n```\n\n## Amounts\n\nAll monetary amounts are provided in both lovelace (smallest unit) and ADA:\n- `amount_lovelace`: Integer amount in lovelace\n- `amount_ada`: Float amount in ADA (1 ADA = 1,000,000 lovelace)",
+        description = "REST API for tracking Cardano treasury contracts and fund disbursements.\n\n## Overview\n\nThis API provides access to treasury contract data, vendor contracts (projects), milestones, and event history for the Cardano treasury system.\n\n## Key Concepts\n\n- **Treasury Contract (TRSC)**: The root treasury reserve contract that holds funds\n- **Vendor Contract (PSSC)**: Project-specific contracts that receive funding from the treasury\n- **Milestone**: Individual deliverables within a vendor contract\n- **Event**: Audit log of all treasury operations (fund, complete, disburse, etc.)\n\n## Response Format\n\nAll responses use a consistent envelope format:\n\n```json\n{\n  \"data\": { ... },\n  \"pagination\": { ... },  // Only for paginated endpoints\n  \"meta\": {\n    \"timestamp\": \"2026-01-28T10:30:00Z\"\n  }\n}\n```\n\nErrors use the same envelope with `error` in place of `data`:\n\n```json\n{\n  \"error\": {\n    \"code\": \"not_found\",\n    \"message\": \"Milestone 42 not found\",\n    \"correlation_id\": \"6f1c2a9e-...\"\n  },\n  \"meta\": { ... }\n}\n```\n\nError codes: `not_found` (404), `invalid_parameter` (400, with `parameter`), `unauthorized` (401), `forbidden` (403), `conflict` (409), `rate_limited` (429), `unavailable` (503) and `internal_error` (500). The correlation id is also returned in the `X-Request-Id` header (a client-supplied `X-Request-Id` is reused) and appears in the server logs.\n\n## Amounts\n\nAll monetary amounts are provided in both lovelace (smallest unit) and ADA:\n- `amount_lovelace`: Integer amount in lovelace\n- `amount_ada`: Float amount in ADA (1 ADA = 1,000,000 lovelace)",
         license(
             name = "Apache 2.0",
             url = "https://www.apache.org/licenses/LICENSE-2.0"
@@ -37,11 +45,17 @@ use crate::routes::v1::{
         (name = "Status", description = "API health and status endpoints"),
         (name = "Treasury", description = "Treasury contract endpoints"),
         (name = "Vendor Contracts", description = "Vendor contract (project) endpoints"),
+        (name = "Vendors", description = "Vendor endpoints (projects grouped by payout credential)"),
         (name = "Milestones", description = "Milestone endpoints"),
         (name = "Events", description = "Event log endpoints"),
-        (name = "Statistics", description = "Aggregated statistics endpoints")
+        (name = "Search", description = "Full-text search across projects, milestones and events"),
+        (name = "Statistics", description = "Aggregated statistics endpoints"),
+        (name = "Export", description = "Bulk exports (require an API key with the export scope)"),
+        (name = "Admin", description = "Operator endpoints (require an admin API key or the admin bearer token)")
     ),
     paths(
+        health::live,
+        health::ready,
         status::get_status,
         treasury::get_treasury,
         treasury::get_treasury_utxos,
@@ -51,12 +65,31 @@ use crate::routes::v1::{
         vendor_contracts::get_vendor_contract_milestones,
         vendor_contracts::get_vendor_contract_events,
         vendor_contracts::get_vendor_contract_utxos,
+        vendor_contracts::get_vendor_contract_revisions,
+        vendors::list_vendors,
+        vendors::get_vendor,
         milestones::list_milestones,
         milestones::get_milestone,
+        milestones::get_milestone_evidence,
+        milestones::get_milestone_evidence_content,
         events::list_events,
         events::get_recent_events,
+        events::get_pending_events,
         events::get_event,
+        search::search,
         statistics::get_statistics,
+        export::export,
+        admin::get_config,
+        admin::list_api_keys,
+        admin::issue_api_key,
+        admin::revoke_api_key,
+        curation::list_overrides,
+        curation::set_override,
+        curation::remove_override,
+        curation::list_annotations,
+        curation::add_annotation,
+        curation::remove_annotation,
+        curation::get_audit_log,
     ),
     components(
         schemas(
@@ -65,16 +98,32 @@ use crate::routes::v1::{
             ApiResponse<VendorContractDetail>,
             ApiResponse<Vec<MilestoneResponse>>,
             ApiResponse<Vec<UtxoResponse>>,
+            ApiResponse<Vec<ContractRevisionResponse>>,
+            ApiResponse<VendorDetail>,
             ApiResponse<Vec<EventResponse>>,
             ApiResponse<EventResponse>,
             ApiResponse<MilestoneResponse>,
+            ApiResponse<Vec<EvidenceResponse>>,
             ApiResponse<StatisticsResponse>,
             ApiResponse<StatusResponse>,
+            ApiResponse<Vec<ApiKeyResponse>>,
+            ApiResponse<ApiKeyResponse>,
+            ApiResponse<IssuedApiKeyResponse>,
+            ApiResponse<Vec<OverrideResponse>>,
+            ApiResponse<OverrideResponse>,
+            ApiResponse<Vec<AnnotationResponse>>,
+            ApiResponse<AnnotationResponse>,
+            PaginatedResponse<Vec<AuditLogEntry>>,
             PaginatedResponse<Vec<VendorContractSummary>>,
+            PaginatedResponse<Vec<VendorSummary>>,
             PaginatedResponse<Vec<MilestoneResponse>>,
             PaginatedResponse<Vec<EventResponse>>,
+            PaginatedResponse<Vec<PendingEventResponse>>,
+            PaginatedResponse<Vec<SearchResult>>,
             Pagination,
             ResponseMeta,
+            ErrorResponse,
+            ErrorBody,
             // Treasury
             TreasuryResponse,
             TreasuryStatistics,
@@ -85,16 +134,28 @@ use crate::routes::v1::{
             VendorFinancials,
             MilestonesSummary,
             TreasuryReference,
+            ContractRevisionResponse,
+            FieldChange,
+            MilestoneChange,
+            // Vendors
+            VendorSummary,
+            VendorDetail,
+            VendorMilestoneStats,
             // Milestones
             MilestoneResponse,
             MilestoneCompletion,
             MilestoneDisbursement,
             ProjectReference,
+            EvidenceResponse,
+            EvidenceArchiveInfo,
             // Events
             EventResponse,
+            PendingEventResponse,
             EventTreasuryContext,
             EventProjectContext,
             EventMilestoneContext,
+            // Search
+            SearchResult,
             // UTXOs
             UtxoResponse,
             // Statistics
@@ -107,12 +168,45 @@ use crate::routes::v1::{
             SyncStats,
             // Status
             StatusResponse,
+            SyncLag,
+            HealthResponse,
+            HealthChecks,
+            HealthCheck,
+            HealthStatus,
+            // API keys
+            ApiScope,
+            ApiKeyResponse,
+            IssueApiKeyRequest,
+            IssuedApiKeyResponse,
+            // Curation
+            Curation,
+            CuratedField,
+            OverrideResponse,
+            AnnotationResponse,
+            AuditLogEntry,
+            SetOverrideRequest,
+            AddAnnotationRequest,
+            RemoveCurationRequest,
+            // Query parameter values
+            SortOrder,
+            ProjectStatus,
+            MilestoneStatus,
+            EventType,
+            PendingStatus,
+            VendorContractSort,
+            VendorSort,
+            MilestoneSort,
+            SearchResultType,
             // Query params
             VendorContractsQuery,
+            VendorsQuery,
             EventsQuery,
             RecentEventsQuery,
+            PendingEventsQuery,
             MilestonesQuery,
             ProjectEventsQuery,
+            SearchQuery,
+            AuditLogQuery,
         )
     )
 )]
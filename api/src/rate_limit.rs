@@ -0,0 +1,84 @@
+//! Token-bucket rate limiting
+//!
+//! Each client (API key, or IP address for anonymous requests) has a bucket
+//! holding up to a minute's worth of requests that refills continuously.
+//! Buckets live in process, so with several API instances behind a load
+//! balancer each instance enforces the limit separately.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Above this many buckets, full (idle) ones are dropped
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Who a bucket belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Client {
+    Key(i32),
+    Ip(IpAddr),
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Outcome of taking a token
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    /// Requests per minute
+    pub limit: u32,
+    /// Requests left right now
+    pub remaining: u32,
+    /// Seconds until the bucket is full again
+    pub reset_secs: u64,
+    /// Seconds until the next request is allowed (0 if allowed now)
+    pub retry_after_secs: u64,
+}
+
+/// Buckets for every client, shared by all requests
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    buckets: Arc<Mutex<HashMap<Client, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take one token from `client`'s bucket, refilling at `per_minute`
+    pub fn check(&self, client: Client, per_minute: u32) -> Decision {
+        let capacity = f64::from(per_minute.max(1));
+        let per_sec = capacity / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|_, b| b.tokens + now.duration_since(b.updated).as_secs_f64() * per_sec < capacity);
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_sec).min(capacity);
+        bucket.updated = now;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        Decision {
+            allowed,
+            limit: per_minute,
+            remaining: bucket.tokens.floor() as u32,
+            reset_secs: ((capacity - bucket.tokens) / per_sec).ceil() as u64,
+            retry_after_secs: if allowed { 0 } else { ((1.0 - bucket.tokens) / per_sec).ceil() as u64 },
+        }
+    }
+}
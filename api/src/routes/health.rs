@@ -0,0 +1,130 @@
+//! Liveness and readiness probes
+//!
+//! `/health/live` only shows the process is serving requests. `/health/ready`
+//! checks what the API needs to return current data, so orchestrators can stop
+//! routing to an instance whose database is down or whose data is stale.
+
+use axum::{extract::State, http::StatusCode, response::Json, routing::get, Router};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::models::v1::{HealthCheck, HealthChecks, HealthResponse, HealthStatus};
+use crate::services::heartbeat;
+use crate::state::AppState;
+
+/// Upper bound on each check, so a hung database fails the probe instead of
+/// stalling it
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/health", get(health_check))
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+}
+
+/// Plain-text health check, kept for existing monitors
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+/// Liveness probe
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    responses(
+        (status = 200, description = "The process is serving requests", body = HealthResponse)
+    ),
+    tag = "Status"
+)]
+pub async fn live() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: HealthStatus::Ok,
+        checks: None,
+    })
+}
+
+/// Readiness probe
+///
+/// Checks database connectivity, the chain source, the sync heartbeat and
+/// sync lag. Returns 503 if any check fails.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "All checks passed", body = HealthResponse),
+        (status = 503, description = "At least one check failed", body = HealthResponse)
+    ),
+    tag = "Status"
+)]
+pub async fn ready(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
+    let pool = &state.pool;
+    let chain = state.chain.as_ref();
+    let (database, chain_source, progress) = tokio::join!(
+        bounded(sqlx::query("SELECT 1").execute(pool)),
+        bounded(chain.check()),
+        bounded(heartbeat::progress(pool, chain)),
+    );
+
+    let health = &state.config.health;
+    let checks = HealthChecks {
+        database: match database {
+            Ok(_) => pass("connected".to_string()),
+            Err(e) => fail(e),
+        },
+        chain_source: match chain_source {
+            Ok(detail) => pass(format!("{}: {}", chain.name(), detail)),
+            Err(e) => fail(format!("{}: {}", chain.name(), e)),
+        },
+        sync_heartbeat: match progress {
+            Ok(ref p) => match (p.heartbeat_age_secs(), p.heartbeat.as_ref()) {
+                (Some(age), Some(beat)) if age <= health.max_heartbeat_age_secs as i64 => {
+                    pass(format!("last cycle {}s ago by {}", age, beat.worker))
+                }
+                (Some(age), _) => fail(format!("last cycle {}s ago (max {}s)", age, health.max_heartbeat_age_secs)),
+                _ => fail("no sync cycle has completed".to_string()),
+            },
+            Err(ref e) => fail(e.clone()),
+        },
+        sync_lag: match progress {
+            Ok(ref p) if p.heartbeat.is_none() => fail("no sync cycle has completed".to_string()),
+            Ok(p) => match p.slots_behind_tip() {
+                Some(lag) if lag <= health.max_lag_slots => pass(format!("{} slots behind the indexer tip", lag)),
+                Some(lag) => fail(format!(
+                    "{} slots behind the indexer tip (max {})",
+                    lag, health.max_lag_slots
+                )),
+                None => fail("the indexer has no blocks".to_string()),
+            },
+            Err(e) => fail(e),
+        },
+    };
+
+    let all_ok = [&checks.database, &checks.chain_source, &checks.sync_heartbeat, &checks.sync_lag]
+        .iter()
+        .all(|c| c.status == HealthStatus::Ok);
+    let (code, status) = if all_ok {
+        (StatusCode::OK, HealthStatus::Ok)
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, HealthStatus::Fail)
+    };
+
+    (code, Json(HealthResponse { status, checks: Some(checks) }))
+}
+
+/// Run a check under `CHECK_TIMEOUT`, reducing failures to a message
+async fn bounded<T, E: std::fmt::Display>(check: impl Future<Output = Result<T, E>>) -> Result<T, String> {
+    match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", CHECK_TIMEOUT.as_secs())),
+    }
+}
+
+fn pass(detail: String) -> HealthCheck {
+    HealthCheck { status: HealthStatus::Ok, detail }
+}
+
+fn fail(detail: String) -> HealthCheck {
+    HealthCheck { status: HealthStatus::Fail, detail }
+}
@@ -1,5 +1,6 @@
 //! API Routes
 //!
-//! All routes are under the v1 namespace.
+//! Data routes are under the v1 namespace; health probes sit at the root.
 
+pub mod health;
 pub mod v1;
@@ -0,0 +1,183 @@
+//! Admin endpoints
+//!
+//! Open to API keys with the `admin` scope, and, when `admin.token` is set,
+//! to requests sending it as `Authorization: Bearer <token>`. Without a
+//! configured token other callers get 404 on every admin path, as if it did
+//! not exist.
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, State},
+    http::{header, request::Parts, StatusCode},
+    response::Json,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::auth::{Auth, Caller};
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::models::v1::{ApiKeyResponse, ApiResponse, ApiScope, IssueApiKeyRequest, IssuedApiKeyResponse};
+use crate::services::api_keys;
+
+use super::extract::{self, Path};
+
+/// Extractor admitting only admin keys and requests carrying the admin token
+pub struct AdminAuth {
+    /// Credential used, for audit logs: `key:<prefix>` or `admin-token`
+    pub actor: String,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AdminAuth
+where
+    Arc<Config>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let caller = Caller::from_request_parts(parts, state).await?;
+        if let Some(key) = caller.key.as_ref().filter(|_| caller.has_scope(ApiScope::Admin)) {
+            return Ok(AdminAuth {
+                actor: format!("key:{}", key.key_prefix),
+            });
+        }
+
+        let config = Arc::<Config>::from_ref(state);
+        let Some(ref expected) = config.admin.token else {
+            return Err(ApiError::not_found("No such endpoint"));
+        };
+
+        let supplied = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+
+        match supplied {
+            Some(supplied) if constant_time_eq(supplied.as_bytes(), expected.as_bytes()) => Ok(AdminAuth {
+                actor: "admin-token".to_string(),
+            }),
+            Some(_) => Err(ApiError::Unauthorized("Invalid admin token".to_string())),
+            None if caller.key.is_some() => Err(ApiError::Forbidden("API key lacks the 'admin' scope".to_string())),
+            None => Err(ApiError::Unauthorized("Missing bearer token".to_string())),
+        }
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Get the effective configuration
+///
+/// Returns the merged configuration (defaults, config file, environment and
+/// flags) the server is running with. The database password and admin token
+/// are redacted. Requires an admin key or the admin token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/config",
+    responses(
+        (status = 200, description = "Effective configuration (redacted)", body = Object),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "Admin API disabled", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn get_config(
+    _auth: AdminAuth,
+    State(config): State<Arc<Config>>,
+) -> Result<Json<ApiResponse<Config>>, ApiError> {
+    Ok(Json(ApiResponse::new(config.redacted())))
+}
+
+/// List API keys
+///
+/// Every issued key, newest first, including revoked ones. Keys themselves
+/// are never stored, only their first characters.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/api-keys",
+    responses(
+        (status = 200, description = "API keys", body = ApiResponse<Vec<ApiKeyResponse>>),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "Admin API disabled", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn list_api_keys(
+    _auth: AdminAuth,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<Vec<ApiKeyResponse>>>, ApiError> {
+    Ok(Json(ApiResponse::new(api_keys::list(&pool).await?)))
+}
+
+/// Issue an API key
+///
+/// The response contains the key itself, which is not shown again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/api-keys",
+    request_body = IssueApiKeyRequest,
+    responses(
+        (status = 201, description = "Key issued", body = ApiResponse<IssuedApiKeyResponse>),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "Admin API disabled", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn issue_api_key(
+    _auth: AdminAuth,
+    State(pool): State<PgPool>,
+    extract::Json(request): extract::Json<IssueApiKeyRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<IssuedApiKeyResponse>>), ApiError> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::invalid_parameter("name", "must not be empty"));
+    }
+    if request.scopes.is_empty() {
+        return Err(ApiError::invalid_parameter("scopes", "at least one scope is required"));
+    }
+    if request.rate_limit_per_minute.is_some_and(|n| n < 1) {
+        return Err(ApiError::invalid_parameter("rate_limit_per_minute", "must be at least 1"));
+    }
+
+    let (details, key) = api_keys::issue(&pool, name, &request.scopes, request.rate_limit_per_minute).await?;
+    tracing::info!("Issued API key {} ({}) to '{}'", details.id, details.key_prefix, details.name);
+    Ok((StatusCode::CREATED, Json(ApiResponse::new(IssuedApiKeyResponse { key, details }))))
+}
+
+/// Revoke an API key
+///
+/// Takes effect immediately on this instance, and on other instances within
+/// their key cache lifetime (30 seconds). Revoking a revoked key is a no-op.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/api-keys/{id}",
+    params(("id" = i32, Path, description = "API key id")),
+    responses(
+        (status = 200, description = "Key revoked", body = ApiResponse<ApiKeyResponse>),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "No such key, or admin API disabled", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn revoke_api_key(
+    _auth: AdminAuth,
+    State(pool): State<PgPool>,
+    State(auth): State<Auth>,
+    Path(id): Path<i32>,
+) -> Result<Json<ApiResponse<ApiKeyResponse>>, ApiError> {
+    let revoked = api_keys::revoke(&pool, id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("API key {} not found", id)))?;
+    auth.clear_keys();
+    tracing::info!("Revoked API key {} ({})", revoked.id, revoked.key_prefix);
+    Ok(Json(ApiResponse::new(revoked)))
+}
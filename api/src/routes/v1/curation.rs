@@ -0,0 +1,240 @@
+//! Curation admin endpoints
+//!
+//! Overrides and annotations on vendor contracts and milestones, and the
+//! audit log of changes to them. Guarded like the rest of the admin API (see
+//! [`AdminAuth`]); curated values themselves are public, in the `curation`
+//! field of vendor contract and milestone responses.
+
+use axum::{extract::State, http::StatusCode, response::Json};
+use sqlx::PgPool;
+
+use super::admin::AdminAuth;
+use super::extract::{self, pagination, Path, Query};
+use crate::error::ApiError;
+use crate::models::v1::{
+    AddAnnotationRequest, AnnotationResponse, ApiResponse, AuditLogEntry, AuditLogQuery, OverrideResponse,
+    PaginatedResponse, RemoveCurationRequest, SetOverrideRequest,
+};
+use crate::services::curation::{self, CONTRACT_FIELDS, MILESTONE_FIELDS};
+
+/// Reject blank required text fields
+fn required(parameter: &str, value: &str) -> Result<(), ApiError> {
+    if value.trim().is_empty() {
+        return Err(ApiError::invalid_parameter(parameter, "must not be empty"));
+    }
+    Ok(())
+}
+
+/// 404 unless the vendor contract (and milestone) exists
+async fn check_target(pool: &PgPool, project_id: &str, milestone_id: Option<&str>) -> Result<(), ApiError> {
+    if curation::target_exists(pool, project_id, milestone_id).await? {
+        return Ok(());
+    }
+    Err(match milestone_id {
+        Some(milestone_id) => ApiError::not_found(format!(
+            "Vendor contract {} has no milestone {}",
+            project_id, milestone_id
+        )),
+        None => ApiError::not_found(format!("Vendor contract {} not found", project_id)),
+    })
+}
+
+/// List overrides
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/overrides",
+    responses(
+        (status = 200, description = "Every field override", body = ApiResponse<Vec<OverrideResponse>>),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "Admin API disabled", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn list_overrides(
+    _auth: AdminAuth,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<Vec<OverrideResponse>>>, ApiError> {
+    Ok(Json(ApiResponse::new(curation::list_overrides(&pool).await?)))
+}
+
+/// Set a field override
+///
+/// Replaces a chain-derived field of a vendor contract (omit `milestone_id`)
+/// or milestone in every response, until removed. Setting a field that is
+/// already overridden replaces the override.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/overrides",
+    request_body = SetOverrideRequest,
+    responses(
+        (status = 200, description = "Override set", body = ApiResponse<OverrideResponse>),
+        (status = 400, description = "Invalid request body or field", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "No such vendor contract or milestone", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn set_override(
+    auth: AdminAuth,
+    State(pool): State<PgPool>,
+    extract::Json(request): extract::Json<SetOverrideRequest>,
+) -> Result<Json<ApiResponse<OverrideResponse>>, ApiError> {
+    required("reason", &request.reason)?;
+    required("author", &request.author)?;
+    let fields = if request.milestone_id.is_some() { MILESTONE_FIELDS } else { CONTRACT_FIELDS };
+    if !fields.contains(&request.field.as_str()) {
+        return Err(ApiError::invalid_parameter(
+            "field",
+            format!("must be one of {}", fields.join(", ")),
+        ));
+    }
+    check_target(&pool, &request.project_id, request.milestone_id.as_deref()).await?;
+
+    let row = curation::set_override(&pool, &request, &auth.actor).await?;
+    tracing::info!(
+        "Override {} set on {} {:?} {} by {} ({})",
+        row.id, row.project_id, row.milestone_id, row.field, row.author, auth.actor
+    );
+    Ok(Json(ApiResponse::new(row)))
+}
+
+/// Remove a field override
+///
+/// Responses show the chain-derived value again.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/overrides/{id}",
+    params(("id" = i32, Path, description = "Override id")),
+    request_body = RemoveCurationRequest,
+    responses(
+        (status = 200, description = "Override removed", body = ApiResponse<OverrideResponse>),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "No such override", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn remove_override(
+    auth: AdminAuth,
+    State(pool): State<PgPool>,
+    Path(id): Path<i32>,
+    extract::Json(request): extract::Json<RemoveCurationRequest>,
+) -> Result<Json<ApiResponse<OverrideResponse>>, ApiError> {
+    required("reason", &request.reason)?;
+    required("author", &request.author)?;
+    let row = curation::remove_override(&pool, id, &request, &auth.actor)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Override {} not found", id)))?;
+    tracing::info!("Override {} removed by {} ({})", id, request.author, auth.actor);
+    Ok(Json(ApiResponse::new(row)))
+}
+
+/// List annotations
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/annotations",
+    responses(
+        (status = 200, description = "Every annotation", body = ApiResponse<Vec<AnnotationResponse>>),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "Admin API disabled", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn list_annotations(
+    _auth: AdminAuth,
+    State(pool): State<PgPool>,
+) -> Result<Json<ApiResponse<Vec<AnnotationResponse>>>, ApiError> {
+    Ok(Json(ApiResponse::new(curation::list_annotations(&pool).await?)))
+}
+
+/// Add an annotation
+///
+/// Attaches a note to a vendor contract (omit `milestone_id`) or milestone,
+/// shown in its `curation.annotations`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/annotations",
+    request_body = AddAnnotationRequest,
+    responses(
+        (status = 201, description = "Annotation added", body = ApiResponse<AnnotationResponse>),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "No such vendor contract or milestone", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn add_annotation(
+    auth: AdminAuth,
+    State(pool): State<PgPool>,
+    extract::Json(request): extract::Json<AddAnnotationRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<AnnotationResponse>>), ApiError> {
+    required("note", &request.note)?;
+    required("author", &request.author)?;
+    check_target(&pool, &request.project_id, request.milestone_id.as_deref()).await?;
+
+    let row = curation::add_annotation(&pool, &request, &auth.actor).await?;
+    tracing::info!("Annotation {} added to {} by {} ({})", row.id, row.project_id, row.author, auth.actor);
+    Ok((StatusCode::CREATED, Json(ApiResponse::new(row))))
+}
+
+/// Remove an annotation
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/annotations/{id}",
+    params(("id" = i32, Path, description = "Annotation id")),
+    request_body = RemoveCurationRequest,
+    responses(
+        (status = 200, description = "Annotation removed", body = ApiResponse<AnnotationResponse>),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "No such annotation", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn remove_annotation(
+    auth: AdminAuth,
+    State(pool): State<PgPool>,
+    Path(id): Path<i32>,
+    extract::Json(request): extract::Json<RemoveCurationRequest>,
+) -> Result<Json<ApiResponse<AnnotationResponse>>, ApiError> {
+    required("reason", &request.reason)?;
+    required("author", &request.author)?;
+    let row = curation::remove_annotation(&pool, id, &request, &auth.actor)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Annotation {} not found", id)))?;
+    tracing::info!("Annotation {} removed by {} ({})", id, request.author, auth.actor);
+    Ok(Json(ApiResponse::new(row)))
+}
+
+/// Get the curation audit log
+///
+/// Every change to overrides and annotations, newest first, with the author
+/// named in the request and the credential used.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-log",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Audit log entries", body = PaginatedResponse<Vec<AuditLogEntry>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorResponse),
+        (status = 403, description = "API key lacks the admin scope", body = ErrorResponse),
+        (status = 404, description = "Admin API disabled", body = ErrorResponse)
+    ),
+    tag = "Admin"
+)]
+pub async fn get_audit_log(
+    _auth: AdminAuth,
+    State(pool): State<PgPool>,
+    Query(params): Query<AuditLogQuery>,
+) -> Result<Json<PaginatedResponse<Vec<AuditLogEntry>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
+    let (entries, total) = curation::audit_log(&pool, params.project_id.as_deref(), limit as i64, offset).await?;
+    Ok(Json(PaginatedResponse::new(entries, page, limit, total)))
+}
@@ -1,15 +1,13 @@
 //! Events endpoints
 
-use axum::{
-    extract::{Extension, Path, Query},
-    http::StatusCode,
-    response::Json,
-};
-use sqlx::PgPool;
+use axum::{extract::State, response::Json};
 
+use super::extract::{pagination, time_range, Path, Query};
+use crate::db::ReadPool;
+use crate::error::ApiError;
 use crate::models::v1::{
     ApiResponse, EventResponse, EventWithContextRow, EventsQuery, PaginatedResponse,
-    RecentEventsQuery,
+    PendingEventResponse, PendingEventsQuery, PendingTransactionRow, RecentEventsQuery, MAX_RECENT_HOURS,
 };
 
 /// List all events
@@ -20,17 +18,18 @@ use crate::models::v1::{
     path = "/api/v1/events",
     params(EventsQuery),
     responses(
-        (status = 200, description = "List of events", body = PaginatedResponse<Vec<EventResponse>>)
+        (status = 200, description = "List of events", body = PaginatedResponse<Vec<EventResponse>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Events"
 )]
 pub async fn list_events(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Query(params): Query<EventsQuery>,
-) -> Result<Json<PaginatedResponse<Vec<EventResponse>>>, StatusCode> {
-    let page = params.page.max(1);
-    let limit = params.limit.min(100).max(1);
-    let offset = ((page - 1) * limit) as i64;
+) -> Result<Json<PaginatedResponse<Vec<EventResponse>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
+    time_range(params.from_time, params.to_time)?;
     let limit_i64 = limit as i64;
 
     // Build dynamic query based on filters
@@ -72,7 +71,7 @@ pub async fn list_events(
     let mut count_q = sqlx::query_as::<_, (i64,)>(&count_query);
 
     if let Some(ref event_type) = params.event_type {
-        count_q = count_q.bind(event_type);
+        count_q = count_q.bind(event_type.as_str());
     }
     if let Some(ref project_id) = params.project_id {
         count_q = count_q.bind(project_id);
@@ -86,11 +85,7 @@ pub async fn list_events(
 
     let (total_count,) = count_q
         .fetch_one(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     // Get data
     let data_query = format!(
@@ -109,7 +104,7 @@ pub async fn list_events(
     let mut data_q = sqlx::query_as::<_, EventWithContextRow>(&data_query);
 
     if let Some(ref event_type) = params.event_type {
-        data_q = data_q.bind(event_type);
+        data_q = data_q.bind(event_type.as_str());
     }
     if let Some(ref project_id) = params.project_id {
         data_q = data_q.bind(project_id);
@@ -125,11 +120,7 @@ pub async fn list_events(
         .bind(limit_i64)
         .bind(offset)
         .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     let events: Vec<EventResponse> = rows.into_iter().map(EventResponse::from).collect();
     Ok(Json(PaginatedResponse::new(events, page, limit, total_count)))
@@ -143,21 +134,30 @@ pub async fn list_events(
     path = "/api/v1/events/recent",
     params(RecentEventsQuery),
     responses(
-        (status = 200, description = "Recent events", body = ApiResponse<Vec<EventResponse>>)
+        (status = 200, description = "Recent events", body = ApiResponse<Vec<EventResponse>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Events"
 )]
 pub async fn get_recent_events(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Query(params): Query<RecentEventsQuery>,
-) -> Result<Json<ApiResponse<Vec<EventResponse>>>, StatusCode> {
-    let hours = params.hours.max(1).min(168); // Max 1 week
-    let limit = params.limit.min(100).max(1) as i64;
+) -> Result<Json<ApiResponse<Vec<EventResponse>>>, ApiError> {
+    if !(1..=MAX_RECENT_HOURS).contains(&params.hours) {
+        return Err(ApiError::invalid_parameter(
+            "hours",
+            format!("must be between 1 and {}", MAX_RECENT_HOURS),
+        ));
+    }
+    let (_, limit, _) = pagination(1, params.limit)?;
+    let hours = params.hours;
+    let limit = limit as i64;
 
     // Calculate cutoff time (hours ago from now)
     let cutoff_seconds = (hours as i64) * 3600;
 
-    let rows = if let Some(ref event_type) = params.event_type {
+    let rows = if let Some(event_type) = params.event_type {
         sqlx::query_as::<_, EventWithContextRow>(
             r#"
             SELECT *
@@ -169,7 +169,7 @@ pub async fn get_recent_events(
             "#
         )
         .bind(cutoff_seconds)
-        .bind(event_type)
+        .bind(event_type.as_str())
         .bind(limit)
         .fetch_all(&pool)
         .await
@@ -189,15 +189,67 @@ pub async fn get_recent_events(
         .await
     };
 
-    let rows = rows.map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let rows = rows?;
 
     let events: Vec<EventResponse> = rows.into_iter().map(EventResponse::from).collect();
     Ok(Json(ApiResponse::new(events)))
 }
 
+/// List submitted events
+///
+/// Returns TOM transactions that have been submitted but not yet processed
+/// from a block, newest first. Confirmed and expired transactions can be
+/// listed with `status`. Empty unless the pending feed is enabled.
+#[utoipa::path(
+    get,
+    path = "/api/v1/events/pending",
+    params(PendingEventsQuery),
+    responses(
+        (status = 200, description = "Submitted events", body = PaginatedResponse<Vec<PendingEventResponse>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Events"
+)]
+pub async fn get_pending_events(
+    State(ReadPool(pool)): State<ReadPool>,
+    Query(params): Query<PendingEventsQuery>,
+) -> Result<Json<PaginatedResponse<Vec<PendingEventResponse>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
+
+    let (total_count,): (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*)
+        FROM treasury.pending_transactions
+        WHERE status = $1 AND ($2::TEXT IS NULL OR project_id = $2)
+        "#
+    )
+    .bind(params.status.as_str())
+    .bind(&params.project_id)
+    .fetch_one(&pool)
+    .await?;
+
+    let rows = sqlx::query_as::<_, PendingTransactionRow>(
+        r#"
+        SELECT tx_hash, status, event_type, instance, project_id, amount_lovelace,
+               invalid_hereafter, metadata, submitted_at, resolved_at, slot
+        FROM treasury.pending_transactions
+        WHERE status = $1 AND ($2::TEXT IS NULL OR project_id = $2)
+        ORDER BY submitted_at DESC, tx_hash
+        LIMIT $3 OFFSET $4
+        "#
+    )
+    .bind(params.status.as_str())
+    .bind(&params.project_id)
+    .bind(limit as i64)
+    .bind(offset)
+    .fetch_all(&pool)
+    .await?;
+
+    let events: Vec<PendingEventResponse> = rows.into_iter().map(PendingEventResponse::from).collect();
+    Ok(Json(PaginatedResponse::new(events, page, limit, total_count)))
+}
+
 /// Get a specific event by transaction hash
 ///
 /// Returns detailed information about a specific event.
@@ -209,14 +261,16 @@ pub async fn get_recent_events(
     ),
     responses(
         (status = 200, description = "Event details", body = ApiResponse<EventResponse>),
-        (status = 404, description = "Event not found")
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Event not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Events"
 )]
 pub async fn get_event(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Path(tx_hash): Path<String>,
-) -> Result<Json<ApiResponse<EventResponse>>, StatusCode> {
+) -> Result<Json<ApiResponse<EventResponse>>, ApiError> {
     let row = sqlx::query_as::<_, EventWithContextRow>(
         r#"
         SELECT *
@@ -226,12 +280,8 @@ pub async fn get_event(
     )
     .bind(&tx_hash)
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?
-    .ok_or(StatusCode::NOT_FOUND)?;
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Event {} not found", tx_hash)))?;
 
     Ok(Json(ApiResponse::new(EventResponse::from(row))))
 }
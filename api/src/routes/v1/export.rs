@@ -0,0 +1,108 @@
+//! Bulk export endpoints
+//!
+//! The same data sets as `treasury-cli export`, for API keys with the
+//! `export` scope.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use futures_util::stream::{self, StreamExt};
+use serde::Deserialize;
+use std::io;
+use tokio::sync::mpsc;
+
+use crate::auth::Caller;
+use crate::db::ReadPool;
+use crate::error::ApiError;
+use crate::models::v1::ApiScope;
+use crate::services::maintenance::{self, ExportEntity, ExportFormat};
+
+use super::extract::{Path, Query};
+
+/// Chunks read ahead of a slow client
+const EXPORT_BUFFER_CHUNKS: usize = 8;
+
+/// Export query parameters
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ExportQuery {
+    pub format: ExportFormat,
+}
+
+/// Export a data set
+///
+/// Every row of a data set as JSON lines (one object per line) or CSV with a
+/// header row, streamed as it is read. Requires an API key with the `export`
+/// scope. Exports are never cached.
+#[utoipa::path(
+    get,
+    path = "/api/v1/export/{entity}",
+    params(
+        ("entity" = String, Path, description = "vendor-contracts, vendors, milestones, events or utxos"),
+        ("format" = Option<String>, Query, description = "jsonl (default) or csv")
+    ),
+    responses(
+        (status = 200, description = "Exported rows", content_type = "application/x-ndjson"),
+        (status = 400, description = "Unknown data set or format", body = ErrorResponse),
+        (status = 401, description = "Missing API key", body = ErrorResponse),
+        (status = 403, description = "API key lacks the export scope", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Export"
+)]
+pub async fn export(
+    caller: Caller,
+    State(ReadPool(pool)): State<ReadPool>,
+    Path(entity): Path<ExportEntity>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    caller.require(ApiScope::Export)?;
+
+    // Rows are read by a separate task and sent on as the client takes them, so
+    // memory use does not grow with the table and the request timeout only
+    // covers the wait for the first chunk
+    let (sender, mut receiver) = mpsc::channel::<Result<Vec<u8>, io::Error>>(EXPORT_BUFFER_CHUNKS);
+    let format = query.format;
+    tokio::spawn(async move {
+        let sent = &sender;
+        let result = maintenance::export_chunks(&pool, entity, format, |chunk| async move {
+            sent.send(Ok(chunk)).await.map_err(|_| anyhow::anyhow!("client disconnected"))
+        })
+        .await;
+        if let Err(e) = result {
+            if sender.is_closed() {
+                tracing::debug!("Export of {:?} abandoned by the client", entity);
+            } else {
+                tracing::error!("Export of {:?} failed: {:#}", entity, e);
+                // Aborts the response, so the client cannot mistake it for a complete export
+                let _ = sender.send(Err(io::Error::other(format!("export failed: {:#}", e)))).await;
+            }
+        }
+    });
+
+    // A failure before the first row can still be reported as an error response
+    let first = match receiver.recv().await {
+        Some(Err(e)) => return Err(ApiError::Internal(format!("Export failed: {}", e))),
+        first => first,
+    };
+    let rest = stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    });
+    let body = Body::from_stream(stream::iter(first).chain(rest));
+
+    let content_type = match query.format {
+        ExportFormat::Jsonl => "application/x-ndjson",
+        ExportFormat::Csv => "text/csv; charset=utf-8",
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        body,
+    )
+        .into_response())
+}
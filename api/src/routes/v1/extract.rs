@@ -0,0 +1,137 @@
+//! Request extractors and parameter validation
+//!
+//! Drop-in replacements for axum's `Path`, `Query` and `Json` that reject with an
+//! [`ApiError`] naming the offending parameter, instead of axum's plain-text
+//! rejection bodies, plus checks shared by the list endpoints.
+
+use axum::{
+    async_trait,
+    extract::{path::ErrorKind, rejection::PathRejection, FromRequest, FromRequestParts, RawPathParams, Request},
+    http::request::Parts,
+};
+use serde::de::DeserializeOwned;
+
+use crate::error::ApiError;
+use crate::models::v1::MAX_LIMIT;
+
+/// Path parameters
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(axum::extract::Path(value)) => Ok(Self(value)),
+            Err(PathRejection::FailedToDeserializePathParams(e)) => {
+                // Positional errors (single values and tuples) don't carry the key
+                let index = match e.kind() {
+                    ErrorKind::ParseErrorAtKey { key, .. }
+                    | ErrorKind::InvalidUtf8InPathParam { key } => {
+                        return Err(ApiError::invalid_parameter(key.clone(), e.kind().to_string()));
+                    }
+                    ErrorKind::ParseErrorAtIndex { index, .. } => *index,
+                    _ => 0,
+                };
+                let parameter = RawPathParams::from_request_parts(parts, state)
+                    .await
+                    .ok()
+                    .and_then(|params| params.iter().nth(index).map(|(key, _)| key.to_string()))
+                    .unwrap_or_else(|| "path".to_string());
+                Err(ApiError::invalid_parameter(parameter, e.kind().to_string()))
+            }
+            Err(e) => Err(ApiError::Internal(format!("Path extraction failed: {}", e))),
+        }
+    }
+}
+
+/// Query string parameters
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+        let deserializer = serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+
+        serde_path_to_error::deserialize(deserializer).map(Self).map_err(|e| {
+            let message = e.inner().to_string();
+            let parameter = match e.path().to_string() {
+                // Missing fields are reported at the root: "missing field `q`"
+                path if path == "." => message.split('`').nth(1).unwrap_or("query").to_string(),
+                path => path,
+            };
+            ApiError::invalid_parameter(parameter, message)
+        })
+    }
+}
+
+/// JSON request body
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match axum::Json::<T>::from_request(request, state).await {
+            Ok(axum::Json(value)) => Ok(Self(value)),
+            Err(e) => Err(ApiError::invalid_parameter("body", e.body_text())),
+        }
+    }
+}
+
+/// Validate `page` and `limit`, returning them with the row offset of the page
+pub fn pagination(page: u32, limit: u32) -> Result<(u32, u32, i64), ApiError> {
+    if page < 1 {
+        return Err(ApiError::invalid_parameter("page", "must be 1 or greater"));
+    }
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::invalid_parameter(
+            "limit",
+            format!("must be between 1 and {}", MAX_LIMIT),
+        ));
+    }
+    Ok((page, limit, i64::from(page - 1) * i64::from(limit)))
+}
+
+/// Validate that `from_time` is not after `to_time`
+pub fn time_range(from_time: Option<i64>, to_time: Option<i64>) -> Result<(), ApiError> {
+    match (from_time, to_time) {
+        (Some(from), Some(to)) if from > to => Err(ApiError::invalid_parameter(
+            "from_time",
+            format!("from_time ({}) is after to_time ({})", from, to),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pagination_offset_does_not_overflow() {
+        assert_eq!(pagination(1, 50).unwrap(), (1, 50, 0));
+        assert_eq!(pagination(3, 50).unwrap(), (3, 50, 100));
+        let (_, _, offset) = pagination(u32::MAX, MAX_LIMIT).unwrap();
+        assert_eq!(offset, i64::from(u32::MAX - 1) * i64::from(MAX_LIMIT));
+        assert!(pagination(0, 50).is_err());
+        assert!(pagination(1, MAX_LIMIT + 1).is_err());
+    }
+}
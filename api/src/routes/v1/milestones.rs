@@ -1,15 +1,20 @@
 //! Milestones endpoints
 
 use axum::{
-    extract::{Extension, Path, Query},
-    http::StatusCode,
-    response::Json,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Json, Response},
 };
-use sqlx::PgPool;
 
+use super::extract::{pagination, Path, Query};
+use crate::db::ReadPool;
+use crate::error::ApiError;
 use crate::models::v1::{
-    ApiResponse, MilestoneResponse, MilestoneRow, MilestonesQuery, PaginatedResponse,
+    ApiResponse, EvidenceResponse, EvidenceRow, MilestoneResponse, MilestoneRow,
+    MilestonesQuery, PaginatedResponse,
 };
+use crate::services::curation;
+use crate::services::evidence::EvidenceArchive;
 
 /// List all milestones
 ///
@@ -19,17 +24,17 @@ use crate::models::v1::{
     path = "/api/v1/milestones",
     params(MilestonesQuery),
     responses(
-        (status = 200, description = "List of milestones", body = PaginatedResponse<Vec<MilestoneResponse>>)
+        (status = 200, description = "List of milestones", body = PaginatedResponse<Vec<MilestoneResponse>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Milestones"
 )]
 pub async fn list_milestones(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Query(params): Query<MilestonesQuery>,
-) -> Result<Json<PaginatedResponse<Vec<MilestoneResponse>>>, StatusCode> {
-    let page = params.page.max(1);
-    let limit = params.limit.min(100).max(1);
-    let offset = ((page - 1) * limit) as i64;
+) -> Result<Json<PaginatedResponse<Vec<MilestoneResponse>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
     let limit_i64 = limit as i64;
 
     // Build dynamic query based on filters
@@ -53,12 +58,7 @@ pub async fn list_milestones(
     };
 
     // Determine sort order
-    let sort_clause = match params.sort.as_deref() {
-        Some("complete_time") => "m.complete_time DESC NULLS LAST",
-        Some("disburse_time") => "m.disburse_time DESC NULLS LAST",
-        Some("amount") => "m.amount_lovelace DESC NULLS LAST",
-        _ => "vc.project_id, m.milestone_order",
-    };
+    let sort_clause = params.sort.unwrap_or_default().as_sql(params.order);
 
     // Get total count
     let count_query = format!(
@@ -74,7 +74,7 @@ pub async fn list_milestones(
     let mut count_q = sqlx::query_as::<_, (i64,)>(&count_query);
 
     if let Some(ref status) = params.status {
-        count_q = count_q.bind(status);
+        count_q = count_q.bind(status.as_str());
     }
     if let Some(ref project_id) = params.project_id {
         count_q = count_q.bind(project_id);
@@ -82,11 +82,7 @@ pub async fn list_milestones(
 
     let (total_count,) = count_q
         .fetch_one(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     // Get data
     let data_query = format!(
@@ -100,6 +96,7 @@ pub async fn list_milestones(
             m.description,
             m.acceptance_criteria,
             m.amount_lovelace,
+            m.maturity,
             m.status,
             m.complete_tx_hash,
             m.complete_time,
@@ -125,7 +122,7 @@ pub async fn list_milestones(
     let mut data_q = sqlx::query_as::<_, MilestoneRow>(&data_query);
 
     if let Some(ref status) = params.status {
-        data_q = data_q.bind(status);
+        data_q = data_q.bind(status.as_str());
     }
     if let Some(ref project_id) = params.project_id {
         data_q = data_q.bind(project_id);
@@ -135,13 +132,10 @@ pub async fn list_milestones(
         .bind(limit_i64)
         .bind(offset)
         .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
-    let milestones: Vec<MilestoneResponse> = rows.into_iter().map(MilestoneResponse::from).collect();
+    let mut milestones: Vec<MilestoneResponse> = rows.into_iter().map(MilestoneResponse::from).collect();
+    curation::apply(&pool, &mut milestones).await?;
     Ok(Json(PaginatedResponse::new(milestones, page, limit, total_count)))
 }
 
@@ -156,14 +150,16 @@ pub async fn list_milestones(
     ),
     responses(
         (status = 200, description = "Milestone details", body = ApiResponse<MilestoneResponse>),
-        (status = 404, description = "Milestone not found")
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Milestone not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Milestones"
 )]
 pub async fn get_milestone(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Path(id): Path<i32>,
-) -> Result<Json<ApiResponse<MilestoneResponse>>, StatusCode> {
+) -> Result<Json<ApiResponse<MilestoneResponse>>, ApiError> {
     let row = sqlx::query_as::<_, MilestoneRow>(
         r#"
         SELECT
@@ -175,6 +171,7 @@ pub async fn get_milestone(
             m.description,
             m.acceptance_criteria,
             m.amount_lovelace,
+            m.maturity,
             m.status,
             m.complete_tx_hash,
             m.complete_time,
@@ -192,12 +189,130 @@ pub async fn get_milestone(
     )
     .bind(id)
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?
-    .ok_or(StatusCode::NOT_FOUND)?;
-
-    Ok(Json(ApiResponse::new(MilestoneResponse::from(row))))
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Milestone {} not found", id)))?;
+
+    let mut milestone = MilestoneResponse::from(row);
+    curation::apply(&pool, std::slice::from_mut(&mut milestone)).await?;
+    Ok(Json(ApiResponse::new(milestone)))
+}
+
+/// Get a milestone's completion evidence
+///
+/// Returns the evidence entries from the completion metadata as typed records,
+/// with archive and hash verification status.
+#[utoipa::path(
+    get,
+    path = "/api/v1/milestones/{id}/evidence",
+    params(
+        ("id" = i32, Path, description = "Milestone database ID")
+    ),
+    responses(
+        (status = 200, description = "Milestone evidence", body = ApiResponse<Vec<EvidenceResponse>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Milestone not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Milestones"
+)]
+pub async fn get_milestone_evidence(
+    State(ReadPool(pool)): State<ReadPool>,
+    Path(id): Path<i32>,
+) -> Result<Json<ApiResponse<Vec<EvidenceResponse>>>, ApiError> {
+    // Check milestone exists
+    let exists: Option<i32> = sqlx::query_scalar("SELECT id FROM treasury.milestones WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?;
+
+    if exists.is_none() {
+        return Err(ApiError::not_found(format!("Milestone {} not found", id)));
+    }
+
+    let rows = sqlx::query_as::<_, EvidenceRow>(
+        r#"
+        SELECT
+            me.milestone_id,
+            me.position,
+            me.label,
+            me.url,
+            me.ipfs_cid,
+            me.declared_hash,
+            me.hash_algorithm,
+            me.status,
+            me.fetch_attempts,
+            me.last_checked_at,
+            me.content_hash,
+            ea.size_bytes,
+            ea.media_type,
+            ea.archived_at
+        FROM treasury.milestone_evidence me
+        LEFT JOIN treasury.evidence_archive ea ON ea.content_hash = me.content_hash
+        WHERE me.milestone_id = $1
+        ORDER BY me.position
+        "#
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await?;
+
+    let evidence: Vec<EvidenceResponse> = rows.into_iter().map(EvidenceResponse::from).collect();
+    Ok(Json(ApiResponse::new(evidence)))
+}
+
+/// Get archived evidence content
+///
+/// Serves the archived copy of one of the milestone's evidence documents.
+#[utoipa::path(
+    get,
+    path = "/api/v1/milestones/{id}/evidence/{content_hash}",
+    params(
+        ("id" = i32, Path, description = "Milestone database ID"),
+        ("content_hash" = String, Path, description = "SHA-256 of the archived content")
+    ),
+    responses(
+        (status = 200, description = "Archived evidence document", content_type = "application/octet-stream"),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "No archived copy for this milestone", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Milestones"
+)]
+pub async fn get_milestone_evidence_content(
+    State(ReadPool(pool)): State<ReadPool>,
+    State(archive): State<EvidenceArchive>,
+    Path((id, content_hash)): Path<(i32, String)>,
+) -> Result<Response, ApiError> {
+    let content_hash = content_hash.to_ascii_lowercase();
+
+    // Only serve documents referenced by this milestone
+    let (media_type,): (Option<String>,) = sqlx::query_as(
+        r#"
+        SELECT ea.media_type
+        FROM treasury.milestone_evidence me
+        JOIN treasury.evidence_archive ea ON ea.content_hash = me.content_hash
+        WHERE me.milestone_id = $1 AND me.content_hash = $2
+        LIMIT 1
+        "#
+    )
+    .bind(id)
+    .bind(&content_hash)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Milestone {} has no archived evidence {}", id, content_hash)))?;
+
+    let content = archive
+        .read(&content_hash)
+        .await?
+        .ok_or_else(|| ApiError::Internal(format!("Archived evidence {} is missing from the archive", content_hash)))?;
+
+    let media_type = media_type.unwrap_or_else(|| "application/octet-stream".to_string());
+    Ok((
+        [
+            (header::CONTENT_TYPE, media_type),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable".to_string()),
+        ],
+        content,
+    )
+        .into_response())
 }
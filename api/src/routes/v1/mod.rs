@@ -8,14 +8,23 @@
 
 pub mod treasury;
 pub mod vendor_contracts;
+pub mod vendors;
 pub mod milestones;
 pub mod events;
 pub mod statistics;
+pub mod search;
+pub mod export;
+pub mod extract;
+pub mod admin;
+pub mod curation;
 
-use axum::{routing::get, Router};
+use axum::{routing::{delete, get}, Router};
+
+use crate::error::ApiError;
+use crate::state::AppState;
 
 /// Create the v1 API router
-pub fn router() -> Router {
+pub fn router() -> Router<AppState> {
     Router::new()
         // Status endpoint
         .route("/status", get(status::get_status))
@@ -29,67 +38,96 @@ pub fn router() -> Router {
         .route("/vendor-contracts/:project_id/milestones", get(vendor_contracts::get_vendor_contract_milestones))
         .route("/vendor-contracts/:project_id/events", get(vendor_contracts::get_vendor_contract_events))
         .route("/vendor-contracts/:project_id/utxos", get(vendor_contracts::get_vendor_contract_utxos))
+        .route("/vendor-contracts/:project_id/revisions", get(vendor_contracts::get_vendor_contract_revisions))
+        // Vendors endpoints
+        .route("/vendors", get(vendors::list_vendors))
+        .route("/vendors/:id", get(vendors::get_vendor))
         // Milestones endpoints
         .route("/milestones", get(milestones::list_milestones))
         .route("/milestones/:id", get(milestones::get_milestone))
+        .route("/milestones/:id/evidence", get(milestones::get_milestone_evidence))
+        .route("/milestones/:id/evidence/:content_hash", get(milestones::get_milestone_evidence_content))
         // Events endpoints
         .route("/events", get(events::list_events))
         .route("/events/recent", get(events::get_recent_events))
+        .route("/events/pending", get(events::get_pending_events))
         .route("/events/:tx_hash", get(events::get_event))
+        // Search endpoint
+        .route("/search", get(search::search))
         // Statistics endpoint
         .route("/statistics", get(statistics::get_statistics))
+        // Export endpoint
+        .route("/export/:entity", get(export::export))
+        // Admin endpoints
+        .route("/admin/config", get(admin::get_config))
+        .route("/admin/api-keys", get(admin::list_api_keys).post(admin::issue_api_key))
+        .route("/admin/api-keys/:id", delete(admin::revoke_api_key))
+        .route("/admin/overrides", get(curation::list_overrides).put(curation::set_override))
+        .route("/admin/overrides/:id", delete(curation::remove_override))
+        .route("/admin/annotations", get(curation::list_annotations).post(curation::add_annotation))
+        .route("/admin/annotations/:id", delete(curation::remove_annotation))
+        .route("/admin/audit-log", get(curation::get_audit_log))
+        // Unknown paths under /api/v1 get the error envelope too
+        .fallback(|| async { ApiError::not_found("No such endpoint") })
 }
 
 pub mod status {
-    use axum::{extract::Extension, http::StatusCode, response::Json};
-    use sqlx::PgPool;
+    use axum::{
+        extract::State,
+        http::{header, HeaderValue},
+        response::Json,
+    };
+    use std::sync::Arc;
+
+    use crate::chain::ChainSource;
+    use crate::config::{Config, HealthConfig};
+    use crate::db::ReadPool;
+    use crate::error::ApiError;
+    use crate::models::v1::{ApiResponse, StatusResponse, SyncLag};
+    use crate::services::heartbeat::{self, SyncProgress};
 
-    use crate::models::v1::{ApiResponse, StatusResponse};
+    /// Responses that report live sync lag must not be served from the
+    /// response cache, which only invalidates on data changes
+    pub(crate) const NO_STORE: (header::HeaderName, HeaderValue) =
+        (header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
 
     /// Get API status and sync information
     #[utoipa::path(
         get,
         path = "/api/v1/status",
         responses(
-            (status = 200, description = "API status", body = ApiResponse<StatusResponse>)
+            (status = 200, description = "API status", body = ApiResponse<StatusResponse>),
+            (status = 500, description = "Internal error", body = ErrorResponse)
         ),
         tag = "Status"
     )]
     pub async fn get_status(
-        Extension(pool): Extension<PgPool>,
-    ) -> Result<Json<ApiResponse<StatusResponse>>, StatusCode> {
+        State(ReadPool(pool)): State<ReadPool>,
+        State(chain): State<Arc<dyn ChainSource>>,
+        State(config): State<Arc<Config>>,
+    ) -> Result<([(header::HeaderName, HeaderValue); 1], Json<ApiResponse<StatusResponse>>), ApiError> {
         // Get sync status
         let sync_row = sqlx::query_as::<_, (Option<i64>, Option<i64>, Option<chrono::DateTime<chrono::Utc>>)>(
             "SELECT last_slot, last_block, updated_at FROM treasury.sync_status WHERE sync_type = 'events'"
         )
         .fetch_optional(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
         let (last_slot, last_block, last_sync_time) = sync_row.unwrap_or((None, None, None));
 
         // Get event count
         let (total_events,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM treasury.events")
             .fetch_one(&pool)
-            .await
-            .map_err(|e| {
-                tracing::error!("Database query error: {}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
+            .await?;
 
         // Get vendor contract count
         let (total_vendor_contracts,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM treasury.vendor_contracts")
             .fetch_one(&pool)
-            .await
-            .map_err(|e| {
-                tracing::error!("Database query error: {}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
+            .await?;
+
+        let progress = heartbeat::progress(&pool, chain.as_ref()).await?;
 
-        Ok(Json(ApiResponse::new(StatusResponse {
+        Ok(([NO_STORE], Json(ApiResponse::new(StatusResponse {
             api_version: "1.0.0".to_string(),
             database_connected: true,
             last_sync_slot: last_slot,
@@ -97,6 +135,22 @@ pub mod status {
             last_sync_time: last_sync_time.map(|t| t.timestamp()),
             total_events,
             total_vendor_contracts,
-        })))
+            sync_lag: sync_lag(&progress, &config.health),
+        }))))
+    }
+
+    /// Report sync progress, judging staleness against the readiness thresholds
+    pub(crate) fn sync_lag(progress: &SyncProgress, health: &HealthConfig) -> SyncLag {
+        SyncLag {
+            indexer_tip_slot: progress.tip.map(|t| t.slot),
+            scanned_slot: progress.heartbeat.as_ref().map(|h| h.scanned_slot),
+            slots_behind: progress.slots_behind_tip(),
+            seconds_behind: progress.seconds_behind_tip(),
+            seconds_behind_now: progress.seconds_behind_now(),
+            indexer_lag_seconds: progress.indexer_lag_secs(),
+            heartbeat_age_seconds: progress.heartbeat_age_secs(),
+            estimated_catch_up_seconds: progress.estimated_catch_up_secs(health),
+            stale: progress.is_stale(health),
+        }
     }
 }
@@ -0,0 +1,173 @@
+//! Search endpoint
+//!
+//! Full-text search over projects, milestones (including completion evidence)
+//! and events (reasons and metadata), backed by the GIN expression indexes
+//! created in `migrations/0001_baseline.up.sql`. Projects and milestones are
+//! matched, ranked and shown with their curated values; the indexes cover
+//! the chain values, so curated rows are checked in addition to index hits.
+
+use axum::{extract::State, response::Json};
+
+use super::extract::{pagination, Query};
+use crate::db::queries::{event_document, milestone_document, project_document};
+use crate::db::ReadPool;
+use crate::error::ApiError;
+use crate::services::curation::{curated_contract_field, curated_contract_row, curated_milestone_row};
+use crate::models::v1::{
+    PaginatedResponse, SearchQuery, SearchResult, SearchResultRow,
+};
+
+/// Options for ts_headline fragments
+const HEADLINE_OPTIONS: &str =
+    "StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=25, MinWords=8, FragmentDelimiter=\" ... \"";
+
+/// Search projects, milestones and events
+///
+/// Returns ranked, typed results with highlighted matching fragments.
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Search results ordered by relevance", body = PaginatedResponse<Vec<SearchResult>>),
+        (status = 400, description = "Missing search terms or unknown result type", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Search"
+)]
+pub async fn search(
+    State(ReadPool(pool)): State<ReadPool>,
+    Query(params): Query<SearchQuery>,
+) -> Result<Json<PaginatedResponse<Vec<SearchResult>>>, ApiError> {
+    let terms = params.q.trim();
+    if terms.is_empty() {
+        return Err(ApiError::invalid_parameter("q", "search terms must not be empty"));
+    }
+
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
+    let limit_i64 = limit as i64;
+
+    // Matching records per type, ranked against the query. Index hits on the
+    // chain values plus every curated row are candidates; the curated values
+    // decide whether they match.
+    let hits = format!(
+        r#"
+        q AS (SELECT websearch_to_tsquery('english', $1) AS query),
+        project_candidates AS (
+            SELECT vc.id FROM treasury.vendor_contracts vc, q
+            WHERE {project_raw} @@ q.query
+            UNION
+            SELECT vc.id FROM treasury.vendor_contracts vc
+            JOIN treasury.curation_overrides o ON o.project_id = vc.project_id AND o.milestone_id IS NULL
+        ),
+        projects AS (
+            SELECT c.* FROM project_candidates pc
+            JOIN treasury.vendor_contracts vc ON vc.id = pc.id
+            CROSS JOIN LATERAL {project_row} c
+            WHERE $2::text IS NULL OR $2 = 'project'
+        ),
+        milestone_candidates AS (
+            SELECT m.id FROM treasury.milestones m, q
+            WHERE {milestone_raw} @@ q.query
+            UNION
+            SELECT m.id FROM treasury.milestones m
+            JOIN treasury.vendor_contracts vc ON vc.id = m.vendor_contract_id
+            JOIN treasury.curation_overrides o ON o.project_id = vc.project_id AND o.milestone_id = m.milestone_id
+        ),
+        milestones AS (
+            SELECT c.* FROM milestone_candidates mc
+            JOIN treasury.milestones m ON m.id = mc.id
+            CROSS JOIN LATERAL {milestone_row} c
+            WHERE ($2::text IS NULL OR $2 = 'milestone') AND m.status <> 'removed'
+        ),
+        hits AS (
+            SELECT 'project'::text AS result_type, vc.id, ts_rank({project}, q.query) AS rank, vc.fund_block_time AS time
+            FROM projects vc, q
+            WHERE {project} @@ q.query
+            UNION ALL
+            SELECT 'milestone', m.id, ts_rank({milestone}, q.query), m.complete_time
+            FROM milestones m, q
+            WHERE {milestone} @@ q.query
+            UNION ALL
+            SELECT 'event', e.id, ts_rank({event}, q.query), e.block_time
+            FROM treasury.events e, q
+            WHERE ($2::text IS NULL OR $2 = 'event') AND {event} @@ q.query
+        )
+        "#,
+        project_raw = project_document("vc."),
+        milestone_raw = milestone_document("m."),
+        project_row = curated_contract_row("vc"),
+        milestone_row = curated_milestone_row("m"),
+        project = project_document("vc."),
+        milestone = milestone_document("m."),
+        event = event_document("e."),
+    );
+
+    // Get total count
+    let count_query = format!("WITH {} SELECT COUNT(*) FROM hits", hits);
+
+    let (total_count,): (i64,) = sqlx::query_as(&count_query)
+        .bind(terms)
+        .bind(params.result_type.map(|t| t.as_str()))
+        .fetch_one(&pool)
+        .await?;
+
+    // Get the page, with context and highlighted fragments
+    let data_query = format!(
+        r#"
+        WITH {hits},
+        page AS (
+            SELECT * FROM hits
+            ORDER BY rank DESC, time DESC NULLS LAST, result_type, id
+            LIMIT $3 OFFSET $4
+        )
+        SELECT
+            p.result_type,
+            p.id,
+            p.rank,
+            CASE p.result_type
+                WHEN 'project' THEN COALESCE(vc.project_name, vc.project_id)
+                WHEN 'milestone' THEN COALESCE(m.label, m.milestone_id)
+                ELSE ev.event_type
+            END AS title,
+            ts_headline('english',
+                CASE p.result_type
+                    WHEN 'project' THEN concat_ws(' ', vc.project_id, vc.project_name, vc.description, vc.vendor_name)
+                    WHEN 'milestone' THEN concat_ws(' ', m.label, m.description, m.acceptance_criteria, m.complete_description,
+                        (SELECT string_agg(s, ' ') FROM jsonb_array_elements_text(
+                            jsonb_path_query_array(m.evidence, 'strict $.** ? (@.type() == "string")')) s))
+                    ELSE concat_ws(' ', ev.reason,
+                        (SELECT string_agg(s, ' ') FROM jsonb_array_elements_text(
+                            jsonb_path_query_array(ev.metadata, 'strict $.** ? (@.type() == "string")')) s))
+                END,
+                q.query, '{options}') AS highlight,
+            pvc.project_id,
+            {project_name} AS project_name,
+            m.milestone_id,
+            ev.tx_hash,
+            ev.event_type,
+            p.time
+        FROM page p
+        CROSS JOIN q
+        LEFT JOIN projects vc ON p.result_type = 'project' AND vc.id = p.id
+        LEFT JOIN milestones m ON p.result_type = 'milestone' AND m.id = p.id
+        LEFT JOIN treasury.events ev ON p.result_type = 'event' AND ev.id = p.id
+        LEFT JOIN treasury.vendor_contracts pvc ON pvc.id = COALESCE(vc.id, m.vendor_contract_id, ev.vendor_contract_id)
+        ORDER BY p.rank DESC, p.time DESC NULLS LAST, p.result_type, p.id
+        "#,
+        hits = hits,
+        options = HEADLINE_OPTIONS,
+        project_name = curated_contract_field("pvc", "project_name"),
+    );
+
+    let rows = sqlx::query_as::<_, SearchResultRow>(&data_query)
+        .bind(terms)
+        .bind(params.result_type.map(|t| t.as_str()))
+        .bind(limit_i64)
+        .bind(offset)
+        .fetch_all(&pool)
+        .await?;
+
+    let results: Vec<SearchResult> = rows.into_iter().map(SearchResult::from).collect();
+    Ok(Json(PaginatedResponse::new(results, page, limit, total_count)))
+}
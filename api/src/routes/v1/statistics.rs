@@ -1,13 +1,24 @@
 //! Statistics endpoint
 
-use axum::{extract::Extension, http::StatusCode, response::Json};
+use axum::{
+    extract::State,
+    http::{HeaderName, HeaderValue},
+    response::Json,
+};
 use sqlx::PgPool;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use super::status::{sync_lag, NO_STORE};
+use crate::chain::ChainSource;
+use crate::config::{Config, HealthConfig};
+use crate::db::ReadPool;
+use crate::error::ApiError;
 use crate::models::v1::{
-    lovelace_to_ada, ApiResponse, EventStats, FinancialStats, MilestoneStats, ProjectStats,
-    StatisticsResponse, SyncStats, TreasuryStats,
+    lovelace_to_ada, ApiResponse, EventStats, FinancialStats, MilestoneStats,
+    ProjectStats, StatisticsResponse, SyncStats, TreasuryStats,
 };
+use crate::services::heartbeat;
 
 /// Get comprehensive statistics
 ///
@@ -16,13 +27,16 @@ use crate::models::v1::{
     get,
     path = "/api/v1/statistics",
     responses(
-        (status = 200, description = "Comprehensive statistics", body = ApiResponse<StatisticsResponse>)
+        (status = 200, description = "Comprehensive statistics", body = ApiResponse<StatisticsResponse>),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Statistics"
 )]
 pub async fn get_statistics(
-    Extension(pool): Extension<PgPool>,
-) -> Result<Json<ApiResponse<StatisticsResponse>>, StatusCode> {
+    State(ReadPool(pool)): State<ReadPool>,
+    State(chain): State<Arc<dyn ChainSource>>,
+    State(config): State<Arc<Config>>,
+) -> Result<([(HeaderName, HeaderValue); 1], Json<ApiResponse<StatisticsResponse>>), ApiError> {
     // Treasury stats
     let treasury_stats = get_treasury_stats(&pool).await?;
 
@@ -39,19 +53,19 @@ pub async fn get_statistics(
     let financial_stats = get_financial_stats(&pool).await?;
 
     // Sync stats
-    let sync_stats = get_sync_stats(&pool).await?;
+    let sync_stats = get_sync_stats(&pool, chain.as_ref(), &config.health).await?;
 
-    Ok(Json(ApiResponse::new(StatisticsResponse {
+    Ok(([NO_STORE], Json(ApiResponse::new(StatisticsResponse {
         treasury: treasury_stats,
         projects: project_stats,
         milestones: milestone_stats,
         events: event_stats,
         financials: financial_stats,
         sync: sync_stats,
-    })))
+    }))))
 }
 
-async fn get_treasury_stats(pool: &PgPool) -> Result<TreasuryStats, StatusCode> {
+async fn get_treasury_stats(pool: &PgPool) -> Result<TreasuryStats, ApiError> {
     let row = sqlx::query_as::<_, (i64, i64)>(
         r#"
         SELECT
@@ -61,22 +75,14 @@ async fn get_treasury_stats(pool: &PgPool) -> Result<TreasuryStats, StatusCode>
         "#
     )
     .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     // Get disbursement count from events
     let (disbursed_count,): (i64,) = sqlx::query_as(
         "SELECT COUNT(*) FROM treasury.events WHERE event_type = 'disburse'"
     )
     .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     Ok(TreasuryStats {
         total_count: row.0,
@@ -85,24 +91,21 @@ async fn get_treasury_stats(pool: &PgPool) -> Result<TreasuryStats, StatusCode>
     })
 }
 
-async fn get_project_stats(pool: &PgPool) -> Result<ProjectStats, StatusCode> {
-    let row = sqlx::query_as::<_, (i64, i64, i64, i64, i64)>(
+async fn get_project_stats(pool: &PgPool) -> Result<ProjectStats, ApiError> {
+    let row = sqlx::query_as::<_, (i64, i64, i64, i64, i64, i64)>(
         r#"
         SELECT
             COUNT(*),
             COUNT(*) FILTER (WHERE status = 'active'),
             COUNT(*) FILTER (WHERE status = 'completed'),
             COUNT(*) FILTER (WHERE status = 'paused'),
-            COUNT(*) FILTER (WHERE status = 'cancelled')
+            COUNT(*) FILTER (WHERE status = 'cancelled'),
+            COUNT(*) FILTER (WHERE status = 'swept')
         FROM treasury.vendor_contracts
         "#
     )
     .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     Ok(ProjectStats {
         total_count: row.0,
@@ -110,14 +113,15 @@ async fn get_project_stats(pool: &PgPool) -> Result<ProjectStats, StatusCode> {
         completed_count: row.2,
         paused_count: row.3,
         cancelled_count: row.4,
+        swept_count: row.5,
     })
 }
 
-async fn get_milestone_stats(pool: &PgPool) -> Result<MilestoneStats, StatusCode> {
+async fn get_milestone_stats(pool: &PgPool) -> Result<MilestoneStats, ApiError> {
     let row = sqlx::query_as::<_, (i64, i64, i64, i64)>(
         r#"
         SELECT
-            COUNT(*),
+            COUNT(*) FILTER (WHERE status <> 'removed'),
             COUNT(*) FILTER (WHERE status = 'pending'),
             COUNT(*) FILTER (WHERE status = 'completed'),
             COUNT(*) FILTER (WHERE status = 'withdrawn')
@@ -125,11 +129,7 @@ async fn get_milestone_stats(pool: &PgPool) -> Result<MilestoneStats, StatusCode
         "#
     )
     .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     Ok(MilestoneStats {
         total_count: row.0,
@@ -139,15 +139,11 @@ async fn get_milestone_stats(pool: &PgPool) -> Result<MilestoneStats, StatusCode
     })
 }
 
-async fn get_event_stats(pool: &PgPool) -> Result<EventStats, StatusCode> {
+async fn get_event_stats(pool: &PgPool) -> Result<EventStats, ApiError> {
     // Get total count
     let (total_count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM treasury.events")
         .fetch_one(pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     // Get counts by type
     let type_rows = sqlx::query_as::<_, (String, i64)>(
@@ -159,11 +155,7 @@ async fn get_event_stats(pool: &PgPool) -> Result<EventStats, StatusCode> {
         "#
     )
     .fetch_all(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     let by_type: HashMap<String, i64> = type_rows.into_iter().collect();
 
@@ -173,39 +165,27 @@ async fn get_event_stats(pool: &PgPool) -> Result<EventStats, StatusCode> {
     })
 }
 
-async fn get_financial_stats(pool: &PgPool) -> Result<FinancialStats, StatusCode> {
+async fn get_financial_stats(pool: &PgPool) -> Result<FinancialStats, ApiError> {
     // Get total allocated (sum of initial amounts)
     let (total_allocated,): (Option<i64>,) = sqlx::query_as(
         "SELECT COALESCE(SUM(initial_amount_lovelace), 0)::BIGINT FROM treasury.vendor_contracts"
     )
     .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     // Get total disbursed
     let (total_disbursed,): (Option<i64>,) = sqlx::query_as(
         "SELECT COALESCE(SUM(disburse_amount), 0)::BIGINT FROM treasury.milestones WHERE status = 'disbursed'"
     )
     .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     // Get current balance (unspent UTXOs)
     let (current_balance,): (Option<i64>,) = sqlx::query_as(
         "SELECT COALESCE(SUM(lovelace_amount), 0)::BIGINT FROM treasury.utxos WHERE NOT spent"
     )
     .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     let allocated = total_allocated.unwrap_or(0);
     let disbursed = total_disbursed.unwrap_or(0);
@@ -221,27 +201,20 @@ async fn get_financial_stats(pool: &PgPool) -> Result<FinancialStats, StatusCode
     })
 }
 
-async fn get_sync_stats(pool: &PgPool) -> Result<SyncStats, StatusCode> {
+async fn get_sync_stats(pool: &PgPool, chain: &dyn ChainSource, health: &HealthConfig) -> Result<SyncStats, ApiError> {
     let row = sqlx::query_as::<_, (Option<i64>, Option<i64>, Option<chrono::DateTime<chrono::Utc>>)>(
         "SELECT last_slot, last_block, updated_at FROM treasury.sync_status WHERE sync_type = 'events'"
     )
     .fetch_optional(pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
-
-    match row {
-        Some((last_slot, last_block, updated_at)) => Ok(SyncStats {
-            last_slot,
-            last_block,
-            last_updated: updated_at,
-        }),
-        None => Ok(SyncStats {
-            last_slot: None,
-            last_block: None,
-            last_updated: None,
-        }),
-    }
+    .await?;
+    let (last_slot, last_block, last_updated) = row.unwrap_or((None, None, None));
+
+    let progress = heartbeat::progress(pool, chain).await?;
+
+    Ok(SyncStats {
+        last_slot,
+        last_block,
+        last_updated,
+        lag: sync_lag(&progress, health),
+    })
 }
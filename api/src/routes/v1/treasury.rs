@@ -1,15 +1,13 @@
 //! Treasury endpoints
 
-use axum::{
-    extract::{Extension, Query},
-    http::StatusCode,
-    response::Json,
-};
-use sqlx::PgPool;
+use axum::{extract::State, response::Json};
 
+use super::extract::{pagination, Query};
+use crate::db::ReadPool;
+use crate::error::ApiError;
 use crate::models::v1::{
-    ApiResponse, EventResponse, EventWithContextRow, EventsQuery, PaginatedResponse,
-    TreasuryResponse, TreasurySummaryRow, UtxoResponse, UtxoRow,
+    ApiResponse, EventResponse, EventWithContextRow, EventsQuery,
+    PaginatedResponse, TreasuryResponse, TreasurySummaryRow, UtxoResponse, UtxoRow,
 };
 
 /// Get treasury contract details
@@ -21,13 +19,14 @@ use crate::models::v1::{
     path = "/api/v1/treasury",
     responses(
         (status = 200, description = "Treasury details", body = ApiResponse<TreasuryResponse>),
-        (status = 404, description = "No treasury found")
+        (status = 404, description = "No treasury found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Treasury"
 )]
 pub async fn get_treasury(
-    Extension(pool): Extension<PgPool>,
-) -> Result<Json<ApiResponse<TreasuryResponse>>, StatusCode> {
+    State(ReadPool(pool)): State<ReadPool>,
+) -> Result<Json<ApiResponse<TreasuryResponse>>, ApiError> {
     let row = sqlx::query_as::<_, TreasurySummaryRow>(
         r#"
         SELECT *
@@ -36,12 +35,8 @@ pub async fn get_treasury(
         "#
     )
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?
-    .ok_or(StatusCode::NOT_FOUND)?;
+    .await?
+    .ok_or_else(|| ApiError::not_found("No treasury contract found"))?;
 
     Ok(Json(ApiResponse::new(TreasuryResponse::from(row))))
 }
@@ -54,26 +49,25 @@ pub async fn get_treasury(
     path = "/api/v1/treasury/utxos",
     responses(
         (status = 200, description = "Treasury UTXOs", body = ApiResponse<Vec<UtxoResponse>>),
-        (status = 404, description = "No treasury found")
+        (status = 404, description = "No treasury found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Treasury"
 )]
 pub async fn get_treasury_utxos(
-    Extension(pool): Extension<PgPool>,
-) -> Result<Json<ApiResponse<Vec<UtxoResponse>>>, StatusCode> {
+    State(ReadPool(pool)): State<ReadPool>,
+) -> Result<Json<ApiResponse<Vec<UtxoResponse>>>, ApiError> {
     // First get the treasury contract address
     let treasury = sqlx::query_as::<_, (Option<String>,)>(
         "SELECT contract_address FROM treasury.treasury_contracts LIMIT 1"
     )
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?
-    .ok_or(StatusCode::NOT_FOUND)?;
+    .await?
+    .ok_or_else(|| ApiError::not_found("No treasury contract found"))?;
 
-    let address = treasury.0.ok_or(StatusCode::NOT_FOUND)?;
+    let address = treasury
+        .0
+        .ok_or_else(|| ApiError::not_found("Treasury contract address not known yet"))?;
 
     let rows = sqlx::query_as::<_, UtxoRow>(
         r#"
@@ -92,11 +86,7 @@ pub async fn get_treasury_utxos(
     )
     .bind(&address)
     .fetch_all(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     let utxos: Vec<UtxoResponse> = rows.into_iter().map(UtxoResponse::from).collect();
     Ok(Json(ApiResponse::new(utxos)))
@@ -110,17 +100,17 @@ pub async fn get_treasury_utxos(
     path = "/api/v1/treasury/events",
     params(EventsQuery),
     responses(
-        (status = 200, description = "Treasury events", body = PaginatedResponse<Vec<EventResponse>>)
+        (status = 200, description = "Treasury events", body = PaginatedResponse<Vec<EventResponse>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Treasury"
 )]
 pub async fn get_treasury_events(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Query(params): Query<EventsQuery>,
-) -> Result<Json<PaginatedResponse<Vec<EventResponse>>>, StatusCode> {
-    let page = params.page.max(1);
-    let limit = params.limit.min(100).max(1);
-    let offset = ((page - 1) * limit) as i64;
+) -> Result<Json<PaginatedResponse<Vec<EventResponse>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
     let limit_i64 = limit as i64;
 
     // Treasury-level event types
@@ -137,11 +127,7 @@ pub async fn get_treasury_events(
     )
     .bind(&treasury_event_types)
     .fetch_one(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     // Get events
     let rows = sqlx::query_as::<_, EventWithContextRow>(
@@ -157,11 +143,7 @@ pub async fn get_treasury_events(
     .bind(limit_i64)
     .bind(offset)
     .fetch_all(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     let events: Vec<EventResponse> = rows.into_iter().map(EventResponse::from).collect();
     Ok(Json(PaginatedResponse::new(events, page, limit, total_count)))
@@ -1,17 +1,18 @@
 //! Vendor Contracts (Projects) endpoints
 
-use axum::{
-    extract::{Extension, Path, Query},
-    http::StatusCode,
-    response::Json,
-};
-use sqlx::PgPool;
+use axum::{extract::State, response::Json};
 
+use super::extract::{pagination, time_range, Path, Query};
+use crate::db::ReadPool;
+use crate::error::ApiError;
 use crate::models::v1::{
-    ApiResponse, EventResponse, EventWithContextRow, MilestoneResponse, MilestoneRow,
-    PaginatedResponse, ProjectEventsQuery, UtxoResponse, UtxoRow, VendorContractDetail,
-    VendorContractSummary, VendorContractSummaryRow, VendorContractsQuery,
+    ApiResponse, ContractRevisionResponse, ContractRevisionRow, EventResponse,
+    EventWithContextRow, MilestoneResponse, MilestoneRow, PaginatedResponse, ProjectEventsQuery,
+    UtxoResponse, UtxoRow, VendorContractDetail, VendorContractSummary, VendorContractSummaryRow,
+    VendorContractsQuery,
 };
+use crate::services::curation;
+use crate::services::revisions::diff_snapshots;
 
 /// List all vendor contracts
 ///
@@ -21,17 +22,18 @@ use crate::models::v1::{
     path = "/api/v1/vendor-contracts",
     params(VendorContractsQuery),
     responses(
-        (status = 200, description = "List of vendor contracts", body = PaginatedResponse<Vec<VendorContractSummary>>)
+        (status = 200, description = "List of vendor contracts", body = PaginatedResponse<Vec<VendorContractSummary>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Vendor Contracts"
 )]
 pub async fn list_vendor_contracts(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Query(params): Query<VendorContractsQuery>,
-) -> Result<Json<PaginatedResponse<Vec<VendorContractSummary>>>, StatusCode> {
-    let page = params.page.max(1);
-    let limit = params.limit.min(100).max(1);
-    let offset = ((page - 1) * limit) as i64;
+) -> Result<Json<PaginatedResponse<Vec<VendorContractSummary>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
+    time_range(params.from_time, params.to_time)?;
     let limit_i64 = limit as i64;
 
     // Build dynamic query based on filters
@@ -68,16 +70,8 @@ pub async fn list_vendor_contracts(
     };
 
     // Determine sort order
-    let sort_field = match params.sort.as_deref() {
-        Some("project_id") => "project_id",
-        Some("project_name") => "project_name",
-        Some("initial_amount") => "initial_amount_lovelace",
-        _ => "fund_block_time",
-    };
-    let sort_order = match params.order.as_deref() {
-        Some("asc") => "ASC",
-        _ => "DESC",
-    };
+    let sort_field = params.sort.unwrap_or_default().as_sql();
+    let sort_order = params.order.unwrap_or_default().as_sql();
 
     // Get total count
     let count_query = format!(
@@ -88,7 +82,7 @@ pub async fn list_vendor_contracts(
     let mut count_q = sqlx::query_as::<_, (i64,)>(&count_query);
 
     if let Some(ref status) = params.status {
-        count_q = count_q.bind(status);
+        count_q = count_q.bind(status.as_str());
     }
     if let Some(ref search) = params.search {
         count_q = count_q.bind(format!("%{}%", search));
@@ -102,11 +96,7 @@ pub async fn list_vendor_contracts(
 
     let (total_count,) = count_q
         .fetch_one(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
     // Get data
     let data_query = format!(
@@ -127,7 +117,7 @@ pub async fn list_vendor_contracts(
     let mut data_q = sqlx::query_as::<_, VendorContractSummaryRow>(&data_query);
 
     if let Some(ref status) = params.status {
-        data_q = data_q.bind(status);
+        data_q = data_q.bind(status.as_str());
     }
     if let Some(ref search) = params.search {
         data_q = data_q.bind(format!("%{}%", search));
@@ -143,13 +133,10 @@ pub async fn list_vendor_contracts(
         .bind(limit_i64)
         .bind(offset)
         .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
-    let contracts: Vec<VendorContractSummary> = rows.into_iter().map(VendorContractSummary::from).collect();
+    let mut contracts: Vec<VendorContractSummary> = rows.into_iter().map(VendorContractSummary::from).collect();
+    curation::apply(&pool, &mut contracts).await?;
     Ok(Json(PaginatedResponse::new(contracts, page, limit, total_count)))
 }
 
@@ -164,14 +151,16 @@ pub async fn list_vendor_contracts(
     ),
     responses(
         (status = 200, description = "Vendor contract details", body = ApiResponse<VendorContractDetail>),
-        (status = 404, description = "Vendor contract not found")
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Vendor contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Vendor Contracts"
 )]
 pub async fn get_vendor_contract(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Path(project_id): Path<String>,
-) -> Result<Json<ApiResponse<VendorContractDetail>>, StatusCode> {
+) -> Result<Json<ApiResponse<VendorContractDetail>>, ApiError> {
     let row = sqlx::query_as::<_, VendorContractSummaryRow>(
         r#"
         SELECT *
@@ -181,14 +170,12 @@ pub async fn get_vendor_contract(
     )
     .bind(&project_id)
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?
-    .ok_or(StatusCode::NOT_FOUND)?;
-
-    Ok(Json(ApiResponse::new(VendorContractDetail::from(row))))
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Vendor contract {} not found", project_id)))?;
+
+    let mut contract = VendorContractDetail::from(row);
+    curation::apply(&pool, std::slice::from_mut(&mut contract)).await?;
+    Ok(Json(ApiResponse::new(contract)))
 }
 
 /// Get milestones for a vendor contract
@@ -202,28 +189,26 @@ pub async fn get_vendor_contract(
     ),
     responses(
         (status = 200, description = "Project milestones", body = ApiResponse<Vec<MilestoneResponse>>),
-        (status = 404, description = "Vendor contract not found")
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Vendor contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Vendor Contracts"
 )]
 pub async fn get_vendor_contract_milestones(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Path(project_id): Path<String>,
-) -> Result<Json<ApiResponse<Vec<MilestoneResponse>>>, StatusCode> {
+) -> Result<Json<ApiResponse<Vec<MilestoneResponse>>>, ApiError> {
     // First verify the project exists
     let exists = sqlx::query_as::<_, (i32,)>(
         "SELECT id FROM treasury.vendor_contracts WHERE project_id = $1"
     )
     .bind(&project_id)
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     if exists.is_none() {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(ApiError::not_found(format!("Vendor contract {} not found", project_id)));
     }
 
     let rows = sqlx::query_as::<_, MilestoneRow>(
@@ -237,6 +222,7 @@ pub async fn get_vendor_contract_milestones(
             m.description,
             m.acceptance_criteria,
             m.amount_lovelace,
+            m.maturity,
             m.status,
             m.complete_tx_hash,
             m.complete_time,
@@ -255,13 +241,10 @@ pub async fn get_vendor_contract_milestones(
     )
     .bind(&project_id)
     .fetch_all(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
-    let milestones: Vec<MilestoneResponse> = rows.into_iter().map(MilestoneResponse::from).collect();
+    let mut milestones: Vec<MilestoneResponse> = rows.into_iter().map(MilestoneResponse::from).collect();
+    curation::apply(&pool, &mut milestones).await?;
     Ok(Json(ApiResponse::new(milestones)))
 }
 
@@ -277,18 +260,18 @@ pub async fn get_vendor_contract_milestones(
     ),
     responses(
         (status = 200, description = "Project events", body = PaginatedResponse<Vec<EventResponse>>),
-        (status = 404, description = "Vendor contract not found")
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Vendor contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Vendor Contracts"
 )]
 pub async fn get_vendor_contract_events(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Path(project_id): Path<String>,
     Query(params): Query<ProjectEventsQuery>,
-) -> Result<Json<PaginatedResponse<Vec<EventResponse>>>, StatusCode> {
-    let page = params.page.max(1);
-    let limit = params.limit.min(100).max(1);
-    let offset = ((page - 1) * limit) as i64;
+) -> Result<Json<PaginatedResponse<Vec<EventResponse>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
     let limit_i64 = limit as i64;
 
     // First verify the project exists
@@ -297,18 +280,14 @@ pub async fn get_vendor_contract_events(
     )
     .bind(&project_id)
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     if exists.is_none() {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(ApiError::not_found(format!("Vendor contract {} not found", project_id)));
     }
 
     // Build query based on event type filter
-    let (total_count, rows) = if let Some(ref event_type) = params.event_type {
+    let (total_count, rows) = if let Some(event_type) = params.event_type {
         let (count,): (i64,) = sqlx::query_as(
             r#"
             SELECT COUNT(*)
@@ -317,13 +296,9 @@ pub async fn get_vendor_contract_events(
             "#
         )
         .bind(&project_id)
-        .bind(event_type)
+        .bind(event_type.as_str())
         .fetch_one(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
         let rows = sqlx::query_as::<_, EventWithContextRow>(
             r#"
@@ -335,15 +310,11 @@ pub async fn get_vendor_contract_events(
             "#
         )
         .bind(&project_id)
-        .bind(event_type)
+        .bind(event_type.as_str())
         .bind(limit_i64)
         .bind(offset)
         .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
         (count, rows)
     } else {
@@ -356,11 +327,7 @@ pub async fn get_vendor_contract_events(
         )
         .bind(&project_id)
         .fetch_one(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
         let rows = sqlx::query_as::<_, EventWithContextRow>(
             r#"
@@ -375,11 +342,7 @@ pub async fn get_vendor_contract_events(
         .bind(limit_i64)
         .bind(offset)
         .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database query error: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?;
 
         (count, rows)
     };
@@ -399,28 +362,26 @@ pub async fn get_vendor_contract_events(
     ),
     responses(
         (status = 200, description = "Project UTXOs", body = ApiResponse<Vec<UtxoResponse>>),
-        (status = 404, description = "Vendor contract not found")
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Vendor contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
     ),
     tag = "Vendor Contracts"
 )]
 pub async fn get_vendor_contract_utxos(
-    Extension(pool): Extension<PgPool>,
+    State(ReadPool(pool)): State<ReadPool>,
     Path(project_id): Path<String>,
-) -> Result<Json<ApiResponse<Vec<UtxoResponse>>>, StatusCode> {
+) -> Result<Json<ApiResponse<Vec<UtxoResponse>>>, ApiError> {
     // First verify the project exists
     let exists = sqlx::query_as::<_, (i32,)>(
         "SELECT id FROM treasury.vendor_contracts WHERE project_id = $1"
     )
     .bind(&project_id)
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     if exists.is_none() {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(ApiError::not_found(format!("Vendor contract {} not found", project_id)));
     }
 
     let rows = sqlx::query_as::<_, UtxoRow>(
@@ -441,12 +402,83 @@ pub async fn get_vendor_contract_utxos(
     )
     .bind(&project_id)
     .fetch_all(&pool)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database query error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .await?;
 
     let utxos: Vec<UtxoResponse> = rows.into_iter().map(UtxoResponse::from).collect();
     Ok(Json(ApiResponse::new(utxos)))
 }
+
+/// Get revision history for a vendor contract
+///
+/// Returns every recorded revision of the contract terms (the original fund
+/// plus each modify amendment), each diffed against the previous revision.
+#[utoipa::path(
+    get,
+    path = "/api/v1/vendor-contracts/{project_id}/revisions",
+    params(
+        ("project_id" = String, Path, description = "Project identifier")
+    ),
+    responses(
+        (status = 200, description = "Contract revisions", body = ApiResponse<Vec<ContractRevisionResponse>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Vendor contract not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Vendor Contracts"
+)]
+pub async fn get_vendor_contract_revisions(
+    State(ReadPool(pool)): State<ReadPool>,
+    Path(project_id): Path<String>,
+) -> Result<Json<ApiResponse<Vec<ContractRevisionResponse>>>, ApiError> {
+    // First verify the project exists
+    let exists = sqlx::query_as::<_, (i32,)>(
+        "SELECT id FROM treasury.vendor_contracts WHERE project_id = $1"
+    )
+    .bind(&project_id)
+    .fetch_optional(&pool)
+    .await?;
+
+    if exists.is_none() {
+        return Err(ApiError::not_found(format!("Vendor contract {} not found", project_id)));
+    }
+
+    let rows = sqlx::query_as::<_, ContractRevisionRow>(
+        r#"
+        SELECT
+            r.revision,
+            r.event_type,
+            r.tx_hash,
+            r.slot,
+            r.block_time,
+            r.reason,
+            r.snapshot
+        FROM treasury.vendor_contract_revisions r
+        JOIN treasury.vendor_contracts vc ON vc.id = r.vendor_contract_id
+        WHERE vc.project_id = $1
+        ORDER BY r.revision
+        "#
+    )
+    .bind(&project_id)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut revisions = Vec::with_capacity(rows.len());
+    let mut previous: Option<serde_json::Value> = None;
+
+    for row in rows {
+        let (changes, milestone_changes) = diff_snapshots(previous.as_ref(), &row.snapshot);
+        revisions.push(ContractRevisionResponse {
+            revision: row.revision,
+            event_type: row.event_type,
+            tx_hash: row.tx_hash,
+            slot: row.slot,
+            block_time: row.block_time,
+            reason: row.reason,
+            changes,
+            milestone_changes,
+        });
+        previous = Some(row.snapshot);
+    }
+
+    Ok(Json(ApiResponse::new(revisions)))
+}
@@ -0,0 +1,143 @@
+//! Vendors endpoints
+//!
+//! A vendor groups every project paid to the same stake credential (or payout
+//! address), with manual overrides from `treasury.vendor_aliases`.
+
+use axum::{extract::State, response::Json};
+
+use super::extract::{pagination, Path, Query};
+use crate::db::ReadPool;
+use crate::error::ApiError;
+use crate::models::v1::{
+    ApiResponse, PaginatedResponse, VendorContractSummary, VendorContractSummaryRow, VendorDetail,
+    VendorSummary, VendorSummaryRow, VendorsQuery,
+};
+use crate::services::curation;
+
+/// List vendors
+///
+/// Returns a paginated list of vendors with totals across their projects.
+#[utoipa::path(
+    get,
+    path = "/api/v1/vendors",
+    params(VendorsQuery),
+    responses(
+        (status = 200, description = "List of vendors", body = PaginatedResponse<Vec<VendorSummary>>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Vendors"
+)]
+pub async fn list_vendors(
+    State(ReadPool(pool)): State<ReadPool>,
+    Query(params): Query<VendorsQuery>,
+) -> Result<Json<PaginatedResponse<Vec<VendorSummary>>>, ApiError> {
+    let (page, limit, offset) = pagination(params.page, params.limit)?;
+    let limit_i64 = limit as i64;
+
+    let where_clause = if params.search.is_some() {
+        "WHERE (name ILIKE $1 OR payout_address ILIKE $1 OR stake_credential ILIKE $1)"
+    } else {
+        ""
+    };
+    let bind_index = if params.search.is_some() { 2 } else { 1 };
+
+    let sort_field = params.sort.unwrap_or_default().as_sql();
+    let sort_order = params.order.unwrap_or_default().as_sql();
+
+    // Get total count
+    let count_query = format!("SELECT COUNT(*) FROM treasury.v_vendor_summary {}", where_clause);
+    let mut count_q = sqlx::query_as::<_, (i64,)>(&count_query);
+    if let Some(ref search) = params.search {
+        count_q = count_q.bind(format!("%{}%", search));
+    }
+
+    let (total_count,) = count_q
+        .fetch_one(&pool)
+        .await?;
+
+    // Get data
+    let data_query = format!(
+        r#"
+        SELECT *
+        FROM treasury.v_vendor_summary
+        {}
+        ORDER BY {} {} NULLS LAST, id
+        LIMIT ${} OFFSET ${}
+        "#,
+        where_clause,
+        sort_field,
+        sort_order,
+        bind_index,
+        bind_index + 1
+    );
+
+    let mut data_q = sqlx::query_as::<_, VendorSummaryRow>(&data_query);
+    if let Some(ref search) = params.search {
+        data_q = data_q.bind(format!("%{}%", search));
+    }
+
+    let rows = data_q
+        .bind(limit_i64)
+        .bind(offset)
+        .fetch_all(&pool)
+        .await?;
+
+    let vendors: Vec<VendorSummary> = rows.into_iter().map(VendorSummary::from).collect();
+    Ok(Json(PaginatedResponse::new(vendors, page, limit, total_count)))
+}
+
+/// Get a vendor by ID
+///
+/// Returns the vendor's totals, on-time milestone rate and all of its projects.
+#[utoipa::path(
+    get,
+    path = "/api/v1/vendors/{id}",
+    params(
+        ("id" = i32, Path, description = "Vendor ID")
+    ),
+    responses(
+        (status = 200, description = "Vendor details", body = ApiResponse<VendorDetail>),
+        (status = 400, description = "Invalid parameter", body = ErrorResponse),
+        (status = 404, description = "Vendor not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse)
+    ),
+    tag = "Vendors"
+)]
+pub async fn get_vendor(
+    State(ReadPool(pool)): State<ReadPool>,
+    Path(id): Path<i32>,
+) -> Result<Json<ApiResponse<VendorDetail>>, ApiError> {
+    let row = sqlx::query_as::<_, VendorSummaryRow>(
+        "SELECT * FROM treasury.v_vendor_summary WHERE id = $1"
+    )
+    .bind(id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("Vendor {} not found", id)))?;
+
+    let project_rows = sqlx::query_as::<_, VendorContractSummaryRow>(
+        r#"
+        SELECT *
+        FROM treasury.v_vendor_contracts_summary
+        WHERE vendor_id = $1
+        ORDER BY fund_block_time DESC NULLS LAST
+        "#
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut projects: Vec<VendorContractSummary> = project_rows.into_iter().map(VendorContractSummary::from).collect();
+    curation::apply(&pool, &mut projects).await?;
+
+    let created_at = row.created_at;
+    let updated_at = row.updated_at;
+
+    Ok(Json(ApiResponse::new(VendorDetail {
+        vendor: VendorSummary::from(row),
+        projects,
+        created_at,
+        updated_at,
+    })))
+}
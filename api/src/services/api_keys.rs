@@ -0,0 +1,132 @@
+//! API key issue, lookup and revocation
+//!
+//! Keys are `trk_` followed by 64 random hex characters. Only the SHA-256
+//! hash is stored; keys carry enough entropy that an unsalted hash cannot be
+//! reversed. Used by the admin API and `treasury-cli keys`.
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::models::v1::{ApiKeyResponse, ApiScope};
+
+/// Marks a string as a treasury API key
+pub const KEY_PREFIX: &str = "trk_";
+
+/// Characters of the key kept in `key_prefix`
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// `api_keys` row
+#[derive(Debug, sqlx::FromRow)]
+struct ApiKeyRow {
+    id: i32,
+    name: String,
+    key_prefix: String,
+    scopes: Vec<String>,
+    rate_limit_per_minute: Option<i32>,
+    created_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApiKeyRow> for ApiKeyResponse {
+    fn from(row: ApiKeyRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            key_prefix: row.key_prefix,
+            // The table's CHECK constraint admits only known scopes
+            scopes: row.scopes.iter().filter_map(|s| ApiScope::parse(s)).collect(),
+            rate_limit_per_minute: row.rate_limit_per_minute,
+            created_at: row.created_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+const COLUMNS: &str = "id, name, key_prefix, scopes, rate_limit_per_minute, created_at, revoked_at";
+
+/// SHA-256 of a key, as stored
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// A fresh random key
+fn generate_key() -> String {
+    // Two v4 UUIDs carry 244 random bits from the OS generator
+    let random = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()]
+        .iter()
+        .map(|u| hex::encode(u.as_bytes()))
+        .collect::<String>();
+    format!("{}{}", KEY_PREFIX, random)
+}
+
+/// Issue a key, returning its details and the key itself
+pub async fn issue(
+    pool: &PgPool,
+    name: &str,
+    scopes: &[ApiScope],
+    rate_limit_per_minute: Option<i32>,
+) -> Result<(ApiKeyResponse, String), sqlx::Error> {
+    let key = generate_key();
+    let mut scopes: Vec<&str> = scopes.iter().map(|s| s.as_str()).collect();
+    scopes.sort();
+    scopes.dedup();
+
+    let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+        r#"
+        INSERT INTO treasury.api_keys (name, key_prefix, key_hash, scopes, rate_limit_per_minute)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}
+        "#,
+        COLUMNS
+    ))
+    .bind(name)
+    .bind(&key[..DISPLAY_PREFIX_LEN])
+    .bind(hash_key(&key))
+    .bind(&scopes)
+    .bind(rate_limit_per_minute)
+    .fetch_one(pool)
+    .await?;
+
+    Ok((row.into(), key))
+}
+
+/// Every key, newest first
+pub async fn list(pool: &PgPool) -> Result<Vec<ApiKeyResponse>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, ApiKeyRow>(&format!(
+        "SELECT {} FROM treasury.api_keys ORDER BY id DESC",
+        COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Revoke a key; revoking twice keeps the first revocation time. `None` if
+/// no such key exists.
+pub async fn revoke(pool: &PgPool, id: i32) -> Result<Option<ApiKeyResponse>, sqlx::Error> {
+    let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+        r#"
+        UPDATE treasury.api_keys SET revoked_at = COALESCE(revoked_at, NOW())
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(Into::into))
+}
+
+/// Look a key up by its hash, revoked or not
+pub async fn find_by_hash(pool: &PgPool, key_hash: &str) -> Result<Option<ApiKeyResponse>, sqlx::Error> {
+    let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+        "SELECT {} FROM treasury.api_keys WHERE key_hash = $1",
+        COLUMNS
+    ))
+    .bind(key_hash)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(Into::into))
+}
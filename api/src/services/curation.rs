@@ -0,0 +1,491 @@
+//! Curated overrides and annotations
+//!
+//! Operators correct chain metadata (a misspelt project name, a missing
+//! vendor name) through overrides stored apart from the chain-derived rows, so
+//! resyncs and reprocessing never lose them. Handlers call [`apply`] on
+//! vendor contract and milestone responses, which swaps in curated values and
+//! reports them, with their chain values, under `curation`. Queries that work
+//! on rows rather than responses (search, exports, vendor resolution) apply
+//! them in SQL through [`curated_contract_row`] and [`curated_milestone_row`].
+//! Every change is written to `curation_audit_log` in the same transaction.
+
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashSet;
+
+use crate::models::v1::{
+    AddAnnotationRequest, AnnotationResponse, AuditLogEntry, CuratedField, Curation, MilestoneResponse,
+    OverrideResponse, RemoveCurationRequest, SetOverrideRequest, VendorContractDetail, VendorContractSummary,
+};
+
+/// Vendor contract fields that can be overridden
+pub const CONTRACT_FIELDS: &[&str] = &["project_name", "description", "vendor_name", "contract_url"];
+
+/// Milestone fields that can be overridden
+pub const MILESTONE_FIELDS: &[&str] = &["label", "description", "acceptance_criteria"];
+
+/// A response that overrides and annotations apply to
+pub trait Curated {
+    /// Logical project id, and milestone id for milestones
+    fn curation_target(&self) -> (&str, Option<&str>);
+
+    /// The overridable field called `field`
+    fn field_mut(&mut self, field: &str) -> Option<&mut Option<String>>;
+
+    /// Where the project's name is repeated, for responses other than the
+    /// vendor contract itself
+    fn project_name_mut(&mut self) -> Option<&mut Option<String>> {
+        None
+    }
+
+    fn set_curation(&mut self, curation: Curation);
+}
+
+impl Curated for VendorContractSummary {
+    fn curation_target(&self) -> (&str, Option<&str>) {
+        (&self.project_id, None)
+    }
+
+    fn field_mut(&mut self, field: &str) -> Option<&mut Option<String>> {
+        match field {
+            "project_name" => Some(&mut self.project_name),
+            "description" => Some(&mut self.description),
+            "vendor_name" => Some(&mut self.vendor_name),
+            "contract_url" => Some(&mut self.contract_url),
+            _ => None,
+        }
+    }
+
+    fn set_curation(&mut self, curation: Curation) {
+        self.curation = Some(curation);
+    }
+}
+
+impl Curated for VendorContractDetail {
+    fn curation_target(&self) -> (&str, Option<&str>) {
+        (&self.project_id, None)
+    }
+
+    fn field_mut(&mut self, field: &str) -> Option<&mut Option<String>> {
+        match field {
+            "project_name" => Some(&mut self.project_name),
+            "description" => Some(&mut self.description),
+            "vendor_name" => Some(&mut self.vendor_name),
+            "contract_url" => Some(&mut self.contract_url),
+            _ => None,
+        }
+    }
+
+    fn set_curation(&mut self, curation: Curation) {
+        self.curation = Some(curation);
+    }
+}
+
+impl Curated for MilestoneResponse {
+    fn curation_target(&self) -> (&str, Option<&str>) {
+        (&self.project.project_id, Some(&self.milestone_id))
+    }
+
+    fn field_mut(&mut self, field: &str) -> Option<&mut Option<String>> {
+        match field {
+            "label" => Some(&mut self.label),
+            "description" => Some(&mut self.description),
+            "acceptance_criteria" => Some(&mut self.acceptance_criteria),
+            _ => None,
+        }
+    }
+
+    fn project_name_mut(&mut self) -> Option<&mut Option<String>> {
+        Some(&mut self.project.project_name)
+    }
+
+    fn set_curation(&mut self, curation: Curation) {
+        self.curation = Some(curation);
+    }
+}
+
+const OVERRIDE_COLUMNS: &str =
+    "id, project_id, milestone_id, field, value, reason, author, created_at, updated_at";
+const ANNOTATION_COLUMNS: &str = "id, project_id, milestone_id, note, author, created_at";
+
+/// Apply overrides and annotations to `items`, which are left untouched if
+/// nothing is curated for them
+pub async fn apply<T: Curated>(pool: &PgPool, items: &mut [T]) -> Result<(), sqlx::Error> {
+    let project_ids: Vec<String> = items
+        .iter()
+        .map(|item| item.curation_target().0.to_string())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if project_ids.is_empty() {
+        return Ok(());
+    }
+
+    let overrides = sqlx::query_as::<_, OverrideResponse>(&format!(
+        "SELECT {} FROM treasury.curation_overrides WHERE project_id = ANY($1) ORDER BY field",
+        OVERRIDE_COLUMNS
+    ))
+    .bind(&project_ids)
+    .fetch_all(pool)
+    .await?;
+    let annotations = sqlx::query_as::<_, AnnotationResponse>(&format!(
+        "SELECT {} FROM treasury.curation_annotations WHERE project_id = ANY($1) ORDER BY id",
+        ANNOTATION_COLUMNS
+    ))
+    .bind(&project_ids)
+    .fetch_all(pool)
+    .await?;
+    if overrides.is_empty() && annotations.is_empty() {
+        return Ok(());
+    }
+
+    for item in items.iter_mut() {
+        let (project_id, milestone_id) = item.curation_target();
+        let (project_id, milestone_id) = (project_id.to_string(), milestone_id.map(str::to_string));
+        let targets = |p: &str, m: Option<&str>| p == project_id && m == milestone_id.as_deref();
+
+        let mut curation = Curation::default();
+        for o in overrides.iter().filter(|o| targets(&o.project_id, o.milestone_id.as_deref())) {
+            if let Some(slot) = item.field_mut(&o.field) {
+                let chain_value = slot.replace(o.value.clone());
+                curation.overrides.push(CuratedField {
+                    field: o.field.clone(),
+                    chain_value,
+                    reason: o.reason.clone(),
+                    author: o.author.clone(),
+                    updated_at: o.updated_at,
+                });
+            }
+        }
+        curation.annotations = annotations
+            .iter()
+            .filter(|a| targets(&a.project_id, a.milestone_id.as_deref()))
+            .cloned()
+            .collect();
+
+        // Milestones show their project's curated name
+        let project_name = overrides
+            .iter()
+            .find(|o| o.project_id == project_id && o.milestone_id.is_none() && o.field == "project_name");
+        if let (Some(o), Some(slot)) = (project_name, item.project_name_mut()) {
+            *slot = Some(o.value.clone());
+        }
+
+        if !curation.overrides.is_empty() || !curation.annotations.is_empty() {
+            item.set_curation(curation);
+        }
+    }
+    Ok(())
+}
+
+/// SQL for the row `alias` of `vendor_contracts`, or of a view with its
+/// columns, with the project's overrides applied. Use as
+/// `CROSS JOIN LATERAL <sql> c`: `c` has the same columns as `alias`.
+pub fn curated_contract_row(alias: &str) -> String {
+    format!(
+        "jsonb_populate_record({a}, COALESCE((\
+            SELECT jsonb_object_agg(o.field, o.value) FROM curation_overrides o \
+            WHERE o.project_id = {a}.project_id AND o.milestone_id IS NULL), '{{}}'))",
+        a = alias
+    )
+}
+
+/// SQL for the row `alias` of `milestones` with the milestone's overrides
+/// applied, used like [`curated_contract_row`]
+pub fn curated_milestone_row(alias: &str) -> String {
+    format!(
+        "jsonb_populate_record({a}, COALESCE((\
+            SELECT jsonb_object_agg(o.field, o.value) FROM curation_overrides o \
+            JOIN vendor_contracts ovc ON ovc.project_id = o.project_id \
+            WHERE ovc.id = {a}.vendor_contract_id AND o.milestone_id = {a}.milestone_id), '{{}}'))",
+        a = alias
+    )
+}
+
+/// SQL for the curated value of a vendor contract field of the row `alias`
+pub fn curated_contract_field(alias: &str, field: &str) -> String {
+    format!(
+        "COALESCE((SELECT o.value FROM curation_overrides o \
+            WHERE o.project_id = {a}.project_id AND o.milestone_id IS NULL AND o.field = '{f}'), {a}.{f})",
+        a = alias,
+        f = field
+    )
+}
+
+/// Whether the vendor contract (and milestone, if given) exists
+pub async fn target_exists(pool: &PgPool, project_id: &str, milestone_id: Option<&str>) -> Result<bool, sqlx::Error> {
+    let (exists,): (bool,) = sqlx::query_as(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM treasury.vendor_contracts vc
+            WHERE vc.project_id = $1
+              AND ($2::TEXT IS NULL OR EXISTS (
+                  SELECT 1 FROM treasury.milestones m
+                  WHERE m.vendor_contract_id = vc.id AND m.milestone_id = $2
+              ))
+        )
+        "#
+    )
+    .bind(project_id)
+    .bind(milestone_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(exists)
+}
+
+/// A change to record in the audit log
+struct AuditRecord<'a> {
+    action: &'a str,
+    project_id: &'a str,
+    milestone_id: Option<&'a str>,
+    field: Option<&'a str>,
+    old_value: Option<&'a str>,
+    new_value: Option<&'a str>,
+    reason: Option<&'a str>,
+    author: &'a str,
+    actor: &'a str,
+}
+
+async fn audit(tx: &mut Transaction<'_, Postgres>, record: AuditRecord<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO treasury.curation_audit_log
+            (action, project_id, milestone_id, field, old_value, new_value, reason, author, actor)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#
+    )
+    .bind(record.action)
+    .bind(record.project_id)
+    .bind(record.milestone_id)
+    .bind(record.field)
+    .bind(record.old_value)
+    .bind(record.new_value)
+    .bind(record.reason)
+    .bind(record.author)
+    .bind(record.actor)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Set or replace the override of one field; `actor` names the credential
+/// used
+pub async fn set_override(
+    pool: &PgPool,
+    request: &SetOverrideRequest,
+    actor: &str,
+) -> Result<OverrideResponse, sqlx::Error> {
+    let SetOverrideRequest { ref project_id, ref field, ref value, ref reason, ref author, .. } = *request;
+    let milestone_id = request.milestone_id.as_deref();
+    let mut tx = pool.begin().await?;
+
+    let previous: Option<(String,)> = sqlx::query_as(
+        r#"
+        SELECT value FROM treasury.curation_overrides
+        WHERE project_id = $1 AND milestone_id IS NOT DISTINCT FROM $2 AND field = $3
+        FOR UPDATE
+        "#
+    )
+    .bind(project_id)
+    .bind(milestone_id)
+    .bind(field)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let row = sqlx::query_as::<_, OverrideResponse>(&format!(
+        r#"
+        INSERT INTO treasury.curation_overrides (project_id, milestone_id, field, value, reason, author)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (project_id, (COALESCE(milestone_id, '')), field) DO UPDATE SET
+            value = EXCLUDED.value,
+            reason = EXCLUDED.reason,
+            author = EXCLUDED.author,
+            updated_at = NOW()
+        RETURNING {}
+        "#,
+        OVERRIDE_COLUMNS
+    ))
+    .bind(project_id)
+    .bind(milestone_id)
+    .bind(field)
+    .bind(value)
+    .bind(reason)
+    .bind(author)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit(&mut tx, AuditRecord {
+        action: "set_override",
+        project_id,
+        milestone_id,
+        field: Some(field),
+        old_value: previous.as_ref().map(|(v,)| v.as_str()),
+        new_value: Some(value),
+        reason: Some(reason),
+        author,
+        actor,
+    })
+    .await?;
+
+    tx.commit().await?;
+    Ok(row)
+}
+
+/// Remove an override, restoring the chain value. `None` if no such override.
+pub async fn remove_override(
+    pool: &PgPool,
+    id: i32,
+    request: &RemoveCurationRequest,
+    actor: &str,
+) -> Result<Option<OverrideResponse>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query_as::<_, OverrideResponse>(&format!(
+        "DELETE FROM treasury.curation_overrides WHERE id = $1 RETURNING {}",
+        OVERRIDE_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(ref o) = row {
+        audit(&mut tx, AuditRecord {
+            action: "remove_override",
+            project_id: &o.project_id,
+            milestone_id: o.milestone_id.as_deref(),
+            field: Some(&o.field),
+            old_value: Some(&o.value),
+            new_value: None,
+            reason: Some(&request.reason),
+            author: &request.author,
+            actor,
+        })
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(row)
+}
+
+/// Attach a note to a vendor contract or milestone
+pub async fn add_annotation(
+    pool: &PgPool,
+    request: &AddAnnotationRequest,
+    actor: &str,
+) -> Result<AnnotationResponse, sqlx::Error> {
+    let AddAnnotationRequest { ref project_id, ref note, ref author, .. } = *request;
+    let milestone_id = request.milestone_id.as_deref();
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query_as::<_, AnnotationResponse>(&format!(
+        r#"
+        INSERT INTO treasury.curation_annotations (project_id, milestone_id, note, author)
+        VALUES ($1, $2, $3, $4)
+        RETURNING {}
+        "#,
+        ANNOTATION_COLUMNS
+    ))
+    .bind(project_id)
+    .bind(milestone_id)
+    .bind(note)
+    .bind(author)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    audit(&mut tx, AuditRecord {
+        action: "add_annotation",
+        project_id,
+        milestone_id,
+        field: None,
+        old_value: None,
+        new_value: Some(note),
+        reason: None,
+        author,
+        actor,
+    })
+    .await?;
+
+    tx.commit().await?;
+    Ok(row)
+}
+
+/// Remove an annotation. `None` if no such annotation.
+pub async fn remove_annotation(
+    pool: &PgPool,
+    id: i32,
+    request: &RemoveCurationRequest,
+    actor: &str,
+) -> Result<Option<AnnotationResponse>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query_as::<_, AnnotationResponse>(&format!(
+        "DELETE FROM treasury.curation_annotations WHERE id = $1 RETURNING {}",
+        ANNOTATION_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(ref a) = row {
+        audit(&mut tx, AuditRecord {
+            action: "remove_annotation",
+            project_id: &a.project_id,
+            milestone_id: a.milestone_id.as_deref(),
+            field: None,
+            old_value: Some(&a.note),
+            new_value: None,
+            reason: Some(&request.reason),
+            author: &request.author,
+            actor,
+        })
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(row)
+}
+
+/// Every override, by target
+pub async fn list_overrides(pool: &PgPool) -> Result<Vec<OverrideResponse>, sqlx::Error> {
+    sqlx::query_as::<_, OverrideResponse>(&format!(
+        "SELECT {} FROM treasury.curation_overrides ORDER BY project_id, milestone_id NULLS FIRST, field",
+        OVERRIDE_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+}
+
+/// Every annotation, oldest first
+pub async fn list_annotations(pool: &PgPool) -> Result<Vec<AnnotationResponse>, sqlx::Error> {
+    sqlx::query_as::<_, AnnotationResponse>(&format!(
+        "SELECT {} FROM treasury.curation_annotations ORDER BY id",
+        ANNOTATION_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+}
+
+/// A page of the audit log, newest first, with the total number of entries
+pub async fn audit_log(
+    pool: &PgPool,
+    project_id: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<AuditLogEntry>, i64), sqlx::Error> {
+    let (total,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM treasury.curation_audit_log WHERE $1::TEXT IS NULL OR project_id = $1"
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await?;
+
+    let entries = sqlx::query_as::<_, AuditLogEntry>(
+        r#"
+        SELECT id, action, project_id, milestone_id, field, old_value, new_value, reason, author, actor, at
+        FROM treasury.curation_audit_log
+        WHERE $1::TEXT IS NULL OR project_id = $1
+        ORDER BY id DESC
+        LIMIT $2 OFFSET $3
+        "#
+    )
+    .bind(project_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+    Ok((entries, total))
+}
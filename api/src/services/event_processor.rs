@@ -3,40 +3,35 @@
 //! Processes TOM (Treasury Oversight Metadata) events and updates the
 //! normalized treasury schema tables.
 
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use serde_json::Value;
+use std::sync::Arc;
 
-use super::sync::RawTomEvent;
+use super::revisions::SNAPSHOT_SQL;
+use super::summaries;
+use crate::chain::{ChainSource, OutputRef, RawTomEvent, TxOutput, TOM_LABEL};
+use crate::metrics::METRICS;
 
 /// Event processor for TOM metadata
 pub struct EventProcessor {
     pool: PgPool,
+    chain: Arc<dyn ChainSource>,
 }
 
 impl EventProcessor {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, chain: Arc<dyn ChainSource>) -> Self {
+        Self { pool, chain }
+    }
+
+    /// Source the processor reads chain data from
+    pub fn chain(&self) -> &dyn ChainSource {
+        self.chain.as_ref()
     }
 
     /// Sync all events from the beginning (for initial sync)
     pub async fn sync_all_events(&self) -> anyhow::Result<()> {
         // Get all TOM events ordered by slot
-        let rows = sqlx::query_as::<_, RawTomEvent>(
-            r#"
-            SELECT
-                m.tx_hash,
-                m.slot,
-                m.body::jsonb as body,
-                b.number as block_number,
-                b.block_time
-            FROM yaci_store.transaction_metadata m
-            JOIN yaci_store.block b ON b.slot = m.slot
-            WHERE m.label = '1694'
-            ORDER BY m.slot ASC
-            "#
-        )
-        .fetch_all(&self.pool)
-        .await?;
+        let rows = self.chain.metadata_since(TOM_LABEL, -1, None).await?;
 
         tracing::info!("Processing {} total TOM events", rows.len());
 
@@ -55,7 +50,7 @@ impl EventProcessor {
         if let Some(last) = rows.last() {
             sqlx::query(
                 r#"
-                UPDATE treasury.sync_status
+                UPDATE sync_status
                 SET last_slot = $1, last_block = $2, last_tx_hash = $3, updated_at = NOW()
                 WHERE sync_type = 'events'
                 "#
@@ -72,6 +67,15 @@ impl EventProcessor {
 
     /// Process a single TOM event
     pub async fn process_event(&self, event: &RawTomEvent) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        self.process_event_in(&mut tx, event).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Process an event inside the caller's transaction, e.g. together with
+    /// removing a previous copy of it
+    pub async fn process_event_in(&self, conn: &mut PgConnection, event: &RawTomEvent) -> anyhow::Result<()> {
         let body = match &event.body {
             Some(b) => b,
             None => return Ok(()), // No body, skip
@@ -87,29 +91,44 @@ impl EventProcessor {
             .and_then(|i| i.as_str())
             .unwrap_or("");
 
-        match event_type.as_str() {
-            "publish" => self.process_publish(event, body, instance).await?,
-            "initialize" => self.process_initialize(event, body, instance).await?,
-            "fund" => self.process_fund(event, body, instance).await?,
-            "complete" => self.process_complete(event, body).await?,
-            "disburse" => self.process_disburse(event, body).await?,
-            "withdraw" => self.process_withdraw(event, body).await?,
-            "pause" => self.process_pause(event, body).await?,
-            "resume" => self.process_resume(event, body).await?,
-            "modify" => self.process_modify(event, body).await?,
-            "cancel" => self.process_cancel(event, body).await?,
-            "sweep" | "sweeptreasury" | "sweepvendor" => self.process_sweep(event, body, instance).await?,
-            "reorganize" => self.process_reorganize(event, body, instance).await?,
+        let result = self.apply_event(conn, event, body, &event_type, instance).await;
+        let metric_type = match event_type.as_str() {
+            "sweeptreasury" | "sweepvendor" => "sweep",
+            other => other,
+        };
+        METRICS.record_event(metric_type, result.is_ok());
+        result
+    }
+
+    /// Dispatch an event by type, then refresh the summaries it affected.
+    /// Everything runs in the caller's transaction so a failed event leaves no partial writes.
+    async fn apply_event(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value, event_type: &str, instance: &str) -> anyhow::Result<()> {
+        match event_type {
+            "publish" => self.process_publish(conn, event, body, instance).await?,
+            "initialize" => self.process_initialize(conn, event, body, instance).await?,
+            "fund" => self.process_fund(conn, event, body, instance).await?,
+            "complete" => self.process_complete(conn, event, body).await?,
+            "disburse" => self.process_disburse(conn, event, body).await?,
+            "withdraw" => self.process_withdraw(conn, event, body).await?,
+            "pause" => self.process_pause(conn, event, body).await?,
+            "resume" => self.process_resume(conn, event, body).await?,
+            "modify" => self.process_modify(conn, event, body).await?,
+            "cancel" => self.process_cancel(conn, event, body).await?,
+            "sweep" | "sweeptreasury" | "sweepvendor" => self.process_sweep(conn, event, body, instance).await?,
+            "reorganize" => self.process_reorganize(conn, event, body, instance).await?,
             _ => {
                 tracing::debug!("Unknown event type: {}", event_type);
             }
         }
 
+        // Keep the summary rows of everything this transaction touched current
+        summaries::refresh_for_tx(&mut *conn, &event.tx_hash).await?;
+
         Ok(())
     }
 
     /// Process a publish event - create treasury contract
-    async fn process_publish(&self, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
+    async fn process_publish(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
         let name = extract_text(event_body, "label");
         let permissions = event_body.get("permissions").cloned();
@@ -117,13 +136,13 @@ impl EventProcessor {
         // Upsert treasury contract
         let treasury_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO treasury.treasury_contracts (contract_instance, name, publish_tx_hash, publish_time, permissions)
+            INSERT INTO treasury_contracts (contract_instance, name, publish_tx_hash, publish_time, permissions)
             VALUES ($1, $2, $3, $4, $5)
             ON CONFLICT (contract_instance) DO UPDATE
-                SET name = COALESCE(EXCLUDED.name, treasury.treasury_contracts.name),
-                    publish_tx_hash = COALESCE(treasury.treasury_contracts.publish_tx_hash, EXCLUDED.publish_tx_hash),
-                    publish_time = COALESCE(treasury.treasury_contracts.publish_time, EXCLUDED.publish_time),
-                    permissions = COALESCE(EXCLUDED.permissions, treasury.treasury_contracts.permissions)
+                SET name = COALESCE(EXCLUDED.name, treasury_contracts.name),
+                    publish_tx_hash = COALESCE(treasury_contracts.publish_tx_hash, EXCLUDED.publish_tx_hash),
+                    publish_time = COALESCE(treasury_contracts.publish_time, EXCLUDED.publish_time),
+                    permissions = COALESCE(EXCLUDED.permissions, treasury_contracts.permissions)
             RETURNING id
             "#
         )
@@ -132,41 +151,41 @@ impl EventProcessor {
         .bind(&event.tx_hash)
         .bind(event.block_time)
         .bind(&permissions)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *conn)
         .await?;
 
         // Insert event record
-        self.insert_event(event, "publish", Some(treasury_id), None, None, body).await?;
+        self.insert_event(conn, event, "publish", Some(treasury_id), None, None, body).await?;
 
         Ok(())
     }
 
     /// Process an initialize event - update treasury contract
-    async fn process_initialize(&self, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
+    async fn process_initialize(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
         // Upsert treasury contract
         let treasury_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO treasury.treasury_contracts (contract_instance, initialized_tx_hash, initialized_at)
+            INSERT INTO treasury_contracts (contract_instance, initialized_tx_hash, initialized_at)
             VALUES ($1, $2, $3)
             ON CONFLICT (contract_instance) DO UPDATE
-                SET initialized_tx_hash = COALESCE(treasury.treasury_contracts.initialized_tx_hash, EXCLUDED.initialized_tx_hash),
-                    initialized_at = COALESCE(treasury.treasury_contracts.initialized_at, EXCLUDED.initialized_at)
+                SET initialized_tx_hash = COALESCE(treasury_contracts.initialized_tx_hash, EXCLUDED.initialized_tx_hash),
+                    initialized_at = COALESCE(treasury_contracts.initialized_at, EXCLUDED.initialized_at)
             RETURNING id
             "#
         )
         .bind(instance)
         .bind(&event.tx_hash)
         .bind(event.block_time)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *conn)
         .await?;
 
-        self.insert_event(event, "initialize", Some(treasury_id), None, None, body).await?;
+        self.insert_event(conn, event, "initialize", Some(treasury_id), None, None, body).await?;
 
         Ok(())
     }
 
     /// Process a fund event - create vendor contract and milestones
-    async fn process_fund(&self, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
+    async fn process_fund(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         let project_id = event_body.get("identifier")
@@ -192,34 +211,31 @@ impl EventProcessor {
             .and_then(|o| o.as_array())
             .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect::<Vec<_>>());
 
-        // Get contract address from fund tx output
-        let contract_address: Option<String> = sqlx::query_scalar(
-            "SELECT owner_addr FROM yaci_store.address_utxo WHERE tx_hash = $1 AND owner_addr LIKE 'addr1x%' LIMIT 1"
-        )
-        .bind(&event.tx_hash)
-        .fetch_optional(&self.pool)
-        .await?;
+        // The fund tx spends treasury UTXOs, so the script address of its inputs
+        // is the treasury address; the other script output is the project contract
+        let treasury_address = self.find_script_input_address(&event.tx_hash).await?;
 
-        // Get initial amount from fund tx output
-        let initial_amount: Option<i64> = sqlx::query_scalar(
-            "SELECT lovelace_amount FROM yaci_store.address_utxo WHERE tx_hash = $1 AND owner_addr LIKE 'addr1x%' LIMIT 1"
-        )
-        .bind(&event.tx_hash)
-        .fetch_optional(&self.pool)
-        .await?;
+        // Get contract address and initial amount from fund tx output
+        let project_output = self.chain.tx_outputs(&event.tx_hash).await?
+            .into_iter()
+            .find(|o| o.address.starts_with("addr1x") && Some(&o.address) != treasury_address.as_ref());
+        let contract_address = project_output.as_ref().map(|o| o.address.clone());
+        let initial_amount = project_output.map(|o| o.lovelace);
 
         // Get or create treasury contract
         let treasury_id: Option<i32> = if !instance.is_empty() {
             sqlx::query_scalar(
                 r#"
-                INSERT INTO treasury.treasury_contracts (contract_instance)
-                VALUES ($1)
-                ON CONFLICT (contract_instance) DO UPDATE SET contract_instance = EXCLUDED.contract_instance
+                INSERT INTO treasury_contracts (contract_instance, contract_address)
+                VALUES ($1, $2)
+                ON CONFLICT (contract_instance) DO UPDATE
+                    SET contract_address = COALESCE(treasury_contracts.contract_address, EXCLUDED.contract_address)
                 RETURNING id
                 "#
             )
             .bind(instance)
-            .fetch_optional(&self.pool)
+            .bind(&treasury_address)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
             None
@@ -228,15 +244,17 @@ impl EventProcessor {
         // Insert vendor contract
         let vendor_contract_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO treasury.vendor_contracts (
+            INSERT INTO vendor_contracts (
                 treasury_id, project_id, other_identifiers, project_name, description,
                 vendor_name, vendor_address, contract_url, contract_address,
                 fund_tx_hash, fund_slot, fund_block_time, initial_amount_lovelace, status
             )
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, 'active')
             ON CONFLICT (project_id) DO UPDATE
-                SET project_name = COALESCE(EXCLUDED.project_name, treasury.vendor_contracts.project_name),
-                    description = COALESCE(EXCLUDED.description, treasury.vendor_contracts.description)
+                SET project_name = COALESCE(EXCLUDED.project_name, vendor_contracts.project_name),
+                    description = COALESCE(EXCLUDED.description, vendor_contracts.description),
+                    contract_address = COALESCE(EXCLUDED.contract_address, vendor_contracts.contract_address),
+                    initial_amount_lovelace = COALESCE(EXCLUDED.initial_amount_lovelace, vendor_contracts.initial_amount_lovelace)
             RETURNING id
             "#
         )
@@ -253,7 +271,7 @@ impl EventProcessor {
         .bind(event.slot)
         .bind(event.block_time)
         .bind(initial_amount)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *conn)
         .await?;
 
         // Process milestones
@@ -268,14 +286,15 @@ impl EventProcessor {
                 let acceptance_criteria = extract_text_from_value(Some(milestone.get("acceptanceCriteria").unwrap_or(&Value::Null)));
                 let amount = milestone.get("amount")
                     .and_then(|a| a.as_i64());
+                let maturity = extract_timestamp(milestone.get("maturity"));
 
                 sqlx::query(
                     r#"
-                    INSERT INTO treasury.milestones (
+                    INSERT INTO milestones (
                         vendor_contract_id, milestone_id, milestone_order, label,
-                        description, acceptance_criteria, amount_lovelace, status
+                        description, acceptance_criteria, amount_lovelace, maturity, status
                     )
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, 'pending')
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'pending')
                     ON CONFLICT (vendor_contract_id, milestone_id) DO NOTHING
                     "#
                 )
@@ -286,51 +305,23 @@ impl EventProcessor {
                 .bind(&description)
                 .bind(&acceptance_criteria)
                 .bind(amount)
-                .execute(&self.pool)
+                .bind(maturity)
+                .execute(&mut *conn)
                 .await?;
             }
         }
 
-        self.insert_event(event, "fund", treasury_id, Some(vendor_contract_id), None, body).await?;
+        self.insert_event(conn, event, "fund", treasury_id, Some(vendor_contract_id), None, body).await?;
+        self.record_revision(conn, vendor_contract_id, event, "fund", &None).await?;
 
         // Record the output UTXOs from this fund transaction for future lookups
-        // Get all outputs from the transaction table
-        let outputs: Option<serde_json::Value> = sqlx::query_scalar(
-            "SELECT outputs::jsonb FROM yaci_store.transaction WHERE tx_hash = $1"
-        )
-        .bind(&event.tx_hash)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        if let Some(serde_json::Value::Array(output_arr)) = outputs {
-            for output in output_arr {
-                if let (Some(tx_hash), Some(output_index)) = (
-                    output.get("tx_hash").and_then(|h| h.as_str()),
-                    output.get("output_index").and_then(|i| i.as_i64())
-                ) {
-                    // Record this UTXO with the vendor_contract_id for future event lookups
-                    sqlx::query(
-                        r#"
-                        INSERT INTO treasury.utxos (tx_hash, output_index, vendor_contract_id, slot, spent)
-                        VALUES ($1, $2, $3, $4, false)
-                        ON CONFLICT (tx_hash, output_index) DO NOTHING
-                        "#
-                    )
-                    .bind(tx_hash)
-                    .bind(output_index as i16)
-                    .bind(vendor_contract_id)
-                    .bind(event.slot)
-                    .execute(&self.pool)
-                    .await?;
-                }
-            }
-        }
+        self.record_tx_outputs(conn, &event.tx_hash, Some(vendor_contract_id)).await?;
 
         Ok(())
     }
 
     /// Process a complete event - update milestone status
-    async fn process_complete(&self, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
+    async fn process_complete(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         // First try to get project_id from metadata (older format)
@@ -341,14 +332,14 @@ impl EventProcessor {
         // Get vendor contract ID - either from metadata or by tracing tx chain
         let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
             sqlx::query_scalar(
-                "SELECT id FROM treasury.vendor_contracts WHERE project_id = $1"
+                "SELECT id FROM vendor_contracts WHERE project_id = $1"
             )
             .bind(pid)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
             // Trace back through transaction chain to find the project
-            self.find_vendor_contract_from_inputs(&event.tx_hash).await?
+            self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await?
         };
 
         let vendor_contract_id = match vendor_contract_id {
@@ -369,7 +360,7 @@ impl EventProcessor {
 
                     let db_milestone_id: Option<i32> = sqlx::query_scalar(
                         r#"
-                        UPDATE treasury.milestones
+                        UPDATE milestones
                         SET status = 'completed',
                             complete_tx_hash = $1,
                             complete_time = $2,
@@ -385,11 +376,11 @@ impl EventProcessor {
                     .bind(&evidence)
                     .bind(vendor_contract_id)
                     .bind(milestone_id)
-                    .fetch_optional(&self.pool)
+                    .fetch_optional(&mut *conn)
                     .await?;
 
                     if let Some(mid) = db_milestone_id {
-                        self.insert_event(event, "complete", None, Some(vendor_contract_id), Some(mid), body).await?;
+                        self.insert_event(conn, event, "complete", None, Some(vendor_contract_id), Some(mid), body).await?;
                     }
                 }
             }
@@ -399,7 +390,7 @@ impl EventProcessor {
         if let Some(milestone_id) = event_body.get("milestone").and_then(|m| m.as_str()) {
             sqlx::query(
                 r#"
-                UPDATE treasury.milestones
+                UPDATE milestones
                 SET status = 'completed',
                     complete_tx_hash = $1,
                     complete_time = $2
@@ -410,7 +401,7 @@ impl EventProcessor {
             .bind(event.block_time)
             .bind(vendor_contract_id)
             .bind(milestone_id)
-            .execute(&self.pool)
+            .execute(&mut *conn)
             .await?;
         }
 
@@ -418,7 +409,7 @@ impl EventProcessor {
     }
 
     /// Process a disburse event - update milestone status
-    async fn process_disburse(&self, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
+    async fn process_disburse(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         let project_id_from_meta = event_body.get("identifier")
@@ -430,28 +421,29 @@ impl EventProcessor {
         // Get vendor contract ID - either from metadata or by tracing tx chain
         let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
             sqlx::query_scalar(
-                "SELECT id FROM treasury.vendor_contracts WHERE project_id = $1"
+                "SELECT id FROM vendor_contracts WHERE project_id = $1"
             )
             .bind(pid)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
-            self.find_vendor_contract_from_inputs(&event.tx_hash).await?
+            self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await?
         };
 
-        // Get disbursed amount from tx outputs - cast SUM to BIGINT
-        let disburse_amount: Option<i64> = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(lovelace_amount)::bigint, 0) FROM yaci_store.address_utxo WHERE tx_hash = $1 AND owner_addr NOT LIKE 'addr1x%'"
-        )
-        .bind(&event.tx_hash)
-        .fetch_optional(&self.pool)
-        .await?;
+        // Disbursed amount: everything paid to non-script addresses
+        let disburse_amount: Option<i64> = Some(
+            self.chain.tx_outputs(&event.tx_hash).await?
+                .iter()
+                .filter(|o| !o.address.starts_with("addr1x"))
+                .map(|o| o.lovelace)
+                .sum(),
+        );
 
         // Check for milestone field and update if present
         let db_milestone_id: Option<i32> = if let (Some(vc_id), Some(milestone_id)) = (vendor_contract_id, event_body.get("milestone").and_then(|m| m.as_str())) {
             sqlx::query_scalar(
                 r#"
-                UPDATE treasury.milestones
+                UPDATE milestones
                 SET status = 'disbursed',
                     disburse_tx_hash = $1,
                     disburse_time = $2,
@@ -465,20 +457,20 @@ impl EventProcessor {
             .bind(disburse_amount)
             .bind(vc_id)
             .bind(milestone_id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
             None
         };
 
         // Always insert the disburse event (may be treasury-level without vendor_contract)
-        self.insert_event_with_destination(event, "disburse", None, vendor_contract_id, db_milestone_id, &destination, body).await?;
+        self.insert_event_with_amount(conn, event, "disburse", None, vendor_contract_id, db_milestone_id, disburse_amount, &destination, body).await?;
 
         Ok(())
     }
 
-    /// Process a withdraw event
-    async fn process_withdraw(&self, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
+    /// Process a withdraw event - vendor withdraws funds from the project contract
+    async fn process_withdraw(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         let project_id_from_meta = event_body.get("identifier")
@@ -487,27 +479,77 @@ impl EventProcessor {
 
         // Get vendor contract ID - either from metadata or by tracing tx chain
         let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
-            sqlx::query_scalar(
-                "SELECT id FROM treasury.vendor_contracts WHERE project_id = $1"
+            let vc_id: Option<i32> = sqlx::query_scalar(
+                "SELECT id FROM vendor_contracts WHERE project_id = $1"
             )
             .bind(pid)
-            .fetch_optional(&self.pool)
-            .await?
+            .fetch_optional(&mut *conn)
+            .await?;
+            if let Some(vc_id) = vc_id {
+                self.transfer_utxos(conn, &event.tx_hash, vc_id).await?;
+            }
+            vc_id
         } else {
-            self.find_vendor_contract_from_inputs(&event.tx_hash).await?
+            self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await?
         };
 
-        if let Some(vc_id) = vendor_contract_id {
-            self.insert_event(event, "withdraw", None, Some(vc_id), None, body).await?;
-        } else {
-            tracing::debug!("Could not find vendor contract for withdraw event {}", event.tx_hash);
+        let vc_id = match vendor_contract_id {
+            Some(id) => id,
+            None => {
+                tracing::debug!("Could not find vendor contract for withdraw event {}", event.tx_hash);
+                return Ok(());
+            }
+        };
+
+        // Funds leaving the project contract are the withdrawn amount
+        let flow = self.outflow_from_project(conn, &event.tx_hash, vc_id).await?;
+
+        // Mark withdrawn milestones (single `milestone` or a `milestones` list/object)
+        let mut milestone_ids: Vec<String> = Vec::new();
+        if let Some(m) = event_body.get("milestone").and_then(|m| m.as_str()) {
+            milestone_ids.push(m.to_string());
+        }
+        match event_body.get("milestones") {
+            Some(Value::Array(arr)) => milestone_ids.extend(arr.iter().filter_map(|m| m.as_str()).map(|m| m.to_string())),
+            Some(Value::Object(obj)) => milestone_ids.extend(obj.keys().cloned()),
+            _ => {}
         }
 
+        let mut db_milestone_id: Option<i32> = None;
+        for milestone_id in &milestone_ids {
+            let mid: Option<i32> = sqlx::query_scalar(
+                r#"
+                UPDATE milestones
+                SET status = 'withdrawn', updated_at = NOW()
+                WHERE vendor_contract_id = $1 AND milestone_id = $2 AND status IN ('pending', 'completed')
+                RETURNING id
+                "#
+            )
+            .bind(vc_id)
+            .bind(milestone_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+            db_milestone_id = db_milestone_id.or(mid);
+        }
+
+        // A withdrawal that empties the contract closes out the project; if the
+        // remainder cannot be worked out the status is left as it is
+        if flow.remaining_lovelace == Some(0) {
+            sqlx::query(
+                "UPDATE vendor_contracts SET status = 'completed', updated_at = NOW() WHERE id = $1 AND status IN ('active', 'paused')"
+            )
+            .bind(vc_id)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        self.insert_event_with_amount(conn, event, "withdraw", None, Some(vc_id), db_milestone_id, flow.amount, &flow.destination, body).await?;
+
         Ok(())
     }
 
     /// Process a pause event - set vendor contract status
-    async fn process_pause(&self, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
+    async fn process_pause(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         let project_id_from_meta = event_body.get("identifier")
@@ -518,17 +560,17 @@ impl EventProcessor {
         // Get vendor contract ID - either from metadata or by tracing tx chain
         let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
             sqlx::query_scalar(
-                "UPDATE treasury.vendor_contracts SET status = 'paused' WHERE project_id = $1 RETURNING id"
+                "UPDATE vendor_contracts SET status = 'paused' WHERE project_id = $1 RETURNING id"
             )
             .bind(pid)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
             // Find via tx chain first, then update
-            if let Some(vc_id) = self.find_vendor_contract_from_inputs(&event.tx_hash).await? {
-                sqlx::query("UPDATE treasury.vendor_contracts SET status = 'paused' WHERE id = $1")
+            if let Some(vc_id) = self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await? {
+                sqlx::query("UPDATE vendor_contracts SET status = 'paused' WHERE id = $1")
                     .bind(vc_id)
-                    .execute(&self.pool)
+                    .execute(&mut *conn)
                     .await?;
                 Some(vc_id)
             } else {
@@ -537,7 +579,7 @@ impl EventProcessor {
         };
 
         if let Some(vc_id) = vendor_contract_id {
-            self.insert_event_with_reason(event, "pause", None, Some(vc_id), None, &reason, body).await?;
+            self.insert_event_with_reason(conn, event, "pause", None, Some(vc_id), None, &reason, body).await?;
         } else {
             tracing::debug!("Could not find vendor contract for pause event {}", event.tx_hash);
         }
@@ -546,7 +588,7 @@ impl EventProcessor {
     }
 
     /// Process a resume event - set vendor contract status
-    async fn process_resume(&self, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
+    async fn process_resume(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         let project_id_from_meta = event_body.get("identifier")
@@ -556,16 +598,16 @@ impl EventProcessor {
         // Get vendor contract ID - either from metadata or by tracing tx chain
         let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
             sqlx::query_scalar(
-                "UPDATE treasury.vendor_contracts SET status = 'active' WHERE project_id = $1 RETURNING id"
+                "UPDATE vendor_contracts SET status = 'active' WHERE project_id = $1 RETURNING id"
             )
             .bind(pid)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
-            if let Some(vc_id) = self.find_vendor_contract_from_inputs(&event.tx_hash).await? {
-                sqlx::query("UPDATE treasury.vendor_contracts SET status = 'active' WHERE id = $1")
+            if let Some(vc_id) = self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await? {
+                sqlx::query("UPDATE vendor_contracts SET status = 'active' WHERE id = $1")
                     .bind(vc_id)
-                    .execute(&self.pool)
+                    .execute(&mut *conn)
                     .await?;
                 Some(vc_id)
             } else {
@@ -574,7 +616,7 @@ impl EventProcessor {
         };
 
         if let Some(vc_id) = vendor_contract_id {
-            self.insert_event(event, "resume", None, Some(vc_id), None, body).await?;
+            self.insert_event(conn, event, "resume", None, Some(vc_id), None, body).await?;
         } else {
             tracing::debug!("Could not find vendor contract for resume event {}", event.tx_hash);
         }
@@ -582,8 +624,8 @@ impl EventProcessor {
         Ok(())
     }
 
-    /// Process a modify event - update vendor contract
-    async fn process_modify(&self, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
+    /// Process a modify event - apply the amendment and record a new revision
+    async fn process_modify(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         let project_id_from_meta = event_body.get("identifier")
@@ -594,17 +636,26 @@ impl EventProcessor {
         // Get vendor contract ID - either from metadata or by tracing tx chain
         let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
             sqlx::query_scalar(
-                "SELECT id FROM treasury.vendor_contracts WHERE project_id = $1"
+                "SELECT id FROM vendor_contracts WHERE project_id = $1"
             )
             .bind(pid)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
-            self.find_vendor_contract_from_inputs(&event.tx_hash).await?
+            self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await?
         };
 
         if let Some(vc_id) = vendor_contract_id {
-            self.insert_event_with_reason(event, "modify", None, Some(vc_id), None, &reason, body).await?;
+            // Keep the pre-amendment terms if the fund revision was never recorded
+            self.ensure_base_revision(conn, vc_id).await?;
+            // Reprocessing an older amendment must not overwrite newer terms
+            if self.has_later_revision(conn, vc_id, event).await? {
+                tracing::debug!("Skipping superseded modify event {}", event.tx_hash);
+            } else {
+                self.apply_modification(conn, vc_id, event_body).await?;
+                self.record_revision(conn, vc_id, event, "modify", &reason).await?;
+            }
+            self.insert_event_with_reason(conn, event, "modify", None, Some(vc_id), None, &reason, body).await?;
         } else {
             tracing::debug!("Could not find vendor contract for modify event {}", event.tx_hash);
         }
@@ -612,8 +663,207 @@ impl EventProcessor {
         Ok(())
     }
 
+    /// Apply the contract fields and milestone schedule from modify metadata.
+    /// Only fields present in the metadata are changed. A `milestones` array
+    /// replaces the schedule: listed milestones are added or re-priced, and
+    /// pending milestones missing from it are marked `removed`.
+    async fn apply_modification(&self, conn: &mut PgConnection, vendor_contract_id: i32, event_body: &Value) -> anyhow::Result<()> {
+        let project_name = extract_text(event_body, "label");
+        let description = extract_text(event_body, "description");
+        let vendor_name = event_body.get("vendor")
+            .and_then(|v| v.get("name"))
+            .and_then(|n| n.as_str())
+            .map(|s| s.to_string());
+        let vendor_address = event_body.get("vendor")
+            .and_then(|v| extract_text_from_value(v.get("label")));
+        let contract_url = event_body.get("contract")
+            .and_then(|c| c.as_str())
+            .map(|s| s.to_string());
+        let other_identifiers = event_body.get("otherIdentifiers")
+            .and_then(|o| o.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect::<Vec<_>>());
+
+        sqlx::query(
+            r#"
+            UPDATE vendor_contracts
+            SET project_name = COALESCE($2, project_name),
+                description = COALESCE($3, description),
+                vendor_name = COALESCE($4, vendor_name),
+                vendor_address = COALESCE($5, vendor_address),
+                contract_url = COALESCE($6, contract_url),
+                other_identifiers = COALESCE($7, other_identifiers),
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(vendor_contract_id)
+        .bind(&project_name)
+        .bind(&description)
+        .bind(&vendor_name)
+        .bind(&vendor_address)
+        .bind(&contract_url)
+        .bind(&other_identifiers)
+        .execute(&mut *conn)
+        .await?;
+
+        let milestones = match event_body.get("milestones").and_then(|m| m.as_array()) {
+            Some(m) => m,
+            None => return Ok(()),
+        };
+
+        let mut scheduled_ids = Vec::with_capacity(milestones.len());
+
+        for (idx, milestone) in milestones.iter().enumerate() {
+            let default_id = format!("m-{}", idx);
+            let milestone_id = milestone.get("identifier")
+                .and_then(|i| i.as_str())
+                .unwrap_or(&default_id)
+                .to_string();
+            let label = extract_text_from_value(Some(milestone.get("label").unwrap_or(&Value::Null)));
+            let description = extract_text_from_value(Some(milestone.get("description").unwrap_or(&Value::Null)));
+            let acceptance_criteria = extract_text_from_value(Some(milestone.get("acceptanceCriteria").unwrap_or(&Value::Null)));
+            let amount = milestone.get("amount")
+                .and_then(|a| a.as_i64());
+            let maturity = extract_timestamp(milestone.get("maturity"));
+
+            sqlx::query(
+                r#"
+                INSERT INTO milestones (
+                    vendor_contract_id, milestone_id, milestone_order, label,
+                    description, acceptance_criteria, amount_lovelace, maturity, status
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'pending')
+                ON CONFLICT (vendor_contract_id, milestone_id) DO UPDATE
+                    SET milestone_order = EXCLUDED.milestone_order,
+                        label = COALESCE(EXCLUDED.label, milestones.label),
+                        description = COALESCE(EXCLUDED.description, milestones.description),
+                        acceptance_criteria = COALESCE(EXCLUDED.acceptance_criteria, milestones.acceptance_criteria),
+                        amount_lovelace = COALESCE(EXCLUDED.amount_lovelace, milestones.amount_lovelace),
+                        maturity = COALESCE(EXCLUDED.maturity, milestones.maturity),
+                        status = CASE WHEN milestones.status = 'removed' THEN 'pending' ELSE milestones.status END,
+                        updated_at = NOW()
+                "#
+            )
+            .bind(vendor_contract_id)
+            .bind(&milestone_id)
+            .bind((idx + 1) as i32)
+            .bind(&label)
+            .bind(&description)
+            .bind(&acceptance_criteria)
+            .bind(amount)
+            .bind(maturity)
+            .execute(&mut *conn)
+            .await?;
+
+            scheduled_ids.push(milestone_id);
+        }
+
+        // Milestones dropped from the schedule are kept (events may reference them)
+        // but flagged; completed or disbursed milestones are history and stay as-is
+        sqlx::query(
+            r#"
+            UPDATE milestones
+            SET status = 'removed', updated_at = NOW()
+            WHERE vendor_contract_id = $1 AND status = 'pending' AND NOT (milestone_id = ANY($2))
+            "#
+        )
+        .bind(vendor_contract_id)
+        .bind(&scheduled_ids)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the current contract terms as a new revision.
+    /// Reprocessing the same transaction keeps the originally recorded revision.
+    async fn record_revision(
+        &self,
+        conn: &mut PgConnection,
+        vendor_contract_id: i32,
+        event: &RawTomEvent,
+        event_type: &str,
+        reason: &Option<String>,
+    ) -> anyhow::Result<()> {
+        let query = format!(
+            r#"
+            INSERT INTO vendor_contract_revisions (
+                vendor_contract_id, revision, tx_hash, slot, block_time, event_type, reason, snapshot
+            )
+            SELECT
+                $1,
+                COALESCE((SELECT MAX(revision) FROM vendor_contract_revisions WHERE vendor_contract_id = $1), 0) + 1,
+                $2, $3, $4, $5, $6,
+                ({})
+            ON CONFLICT (vendor_contract_id, tx_hash) DO NOTHING
+            "#,
+            SNAPSHOT_SQL
+        );
+
+        sqlx::query(&query)
+            .bind(vendor_contract_id)
+            .bind(&event.tx_hash)
+            .bind(event.slot)
+            .bind(event.block_time)
+            .bind(event_type)
+            .bind(reason)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether the contract has a revision recorded after `event`: one in a
+    /// later slot, or one numbered after the event's own revision (a later
+    /// transaction in the same slot)
+    async fn has_later_revision(&self, conn: &mut PgConnection, vendor_contract_id: i32, event: &RawTomEvent) -> anyhow::Result<bool> {
+        let later: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM vendor_contract_revisions l
+                WHERE l.vendor_contract_id = $1
+                  AND (l.slot > $3 OR l.revision > (
+                      SELECT r.revision FROM vendor_contract_revisions r
+                      WHERE r.vendor_contract_id = $1 AND r.tx_hash = $2
+                  ))
+            )
+            "#
+        )
+        .bind(vendor_contract_id)
+        .bind(&event.tx_hash)
+        .bind(event.slot)
+        .fetch_one(&mut *conn)
+        .await?;
+        Ok(later)
+    }
+
+    /// Record the current terms as the fund revision if the contract has no revisions yet
+    /// (contracts funded before revision tracking existed)
+    async fn ensure_base_revision(&self, conn: &mut PgConnection, vendor_contract_id: i32) -> anyhow::Result<()> {
+        let query = format!(
+            r#"
+            INSERT INTO vendor_contract_revisions (
+                vendor_contract_id, revision, tx_hash, slot, block_time, event_type, snapshot
+            )
+            SELECT vc.id, 1, vc.fund_tx_hash, vc.fund_slot, vc.fund_block_time, 'fund', ({})
+            FROM vendor_contracts vc
+            WHERE vc.id = $1
+              AND NOT EXISTS (SELECT 1 FROM vendor_contract_revisions WHERE vendor_contract_id = $1)
+            ON CONFLICT DO NOTHING
+            "#,
+            SNAPSHOT_SQL
+        );
+
+        sqlx::query(&query)
+            .bind(vendor_contract_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
     /// Process a cancel event - set vendor contract status
-    async fn process_cancel(&self, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
+    async fn process_cancel(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value) -> anyhow::Result<()> {
         let event_body = body.get("body").unwrap_or(body);
 
         let project_id_from_meta = event_body.get("identifier")
@@ -624,16 +874,16 @@ impl EventProcessor {
         // Get vendor contract ID - either from metadata or by tracing tx chain
         let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
             sqlx::query_scalar(
-                "UPDATE treasury.vendor_contracts SET status = 'cancelled' WHERE project_id = $1 RETURNING id"
+                "UPDATE vendor_contracts SET status = 'cancelled' WHERE project_id = $1 RETURNING id"
             )
             .bind(pid)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
-            if let Some(vc_id) = self.find_vendor_contract_from_inputs(&event.tx_hash).await? {
-                sqlx::query("UPDATE treasury.vendor_contracts SET status = 'cancelled' WHERE id = $1")
+            if let Some(vc_id) = self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await? {
+                sqlx::query("UPDATE vendor_contracts SET status = 'cancelled' WHERE id = $1")
                     .bind(vc_id)
-                    .execute(&self.pool)
+                    .execute(&mut *conn)
                     .await?;
                 Some(vc_id)
             } else {
@@ -642,7 +892,7 @@ impl EventProcessor {
         };
 
         if let Some(vc_id) = vendor_contract_id {
-            self.insert_event_with_reason(event, "cancel", None, Some(vc_id), None, &reason, body).await?;
+            self.insert_event_with_reason(conn, event, "cancel", None, Some(vc_id), None, &reason, body).await?;
         } else {
             tracing::debug!("Could not find vendor contract for cancel event {}", event.tx_hash);
         }
@@ -650,37 +900,152 @@ impl EventProcessor {
         Ok(())
     }
 
-    /// Process a sweep event
-    async fn process_sweep(&self, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
+    /// Process a sweep event - return project funds to the treasury, or
+    /// expired treasury funds to the Cardano treasury
+    async fn process_sweep(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
+        let event_body = body.get("body").unwrap_or(body);
+
         let treasury_id: Option<i32> = sqlx::query_scalar(
-            "SELECT id FROM treasury.treasury_contracts WHERE contract_instance = $1"
+            "SELECT id FROM treasury_contracts WHERE contract_instance = $1"
         )
         .bind(instance)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
-        self.insert_event(event, "sweep", treasury_id, None, None, body).await?;
+        let project_id_from_meta = event_body.get("identifier")
+            .and_then(|i| i.as_str())
+            .filter(|s| !s.is_empty());
+
+        // A sweep either spends a project contract (sweepvendor) or the treasury itself
+        let vendor_contract_id: Option<i32> = if let Some(pid) = project_id_from_meta {
+            let vc_id: Option<i32> = sqlx::query_scalar(
+                "SELECT id FROM vendor_contracts WHERE project_id = $1"
+            )
+            .bind(pid)
+            .fetch_optional(&mut *conn)
+            .await?;
+            if let Some(vc_id) = vc_id {
+                self.transfer_utxos(conn, &event.tx_hash, vc_id).await?;
+            }
+            vc_id
+        } else {
+            self.find_vendor_contract_from_inputs(conn, &event.tx_hash).await?
+        };
+
+        let donation = self.chain.treasury_donation(&event.tx_hash).await?;
+
+        let (amount, destination) = if let Some(vc_id) = vendor_contract_id {
+            let flow = self.outflow_from_project(conn, &event.tx_hash, vc_id).await?;
+
+            // Only a sweep that leaves nothing in the project contract retires it;
+            // if the remainder cannot be worked out, or a tracked output's amount
+            // is unknown, the status is left as it is
+            if flow.remaining_lovelace == Some(0) {
+                sqlx::query(
+                    r#"
+                    UPDATE vendor_contracts vc
+                    SET status = 'swept', updated_at = NOW()
+                    WHERE vc.id = $1
+                      AND NOT EXISTS (
+                          SELECT 1 FROM utxos u
+                          WHERE u.vendor_contract_id = vc.id AND NOT u.spent
+                            AND (u.lovelace_amount IS NULL OR u.lovelace_amount > 0)
+                      )
+                    "#
+                )
+                .bind(vc_id)
+                .execute(&mut *conn)
+                .await?;
+            }
+
+            (flow.amount, flow.destination)
+        } else {
+            let spent = self.spend_tx_inputs(conn, &event.tx_hash).await?;
+            self.record_tx_outputs(conn, &event.tx_hash, None).await?;
+
+            // Whatever did not return to the treasury address left the treasury
+            let treasury_address: Option<String> = match treasury_id {
+                Some(tid) => sqlx::query_scalar("SELECT contract_address FROM treasury_contracts WHERE id = $1")
+                    .bind(tid)
+                    .fetch_one(&mut *conn)
+                    .await?,
+                None => None,
+            };
+            let outputs = self.chain.tx_outputs(&event.tx_hash).await?;
+            let returned: i64 = outputs.iter()
+                .filter(|o| Some(&o.address) == treasury_address.as_ref())
+                .map(|o| o.lovelace)
+                .sum();
+            let spent_total: i64 = spent.iter().map(|(_, amount)| amount.unwrap_or(0)).sum();
+
+            if donation > 0 {
+                (Some(donation), Some("cardano_treasury".to_string()))
+            } else if spent_total > 0 {
+                let destination = outputs.iter()
+                    .filter(|o| Some(&o.address) != treasury_address.as_ref())
+                    .max_by_key(|o| o.lovelace)
+                    .map(|o| o.address.clone());
+                (Some((spent_total - returned).max(0)), destination)
+            } else {
+                (None, None)
+            }
+        };
+
+        // Treasury-level sweep that leaves nothing behind retires the treasury;
+        // an unspent output of unknown amount keeps it open
+        if vendor_contract_id.is_none() {
+            if let Some(tid) = treasury_id {
+                sqlx::query(
+                    r#"
+                    UPDATE treasury_contracts tc
+                    SET status = 'swept', updated_at = NOW()
+                    WHERE tc.id = $1
+                      AND tc.contract_address IS NOT NULL
+                      AND NOT EXISTS (
+                          SELECT 1 FROM utxos u
+                          WHERE u.address = tc.contract_address AND NOT u.spent
+                            AND (u.lovelace_amount IS NULL OR u.lovelace_amount > 0)
+                      )
+                    "#
+                )
+                .bind(tid)
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
+
+        self.insert_event_with_amount(conn, event, "sweep", treasury_id, vendor_contract_id, None, amount, &destination, body).await?;
 
         Ok(())
     }
 
-    /// Process a reorganize event
-    async fn process_reorganize(&self, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
+    /// Process a reorganize event - split/merge treasury UTXOs.
+    /// Spent inputs lose their attribution; each output is attributed to the
+    /// treasury or project whose address it lands at.
+    async fn process_reorganize(&self, conn: &mut PgConnection, event: &RawTomEvent, body: &Value, instance: &str) -> anyhow::Result<()> {
         let treasury_id: Option<i32> = sqlx::query_scalar(
-            "SELECT id FROM treasury.treasury_contracts WHERE contract_instance = $1"
+            "SELECT id FROM treasury_contracts WHERE contract_instance = $1"
         )
         .bind(instance)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
-        self.insert_event(event, "reorganize", treasury_id, None, None, body).await?;
+        let spent = self.spend_tx_inputs(conn, &event.tx_hash).await?;
+        self.record_tx_outputs(conn, &event.tx_hash, None).await?;
+
+        let spent_total: i64 = spent.iter().map(|(_, amount)| amount.unwrap_or(0)).sum();
+        let amount = if spent_total > 0 { Some(spent_total) } else { None };
+
+        self.insert_event_with_amount(conn, event, "reorganize", treasury_id, None, None, amount, &None, body).await?;
 
         Ok(())
     }
 
     /// Insert an event record
+    #[allow(clippy::too_many_arguments)]
     async fn insert_event(
         &self,
+        conn: &mut PgConnection,
         event: &RawTomEvent,
         event_type: &str,
         treasury_id: Option<i32>,
@@ -690,7 +1055,7 @@ impl EventProcessor {
     ) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO treasury.events (
+            INSERT INTO events (
                 tx_hash, slot, block_number, block_time, event_type,
                 treasury_id, vendor_contract_id, milestone_id, metadata
             )
@@ -707,15 +1072,17 @@ impl EventProcessor {
         .bind(vendor_contract_id)
         .bind(milestone_id)
         .bind(body)
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(())
     }
 
     /// Insert an event with reason field
+    #[allow(clippy::too_many_arguments)]
     async fn insert_event_with_reason(
         &self,
+        conn: &mut PgConnection,
         event: &RawTomEvent,
         event_type: &str,
         treasury_id: Option<i32>,
@@ -726,7 +1093,7 @@ impl EventProcessor {
     ) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO treasury.events (
+            INSERT INTO events (
                 tx_hash, slot, block_number, block_time, event_type,
                 treasury_id, vendor_contract_id, milestone_id, reason, metadata
             )
@@ -744,31 +1111,37 @@ impl EventProcessor {
         .bind(milestone_id)
         .bind(reason)
         .bind(body)
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(())
     }
 
-    /// Insert an event with destination field
-    async fn insert_event_with_destination(
+    /// Insert an event with the amount moved and its destination
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_event_with_amount(
         &self,
+        conn: &mut PgConnection,
         event: &RawTomEvent,
         event_type: &str,
         treasury_id: Option<i32>,
         vendor_contract_id: Option<i32>,
         milestone_id: Option<i32>,
+        amount_lovelace: Option<i64>,
         destination: &Option<String>,
         body: &Value,
     ) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO treasury.events (
+            INSERT INTO events (
                 tx_hash, slot, block_number, block_time, event_type,
-                treasury_id, vendor_contract_id, milestone_id, destination, metadata
+                treasury_id, vendor_contract_id, milestone_id, amount_lovelace, destination, metadata
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            ON CONFLICT (tx_hash) DO NOTHING
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (tx_hash) DO UPDATE
+                SET amount_lovelace = EXCLUDED.amount_lovelace,
+                    destination = COALESCE(EXCLUDED.destination, events.destination),
+                    vendor_contract_id = COALESCE(EXCLUDED.vendor_contract_id, events.vendor_contract_id)
             "#
         )
         .bind(&event.tx_hash)
@@ -779,9 +1152,10 @@ impl EventProcessor {
         .bind(treasury_id)
         .bind(vendor_contract_id)
         .bind(milestone_id)
+        .bind(amount_lovelace)
         .bind(destination)
         .bind(body)
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(())
@@ -791,114 +1165,270 @@ impl EventProcessor {
     /// When a fund event is processed, its output UTXOs are recorded with the vendor_contract_id.
     /// Subsequent events (complete/withdraw/etc) spend those UTXOs, so we can find the project
     /// by looking at which tracked UTXOs are being spent as inputs.
-    async fn find_vendor_contract_from_inputs(&self, tx_hash: &str) -> anyhow::Result<Option<i32>> {
-        // Get the inputs to this transaction
-        let inputs: Vec<(String, i16)> = sqlx::query_as(
+    async fn find_vendor_contract_from_inputs(&self, conn: &mut PgConnection, tx_hash: &str) -> anyhow::Result<Option<i32>> {
+        let (input_hashes, input_indexes): (Vec<String>, Vec<i16>) = self.chain.tx_inputs(tx_hash).await?
+            .into_iter()
+            .map(|i| (i.tx_hash, i.output_index))
+            .unzip();
+        let vendor_contract_id: Option<i32> = sqlx::query_scalar(
             r#"
-            SELECT tx_hash, output_index::smallint
-            FROM yaci_store.tx_input
-            WHERE spent_tx_hash = $1
+            SELECT u.vendor_contract_id
+            FROM UNNEST($1::text[], $2::smallint[]) AS i(tx_hash, output_index)
+            JOIN utxos u ON u.tx_hash = i.tx_hash AND u.output_index = i.output_index
+            WHERE u.vendor_contract_id IS NOT NULL
+            LIMIT 1
+            "#
+        )
+        .bind(&input_hashes)
+        .bind(&input_indexes)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match vendor_contract_id {
+            Some(vc_id) => {
+                // Move the project's tracked UTXOs forward to this transaction's outputs
+                self.transfer_utxos(conn, tx_hash, vc_id).await?;
+                Ok(Some(vc_id))
+            }
+            None => {
+                tracing::debug!("No tracked UTXO found for tx {} inputs", tx_hash);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Spend the tracked inputs of a transaction that acts on a project and
+    /// record its outputs (script outputs stay with the project)
+    async fn transfer_utxos(&self, conn: &mut PgConnection, tx_hash: &str, vendor_contract_id: i32) -> anyhow::Result<()> {
+        self.spend_tx_inputs(conn, tx_hash).await?;
+        self.record_tx_outputs(conn, tx_hash, Some(vendor_contract_id)).await?;
+        Ok(())
+    }
+
+    /// Mark every tracked UTXO consumed by this transaction as spent.
+    /// Returns the attribution and amount of each spent UTXO.
+    async fn spend_tx_inputs(&self, conn: &mut PgConnection, tx_hash: &str) -> anyhow::Result<Vec<(Option<i32>, Option<i64>)>> {
+        let inputs = self.chain.tx_inputs(tx_hash).await?;
+        let input_hashes: Vec<&str> = inputs.iter().map(|i| i.tx_hash.as_str()).collect();
+        let input_indexes: Vec<i16> = inputs.iter().map(|i| i.output_index).collect();
+        let spent_slots: Vec<Option<i64>> = inputs.iter().map(|i| i.spent_slot).collect();
+        let spent = sqlx::query_as::<_, (Option<i32>, Option<i64>)>(
+            r#"
+            UPDATE utxos u
+            SET spent = true, spent_tx_hash = $1, spent_slot = i.spent_slot
+            FROM UNNEST($2::text[], $3::smallint[], $4::bigint[]) AS i(tx_hash, output_index, spent_slot)
+            WHERE u.tx_hash = i.tx_hash AND u.output_index = i.output_index
+            RETURNING u.vendor_contract_id, u.lovelace_amount
             "#
         )
         .bind(tx_hash)
-        .fetch_all(&self.pool)
+        .bind(&input_hashes)
+        .bind(&input_indexes)
+        .bind(&spent_slots)
+        .fetch_all(&mut *conn)
         .await?;
 
-        // Look up each input in our tracked UTXOs
-        for (input_tx_hash, input_output_index) in &inputs {
-            let vendor_contract_id: Option<i32> = sqlx::query_scalar(
+        Ok(spent)
+    }
+
+    /// Record the outputs of a transaction in treasury.utxos, attributing each
+    /// output by the address it pays to:
+    /// - a project contract address -> that project
+    /// - the treasury contract address -> the treasury (no project)
+    /// - any other script address -> `default_vendor_contract_id` when the
+    ///   project address is not known yet
+    /// - anything else (vendor wallets, external destinations) -> no project
+    async fn record_tx_outputs(&self, conn: &mut PgConnection, tx_hash: &str, default_vendor_contract_id: Option<i32>) -> anyhow::Result<()> {
+        let outputs = self.chain.tx_outputs(tx_hash).await?;
+
+        if outputs.is_empty() {
+            // Output addresses are not indexed; fall back to the bare output
+            // references so chained events can still be attributed to the project
+            if let Some(vc_id) = default_vendor_contract_id {
+                for OutputRef { tx_hash: out_tx_hash, output_index } in self.chain.tx_output_refs(tx_hash).await? {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO utxos (tx_hash, output_index, vendor_contract_id, spent)
+                        VALUES ($1, $2, $3, false)
+                        ON CONFLICT (tx_hash, output_index) DO UPDATE
+                            SET vendor_contract_id = EXCLUDED.vendor_contract_id
+                        "#
+                    )
+                    .bind(&out_tx_hash)
+                    .bind(output_index)
+                    .bind(vc_id)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+            }
+
+            return Ok(());
+        }
+
+        for output in outputs {
+            let project: Option<i32> = sqlx::query_scalar(
+                "SELECT id FROM vendor_contracts WHERE contract_address = $1 ORDER BY id DESC LIMIT 1"
+            )
+            .bind(&output.address)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+            let is_treasury: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM treasury_contracts WHERE contract_address = $1)"
+            )
+            .bind(&output.address)
+            .fetch_one(&mut *conn)
+            .await?;
+
+            let is_script = output.address.starts_with("addr1x");
+
+            let (vendor_contract_id, address_type) = if let Some(vc_id) = project {
+                (Some(vc_id), "vendor_contract")
+            } else if is_treasury {
+                (None, "treasury")
+            } else if is_script && default_vendor_contract_id.is_some() {
+                (default_vendor_contract_id, "vendor_contract")
+            } else {
+                (None, "external")
+            };
+
+            sqlx::query(
                 r#"
-                SELECT vendor_contract_id
-                FROM treasury.utxos
-                WHERE tx_hash = $1 AND output_index = $2 AND vendor_contract_id IS NOT NULL
+                INSERT INTO utxos (
+                    tx_hash, output_index, address, address_type,
+                    vendor_contract_id, lovelace_amount, slot, block_number, spent
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, false)
+                ON CONFLICT (tx_hash, output_index) DO UPDATE
+                    SET address = EXCLUDED.address,
+                        address_type = EXCLUDED.address_type,
+                        vendor_contract_id = EXCLUDED.vendor_contract_id,
+                        lovelace_amount = EXCLUDED.lovelace_amount,
+                        slot = COALESCE(EXCLUDED.slot, utxos.slot),
+                        block_number = COALESCE(EXCLUDED.block_number, utxos.block_number)
                 "#
             )
-            .bind(input_tx_hash)
-            .bind(input_output_index)
-            .fetch_optional(&self.pool)
+            .bind(tx_hash)
+            .bind(output.output_index)
+            .bind(&output.address)
+            .bind(address_type)
+            .bind(vendor_contract_id)
+            .bind(output.lovelace)
+            .bind(output.slot)
+            .bind(output.block_number)
+            .execute(&mut *conn)
             .await?;
+        }
 
-            if let Some(vc_id) = vendor_contract_id {
-                // Mark this UTXO as spent and record the new outputs
-                sqlx::query(
-                    r#"
-                    UPDATE treasury.utxos
-                    SET spent = true, spent_tx_hash = $1
-                    WHERE tx_hash = $2 AND output_index = $3
-                    "#
-                )
-                .bind(tx_hash)
-                .bind(input_tx_hash)
-                .bind(input_output_index)
-                .execute(&self.pool)
-                .await?;
+        Ok(())
+    }
 
-                // Record the outputs of this transaction with the same vendor_contract_id
-                let outputs: Option<serde_json::Value> = sqlx::query_scalar(
-                    "SELECT outputs::jsonb FROM yaci_store.transaction WHERE tx_hash = $1"
-                )
-                .bind(tx_hash)
-                .fetch_optional(&self.pool)
-                .await?;
+    /// Script address of the first input spent by a transaction
+    async fn find_script_input_address(&self, tx_hash: &str) -> anyhow::Result<Option<String>> {
+        let address = self.chain.tx_inputs(tx_hash).await?
+            .into_iter()
+            .filter_map(|i| i.address)
+            .find(|a| a.starts_with("addr1x"));
 
-                if let Some(serde_json::Value::Array(output_arr)) = outputs {
-                    for output in output_arr {
-                        if let (Some(out_tx_hash), Some(output_index)) = (
-                            output.get("tx_hash").and_then(|h| h.as_str()),
-                            output.get("output_index").and_then(|i| i.as_i64())
-                        ) {
-                            sqlx::query(
-                                r#"
-                                INSERT INTO treasury.utxos (tx_hash, output_index, vendor_contract_id, spent)
-                                VALUES ($1, $2, $3, false)
-                                ON CONFLICT (tx_hash, output_index) DO UPDATE
-                                    SET vendor_contract_id = EXCLUDED.vendor_contract_id
-                                "#
-                            )
-                            .bind(out_tx_hash)
-                            .bind(output_index as i16)
-                            .bind(vc_id)
-                            .execute(&self.pool)
-                            .await?;
-                        }
-                    }
-                }
+        Ok(address)
+    }
 
-                return Ok(Some(vc_id));
-            }
-        }
+    /// Work out how much left a project contract in a transaction (after its
+    /// UTXOs have been transferred) and where the largest part of it went
+    async fn outflow_from_project(&self, conn: &mut PgConnection, tx_hash: &str, vendor_contract_id: i32) -> anyhow::Result<ProjectOutflow> {
+        let outputs = self.chain.tx_outputs(tx_hash).await?;
+
+        let project_address: Option<String> = sqlx::query_scalar(
+            "SELECT contract_address FROM vendor_contracts WHERE id = $1"
+        )
+        .bind(vendor_contract_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let (kept, moved): (Vec<&TxOutput>, Vec<&TxOutput>) = outputs.iter()
+            .partition(|o| Some(&o.address) == project_address.as_ref());
+
+        let donation = self.chain.treasury_donation(tx_hash).await?;
+        let moved_total: i64 = moved.iter().map(|o| o.lovelace).sum::<i64>() + donation;
+
+        let destination = moved.iter()
+            .max_by_key(|o| o.lovelace)
+            .map(|o| o.address.clone())
+            .or_else(|| (donation > 0).then(|| "cardano_treasury".to_string()));
 
-        tracing::debug!("No tracked UTXO found for tx {} inputs", tx_hash);
-        Ok(None)
+        Ok(ProjectOutflow {
+            amount: if outputs.is_empty() && donation == 0 { None } else { Some(moved_total) },
+            destination,
+            remaining_lovelace: (!outputs.is_empty() && project_address.is_some())
+                .then(|| kept.iter().map(|o| o.lovelace).sum()),
+        })
     }
 
     /// Sync UTXOs for all tracked addresses
     pub async fn sync_utxos(&self) -> anyhow::Result<()> {
+        let _timer = METRICS.utxo_sync_duration.start_timer();
+
         // Get all contract addresses (both treasury and vendor)
         let addresses: Vec<String> = sqlx::query_scalar(
             r#"
-            SELECT contract_address FROM treasury.treasury_contracts WHERE contract_address IS NOT NULL
+            SELECT contract_address FROM treasury_contracts WHERE contract_address IS NOT NULL
             UNION
-            SELECT contract_address FROM treasury.vendor_contracts WHERE contract_address IS NOT NULL
+            SELECT contract_address FROM vendor_contracts WHERE contract_address IS NOT NULL
             UNION
-            SELECT vendor_address FROM treasury.vendor_contracts WHERE vendor_address IS NOT NULL
+            SELECT vendor_address FROM vendor_contracts WHERE vendor_address IS NOT NULL
             "#
         )
         .fetch_all(&self.pool)
         .await?;
 
+        let mut changed = Vec::new();
         for address in addresses {
-            self.sync_address_utxos(&address).await?;
+            if self.sync_address_utxos(&address).await? {
+                changed.push(address);
+            }
         }
 
+        // Mark tracked UTXOs that have since been spent
+        let unspent: Vec<(String, i16)> = sqlx::query_as(
+            "SELECT tx_hash, output_index FROM utxos WHERE NOT spent"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let unspent: Vec<OutputRef> = unspent
+            .into_iter()
+            .map(|(tx_hash, output_index)| OutputRef { tx_hash, output_index })
+            .collect();
+        let spends = self.chain.spends(&unspent).await?;
+        let spent_at: Vec<Option<String>> = sqlx::query_scalar(
+            r#"
+            UPDATE utxos u
+            SET spent = true, spent_tx_hash = i.spent_tx_hash, spent_slot = i.spent_slot
+            FROM UNNEST($1::text[], $2::smallint[], $3::text[], $4::bigint[])
+                AS i(tx_hash, output_index, spent_tx_hash, spent_slot)
+            WHERE NOT u.spent AND u.tx_hash = i.tx_hash AND u.output_index = i.output_index
+            RETURNING u.address
+            "#
+        )
+        .bind(spends.iter().map(|s| s.tx_hash.as_str()).collect::<Vec<_>>())
+        .bind(spends.iter().map(|s| s.output_index).collect::<Vec<_>>())
+        .bind(spends.iter().map(|s| s.spent_tx_hash.as_str()).collect::<Vec<_>>())
+        .bind(spends.iter().map(|s| s.spent_slot).collect::<Vec<_>>())
+        .fetch_all(&self.pool)
+        .await?;
+        changed.extend(spent_at.into_iter().flatten());
+        changed.sort();
+        changed.dedup();
+
+        summaries::refresh_for_addresses(&mut *self.pool.acquire().await?, &changed).await?;
+
         Ok(())
     }
 
-    /// Sync UTXOs for a specific address
-    async fn sync_address_utxos(&self, address: &str) -> anyhow::Result<()> {
+    /// Sync UTXOs for a specific address. Returns whether new UTXOs were recorded.
+    async fn sync_address_utxos(&self, address: &str) -> anyhow::Result<bool> {
         // Determine address type and get vendor_contract_id if applicable
         let vendor_contract_id: Option<i32> = sqlx::query_scalar(
-            "SELECT id FROM treasury.vendor_contracts WHERE contract_address = $1 OR vendor_address = $1"
+            "SELECT id FROM vendor_contracts WHERE contract_address = $1 OR vendor_address = $1"
         )
         .bind(address)
         .fetch_optional(&self.pool)
@@ -910,22 +1440,13 @@ impl EventProcessor {
             "vendor"
         };
 
-        // Get UTXOs from yaci_store
-        let utxos = sqlx::query_as::<_, (String, i16, i64, i64, Option<i64>)>(
-            r#"
-            SELECT tx_hash, output_index::smallint, lovelace_amount, slot, block as block_number
-            FROM yaci_store.address_utxo
-            WHERE owner_addr = $1
-            "#
-        )
-        .bind(address)
-        .fetch_all(&self.pool)
-        .await?;
+        let utxos = self.chain.address_utxos(address).await?;
 
-        for (tx_hash, output_index, lovelace_amount, slot, block_number) in utxos {
-            sqlx::query(
+        let mut recorded = false;
+        for utxo in utxos {
+            let inserted = sqlx::query(
                 r#"
-                INSERT INTO treasury.utxos (
+                INSERT INTO utxos (
                     tx_hash, output_index, address, address_type,
                     vendor_contract_id, lovelace_amount, slot, block_number, spent
                 )
@@ -933,22 +1454,46 @@ impl EventProcessor {
                 ON CONFLICT (tx_hash, output_index) DO NOTHING
                 "#
             )
-            .bind(&tx_hash)
-            .bind(output_index)
+            .bind(&utxo.tx_hash)
+            .bind(utxo.output_index)
             .bind(address)
             .bind(address_type)
             .bind(vendor_contract_id)
-            .bind(lovelace_amount)
-            .bind(slot)
-            .bind(block_number)
+            .bind(utxo.lovelace)
+            .bind(utxo.slot)
+            .bind(utxo.block_number)
             .execute(&self.pool)
-            .await?;
+            .await?
+            .rows_affected();
+            recorded |= inserted > 0;
         }
 
-        Ok(())
+        Ok(recorded)
     }
 }
 
+/// Funds that left a project contract in a single transaction
+struct ProjectOutflow {
+    /// Lovelace paid out of the contract (None when outputs are not indexed)
+    amount: Option<i64>,
+    /// Address that received the largest share
+    destination: Option<String>,
+    /// Lovelace that went back to the project contract (None when outputs
+    /// are not indexed or the contract address is not known)
+    remaining_lovelace: Option<i64>,
+}
+
+/// Extract a Unix timestamp (seconds) from a number or numeric string.
+/// Values that look like POSIX milliseconds (as used in Plutus datums) are scaled down.
+fn extract_timestamp(value: Option<&Value>) -> Option<i64> {
+    let ts = match value? {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse::<i64>().ok(),
+        _ => None,
+    }?;
+    Some(if ts > 100_000_000_000 { ts / 1000 } else { ts })
+}
+
 /// Extract text from a field that might be a string or array
 fn extract_text(obj: &Value, field: &str) -> Option<String> {
     extract_text_from_value(obj.get(field))
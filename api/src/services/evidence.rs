@@ -0,0 +1,557 @@
+//! Completion evidence resolver and archiver
+//!
+//! `complete` metadata stores milestone evidence as free-form JSON, usually a
+//! list of URLs or IPFS CIDs that may later disappear. Each sync cycle this:
+//!
+//! 1. normalises `treasury.milestones.evidence` into typed rows in
+//!    `treasury.milestone_evidence` (label, url, ipfs cid, declared hash);
+//! 2. fetches pending entries through an [`EvidenceFetcher`] and stores the
+//!    content in a content-addressed [`EvidenceArchive`] (keyed by SHA-256);
+//! 3. verifies any declared hash (sha256 or blake2b-256) against the copy.
+//!
+//! The only fetcher is [`LocalDirFetcher`], which reads from a local mirror
+//! directory (`EVIDENCE_SOURCE_DIR`). Without it evidence is still normalised
+//! but nothing is archived.
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use serde_json::Value;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::path::{Component, Path, PathBuf};
+
+/// Entries fetched per sync cycle
+const FETCH_BATCH_SIZE: i64 = 50;
+
+/// Keys holding the evidence label, most specific first
+const LABEL_KEYS: &[&str] = &["label", "name", "title", "description"];
+
+/// Keys holding the evidence location
+const URL_KEYS: &[&str] = &["url", "uri", "href", "link", "anchorUrl", "anchor_url", "location"];
+
+/// Keys holding a bare IPFS CID
+const CID_KEYS: &[&str] = &["cid", "ipfs", "ipfsCid", "ipfs_cid"];
+
+/// Keys holding a declared content hash
+const HASH_KEYS: &[&str] = &[
+    "hash", "contentHash", "content_hash", "dataHash", "anchorDataHash", "anchor_data_hash",
+    "hashDigest", "sha256", "blake2b", "blake2b-256", "blake2b_256",
+];
+
+/// Keys holding the declared hash algorithm
+const ALGORITHM_KEYS: &[&str] = &["hashAlgorithm", "hash_algorithm", "algorithm"];
+
+/// Evidence entry normalised from the milestone's evidence JSON
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EvidenceRecord {
+    pub label: Option<String>,
+    pub url: Option<String>,
+    pub ipfs_cid: Option<String>,
+    pub declared_hash: Option<String>,
+    pub hash_algorithm: Option<String>,
+    pub raw: Value,
+}
+
+impl EvidenceRecord {
+    /// Whether there is anything to fetch
+    fn is_resolvable(&self) -> bool {
+        self.url.is_some() || self.ipfs_cid.is_some()
+    }
+}
+
+/// Split evidence JSON into typed records, in their original order.
+///
+/// Accepts a list of entries, a single entry, or an object of entries keyed by
+/// name. Entries are either strings (URL, ipfs:// locator, CID or plain label)
+/// or objects using common key spellings, including CIP-100 style references
+/// (`uri` plus `referenceHash.hashDigest` / `hashAlgorithm`).
+pub fn normalise_evidence(evidence: &Value) -> Vec<EvidenceRecord> {
+    match evidence {
+        Value::Array(entries) => entries.iter().filter_map(|e| normalise_entry(e, None)).collect(),
+        Value::Object(obj) if !is_entry(obj) => obj
+            .iter()
+            .filter_map(|(key, e)| normalise_entry(e, Some(key)))
+            .collect(),
+        other => normalise_entry(other, None).into_iter().collect(),
+    }
+}
+
+/// Whether an object is a single evidence entry rather than a map of entries
+fn is_entry(obj: &serde_json::Map<String, Value>) -> bool {
+    [LABEL_KEYS, URL_KEYS, CID_KEYS, HASH_KEYS]
+        .iter()
+        .flat_map(|keys| keys.iter())
+        .any(|k| obj.contains_key(*k))
+        || obj.contains_key("referenceHash")
+}
+
+fn normalise_entry(entry: &Value, key: Option<&String>) -> Option<EvidenceRecord> {
+    let mut record = EvidenceRecord { raw: entry.clone(), ..Default::default() };
+
+    match entry {
+        Value::String(s) => {
+            let s = s.trim();
+            if s.is_empty() {
+                return None;
+            }
+            if !apply_locator(&mut record, s) {
+                record.label = Some(s.to_string());
+            }
+        }
+        Value::Object(obj) => {
+            record.label = first_string(obj, LABEL_KEYS);
+            if let Some(url) = first_string(obj, URL_KEYS) {
+                apply_locator(&mut record, &url);
+            }
+            if record.ipfs_cid.is_none() {
+                record.ipfs_cid = first_string(obj, CID_KEYS).map(|c| c.trim_start_matches("ipfs://").to_string());
+            }
+
+            // Declared hash, either flat or nested in a CIP-100 referenceHash
+            let reference = obj.get("referenceHash").and_then(|r| r.as_object());
+            let hash_key = HASH_KEYS.iter().find(|k| obj.get(**k).and_then(|v| v.as_str()).is_some());
+            let hash = hash_key
+                .and_then(|k| obj.get(*k))
+                .or_else(|| reference.and_then(|r| r.get("hashDigest")))
+                .and_then(|v| v.as_str());
+            let algorithm = first_string(obj, ALGORITHM_KEYS)
+                .or_else(|| reference.and_then(|r| first_string(r, ALGORITHM_KEYS)))
+                .or_else(|| hash_key.and_then(|k| algorithm_from_name(k)));
+            if let Some((digest, prefixed)) = hash.and_then(parse_hash) {
+                record.declared_hash = Some(digest);
+                record.hash_algorithm = prefixed.or(algorithm.and_then(|a| algorithm_from_name(&a)));
+            }
+        }
+        _ => return None,
+    }
+
+    if record.label.is_none() {
+        record.label = key.cloned();
+    }
+    Some(record)
+}
+
+fn first_string(obj: &serde_json::Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Fill url / ipfs_cid from a locator string; false if it is not one
+fn apply_locator(record: &mut EvidenceRecord, locator: &str) -> bool {
+    let locator = locator.trim();
+    if let Some((cid, _)) = ipfs_locator(locator) {
+        record.ipfs_cid = Some(cid.to_string());
+        record.url = Some(locator.to_string());
+        true
+    } else if locator.starts_with("http://") || locator.starts_with("https://") {
+        record.url = Some(locator.to_string());
+        true
+    } else if looks_like_cid(locator) {
+        record.ipfs_cid = Some(locator.to_string());
+        true
+    } else {
+        false
+    }
+}
+
+/// CID and sub-path of an ipfs:// locator or an IPFS gateway URL
+fn ipfs_locator(locator: &str) -> Option<(&str, &str)> {
+    let rest = locator
+        .strip_prefix("ipfs://ipfs/")
+        .or_else(|| locator.strip_prefix("ipfs://"))
+        .or_else(|| {
+            locator
+                .strip_prefix("https://")
+                .or_else(|| locator.strip_prefix("http://"))
+                .and_then(|u| u.split_once("/ipfs/"))
+                .map(|(_, path)| path)
+        })?;
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (cid, path) = rest.split_once('/').unwrap_or((rest, ""));
+    (!cid.is_empty()).then_some((cid, path))
+}
+
+/// CIDv0 (base58 "Qm...") or CIDv1 in base32 ("b...")
+fn looks_like_cid(s: &str) -> bool {
+    (s.len() == 46 && s.starts_with("Qm") && s.chars().all(|c| c.is_ascii_alphanumeric()))
+        || (s.len() >= 50
+            && s.starts_with('b')
+            && s.chars().all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c)))
+}
+
+/// Canonical algorithm name from a key or declared algorithm
+fn algorithm_from_name(name: &str) -> Option<String> {
+    let name = name.to_ascii_lowercase().replace(['_', '-'], "");
+    if name.contains("sha256") {
+        Some("sha256".to_string())
+    } else if name.contains("blake2b") {
+        Some("blake2b-256".to_string())
+    } else {
+        None
+    }
+}
+
+/// Lower-case hex digest, plus the algorithm if given as an "alg:digest" prefix
+fn parse_hash(value: &str) -> Option<(String, Option<String>)> {
+    let value = value.trim();
+    let (algorithm, digest) = match value.rsplit_once(':') {
+        Some((alg, digest)) => (algorithm_from_name(alg), digest),
+        None => (None, value),
+    };
+    let digest = digest.trim_start_matches("0x").to_ascii_lowercase();
+    let valid = !digest.is_empty() && digest.len() % 2 == 0 && digest.chars().all(|c| c.is_ascii_hexdigit());
+    valid.then_some((digest, algorithm))
+}
+
+/// Hex SHA-256 of a document (the archive key)
+pub fn sha256_hex(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// Check a declared hash against content. Returns the matching algorithm,
+/// `Err(())` on mismatch, or `Ok(None)` if the algorithm is not supported.
+fn verify_hash(content: &[u8], declared: &str, algorithm: Option<&str>) -> Result<Option<&'static str>, ()> {
+    let candidates: &[&'static str] = match algorithm {
+        Some("sha256") => &["sha256"],
+        Some("blake2b-256") => &["blake2b-256"],
+        Some(_) => return Ok(None),
+        None => &["blake2b-256", "sha256"],
+    };
+    for alg in candidates {
+        let digest = match *alg {
+            "sha256" => hex::encode(Sha256::digest(content)),
+            _ => hex::encode(Blake2b::<U32>::digest(content)),
+        };
+        if digest == declared {
+            return Ok(Some(alg));
+        }
+    }
+    Err(())
+}
+
+// ============================================================================
+// ARCHIVE
+// ============================================================================
+
+/// Content-addressed evidence archive on the local filesystem.
+///
+/// Documents are stored as `<root>/<first two hex chars>/<sha256>`.
+#[derive(Debug, Clone)]
+pub struct EvidenceArchive {
+    root: PathBuf,
+}
+
+impl EvidenceArchive {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, content_hash: &str) -> Option<PathBuf> {
+        let valid = content_hash.len() == 64 && content_hash.chars().all(|c| c.is_ascii_hexdigit());
+        valid.then(|| self.root.join(&content_hash[..2]).join(content_hash))
+    }
+
+    /// Store a document, returning its content hash. Existing copies are kept.
+    pub async fn store(&self, content: &[u8]) -> std::io::Result<String> {
+        let hash = sha256_hex(content);
+        let path = self.path_for(&hash).expect("sha256 hex is a valid archive key");
+        if tokio::fs::try_exists(&path).await? {
+            return Ok(hash);
+        }
+        let dir = path.parent().expect("archive path has a parent");
+        tokio::fs::create_dir_all(dir).await?;
+
+        // Write then rename so readers never see a partial document
+        let tmp = dir.join(format!(".{}.tmp", hash));
+        tokio::fs::write(&tmp, content).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(hash)
+    }
+
+    /// Read an archived document, if present
+    pub async fn read(&self, content_hash: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let Some(path) = self.path_for(content_hash) else {
+            return Ok(None);
+        };
+        match tokio::fs::read(path).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// ============================================================================
+// FETCHERS
+// ============================================================================
+
+/// Fetched evidence document
+#[derive(Debug)]
+pub struct FetchedEvidence {
+    pub content: Vec<u8>,
+    pub media_type: Option<String>,
+}
+
+/// Where to fetch a pending evidence entry from
+#[derive(Debug, sqlx::FromRow)]
+pub struct EvidenceLocator {
+    pub id: i32,
+    pub url: Option<String>,
+    pub ipfs_cid: Option<String>,
+    pub declared_hash: Option<String>,
+    pub hash_algorithm: Option<String>,
+}
+
+/// Source of evidence documents. `Ok(None)` means the document was not found.
+// Only awaited from the sync task, so the future needs no `Send` bound
+#[allow(async_fn_in_trait)]
+pub trait EvidenceFetcher {
+    async fn fetch(&self, locator: &EvidenceLocator) -> anyhow::Result<Option<FetchedEvidence>>;
+}
+
+/// Fetcher backed by a local mirror directory, standing in for HTTP and an
+/// IPFS gateway:
+///
+/// - `ipfs://<cid>/<path>` and bare CIDs are read from `<dir>/ipfs/<cid>/<path>`
+/// - `https://<host>/<path>` is read from `<dir>/<host>/<path>`
+///   (`index.html` for directory URLs)
+#[derive(Debug, Clone)]
+pub struct LocalDirFetcher {
+    root: PathBuf,
+}
+
+impl LocalDirFetcher {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Local path for a locator, refusing anything that escapes the root
+    fn path_for(&self, locator: &EvidenceLocator) -> Option<PathBuf> {
+        let relative = match (&locator.ipfs_cid, &locator.url) {
+            (Some(cid), url) => {
+                let path = url.as_deref().and_then(ipfs_locator).map(|(_, p)| p).unwrap_or_default();
+                Path::new("ipfs").join(cid).join(path)
+            }
+            (None, Some(url)) => {
+                let rest = url.split_once("://")?.1;
+                let rest = rest.split(['?', '#']).next().unwrap_or_default();
+                let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+                let host = host.rsplit('@').next().unwrap_or(host);
+                let mut relative = Path::new(host).join(path);
+                if path.is_empty() || path.ends_with('/') {
+                    relative.push("index.html");
+                }
+                relative
+            }
+            (None, None) => return None,
+        };
+
+        let safe = relative.components().all(|c| matches!(c, Component::Normal(_)));
+        safe.then(|| self.root.join(relative))
+    }
+}
+
+impl EvidenceFetcher for LocalDirFetcher {
+    async fn fetch(&self, locator: &EvidenceLocator) -> anyhow::Result<Option<FetchedEvidence>> {
+        let Some(path) = self.path_for(locator) else {
+            return Ok(None);
+        };
+        // A CID without a path may be mirrored as a directory with an index
+        let path = if tokio::fs::metadata(&path).await.map(|m| m.is_dir()).unwrap_or(false) {
+            path.join("index.html")
+        } else {
+            path
+        };
+
+        match tokio::fs::read(&path).await {
+            Ok(content) => Ok(Some(FetchedEvidence {
+                content,
+                media_type: mime_guess::from_path(&path).first_raw().map(str::to_string),
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+// ============================================================================
+// SYNC
+// ============================================================================
+
+/// Normalise evidence for all milestones, then fetch and verify pending
+/// entries. Without a fetcher only normalisation runs.
+pub async fn sync_evidence<F: EvidenceFetcher>(
+    pool: &PgPool,
+    fetcher: Option<&F>,
+    archive: &EvidenceArchive,
+) -> anyhow::Result<()> {
+    normalise_all(pool).await?;
+    if let Some(fetcher) = fetcher {
+        archive_pending(pool, fetcher, archive).await?;
+    }
+    Ok(())
+}
+
+/// Rebuild treasury.milestone_evidence from milestones.evidence. Entries keep
+/// their archive status unless their location or declared hash changed.
+pub async fn normalise_all(pool: &PgPool) -> anyhow::Result<()> {
+    let milestones = sqlx::query_as::<_, (i32, Value)>(
+        "SELECT id, evidence FROM milestones WHERE evidence IS NOT NULL"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for (milestone_id, evidence) in milestones {
+        let records = normalise_evidence(&evidence);
+
+        for (position, record) in records.iter().enumerate() {
+            let status = if record.is_resolvable() { "pending" } else { "unresolvable" };
+            sqlx::query(
+                r#"
+                INSERT INTO milestone_evidence
+                    (milestone_id, position, label, url, ipfs_cid, declared_hash, hash_algorithm, raw, status)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (milestone_id, position) DO UPDATE SET
+                    label = EXCLUDED.label,
+                    url = EXCLUDED.url,
+                    ipfs_cid = EXCLUDED.ipfs_cid,
+                    declared_hash = EXCLUDED.declared_hash,
+                    hash_algorithm = EXCLUDED.hash_algorithm,
+                    raw = EXCLUDED.raw,
+                    status = EXCLUDED.status,
+                    content_hash = NULL,
+                    fetch_attempts = 0,
+                    last_checked_at = NULL,
+                    updated_at = NOW()
+                WHERE (milestone_evidence.url, milestone_evidence.ipfs_cid,
+                       milestone_evidence.declared_hash, milestone_evidence.raw)
+                    IS DISTINCT FROM (EXCLUDED.url, EXCLUDED.ipfs_cid, EXCLUDED.declared_hash, EXCLUDED.raw)
+                "#
+            )
+            .bind(milestone_id)
+            .bind(position as i32)
+            .bind(&record.label)
+            .bind(&record.url)
+            .bind(&record.ipfs_cid)
+            .bind(&record.declared_hash)
+            .bind(&record.hash_algorithm)
+            .bind(&record.raw)
+            .bind(status)
+            .execute(pool)
+            .await?;
+        }
+
+        sqlx::query("DELETE FROM milestone_evidence WHERE milestone_id = $1 AND position >= $2")
+            .bind(milestone_id)
+            .bind(records.len() as i32)
+            .execute(pool)
+            .await?;
+    }
+
+    // Evidence cleared on the milestone
+    sqlx::query(
+        r#"
+        DELETE FROM milestone_evidence me
+        USING milestones m
+        WHERE m.id = me.milestone_id AND m.evidence IS NULL
+        "#
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Fetch pending entries (and unavailable ones, with backoff), archive them
+/// and verify declared hashes
+async fn archive_pending<F: EvidenceFetcher>(
+    pool: &PgPool,
+    fetcher: &F,
+    archive: &EvidenceArchive,
+) -> anyhow::Result<()> {
+    // Unavailable entries are retried after 15 minutes per failed attempt, up to a day
+    let pending = sqlx::query_as::<_, EvidenceLocator>(
+        r#"
+        SELECT id, url, ipfs_cid, declared_hash, hash_algorithm
+        FROM milestone_evidence
+        WHERE status = 'pending'
+           OR (status = 'unavailable'
+               AND last_checked_at < NOW() - LEAST(fetch_attempts, 96) * INTERVAL '15 minutes')
+        ORDER BY last_checked_at NULLS FIRST, id
+        LIMIT $1
+        "#
+    )
+    .bind(FETCH_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    for locator in pending {
+        let fetched = match fetcher.fetch(&locator).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                tracing::warn!("Failed to fetch evidence {}: {}", locator.id, e);
+                None
+            }
+        };
+
+        let Some(doc) = fetched else {
+            sqlx::query(
+                r#"
+                UPDATE milestone_evidence
+                SET status = 'unavailable', fetch_attempts = fetch_attempts + 1,
+                    last_checked_at = NOW(), updated_at = NOW()
+                WHERE id = $1
+                "#
+            )
+            .bind(locator.id)
+            .execute(pool)
+            .await?;
+            continue;
+        };
+
+        let content_hash = archive.store(&doc.content).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO evidence_archive (content_hash, size_bytes, media_type)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (content_hash) DO NOTHING
+            "#
+        )
+        .bind(&content_hash)
+        .bind(doc.content.len() as i64)
+        .bind(&doc.media_type)
+        .execute(pool)
+        .await?;
+
+        let (status, matched_algorithm) = match &locator.declared_hash {
+            None => ("archived", None),
+            Some(declared) => match verify_hash(&doc.content, declared, locator.hash_algorithm.as_deref()) {
+                Ok(Some(algorithm)) => ("verified", Some(algorithm)),
+                Ok(None) => ("archived", None),
+                Err(()) => {
+                    tracing::warn!("Evidence {} does not match its declared hash {}", locator.id, declared);
+                    ("mismatch", None)
+                }
+            },
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE milestone_evidence
+            SET content_hash = $2, status = $3, hash_algorithm = COALESCE(hash_algorithm, $4),
+                fetch_attempts = fetch_attempts + 1, last_checked_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(locator.id)
+        .bind(&content_hash)
+        .bind(status)
+        .bind(matched_algorithm)
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(())
+}
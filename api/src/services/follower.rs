@@ -0,0 +1,520 @@
+//! Built-in chain follower
+//!
+//! With `chain.source = "follower"` the sync worker reads blocks itself from
+//! a node or a block file (see `chain::blocks`) and writes what the event
+//! processor needs into the `chain_*` tables of the treasury schema, which
+//! the follower chain source then serves. A transaction is kept when it
+//! carries TOM metadata, pays to a tracked address or spends an output
+//! already kept. Tracked addresses are the contract and vendor addresses the
+//! processor has recorded, `chain.addresses`, and script outputs of TOM
+//! transactions, so a project contract is followed from its fund
+//! transaction on, before the processor has seen it.
+//!
+//! Every block header is stored so the follower can resume from and roll
+//! back to it; headers deeper than the security parameter without kept
+//! transactions are pruned. A rollback deletes the blocks after the
+//! rollback point with their transactions. If the treasury tables already
+//! reflect a removed transaction, the follower undoes it in place: the
+//! projects with events after the rollback point are rebuilt from their
+//! earlier events, UTXOs created or spent after it are restored, and the
+//! events cursor is rewound so the next sync processes the new fork. Deep
+//! rollbacks, and rollbacks of treasury-level events, instead rebuild the
+//! treasury tables from the remaining chain data through a reindex (see
+//! [`reindex`]), which the sync worker may run as it holds sync leadership.
+//! The rebuild replaces any shadow schema an operator has built.
+
+use anyhow::Context;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::event_processor::EventProcessor;
+use super::{reindex, summaries};
+use crate::chain::blocks::{self, Block, BlockSource, Point, Step};
+use crate::chain::{ChainSource, OutputRef, TOM_LABEL};
+use crate::config::{ChainConfig, DatabaseConfig};
+
+/// Blocks after which a block can no longer be rolled back (Cardano's
+/// security parameter k)
+const SECURITY_PARAM: i64 = 2160;
+
+/// Blocks a rollback may remove and still be undone in place; deeper
+/// rollbacks rebuild the treasury tables
+const MAX_UNDO_BLOCKS: u64 = 100;
+
+/// Depths below the newest stored block offered as resume points, so
+/// resuming survives rollbacks of the newest blocks
+const RESUME_DEPTHS: &[i64] = &[0, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233, 377, 610, 987, 1597];
+
+/// What a call to [`Follower::follow`] did
+#[derive(Debug, Default)]
+pub struct FollowReport {
+    /// Blocks stored
+    pub blocks: usize,
+    /// Transactions kept from them
+    pub kept: usize,
+    /// Rollbacks that removed stored blocks
+    pub rollbacks: usize,
+    /// Whether a rollback was undone in the treasury tables in place
+    pub undone: bool,
+    /// Whether the treasury tables were rebuilt after a rollback
+    pub rebuilt: bool,
+    /// Whether the source had no more blocks
+    pub at_tip: bool,
+}
+
+/// Follows the chain into the `chain_*` tables
+pub struct Follower {
+    pool: PgPool,
+    chain: Arc<dyn ChainSource>,
+    database: DatabaseConfig,
+    source: Box<dyn BlockSource>,
+    /// Block to start after while none is stored
+    start: Option<Point>,
+    /// `chain.addresses`
+    addresses: Vec<String>,
+    /// Events per batch when rebuilding
+    batch_size: i64,
+    /// Whether `source` is positioned after the stored blocks
+    intersected: bool,
+    /// Earliest rollback point past which the treasury tables reflect
+    /// removed transactions
+    undo_after: Option<i64>,
+    /// Blocks removed by the rollbacks behind `undo_after`
+    undo_blocks: u64,
+}
+
+impl Follower {
+    /// `chain` is the follower chain source on `pool`, used to rebuild the
+    /// treasury tables after rollbacks
+    pub fn new(
+        pool: PgPool,
+        chain: Arc<dyn ChainSource>,
+        config: &ChainConfig,
+        database: &DatabaseConfig,
+        batch_size: i64,
+    ) -> anyhow::Result<Self> {
+        let start = match (config.start_slot, &config.start_hash) {
+            (Some(slot), Some(hash)) => Some(Point {
+                slot,
+                hash: hash.to_lowercase(),
+            }),
+            _ => None,
+        };
+        Ok(Self {
+            pool,
+            chain,
+            database: database.clone(),
+            source: blocks::open(config)?,
+            start,
+            addresses: config.addresses.clone(),
+            batch_size,
+            intersected: false,
+            undo_after: None,
+            undo_blocks: 0,
+        })
+    }
+
+    /// Apply up to `max_blocks` blocks, and the rollbacks between them, from
+    /// the source. Stops early when the source has nothing new. After an
+    /// error the next call resumes from the stored blocks.
+    pub async fn follow(&mut self, max_blocks: usize) -> anyhow::Result<FollowReport> {
+        let mut report = FollowReport::default();
+        let result = self.follow_steps(max_blocks, &mut report).await;
+        if result.is_err() {
+            self.intersected = false;
+        }
+        result?;
+
+        if report.blocks > 0 {
+            self.prune().await?;
+        }
+        if let Some(tip) = self.source.tip() {
+            sqlx::query(
+                r#"
+                INSERT INTO chain_tip (slot, number, hash, block_time) VALUES ($1, $2, $3, $4)
+                ON CONFLICT (id) DO UPDATE
+                SET slot = $1, number = $2, hash = $3, block_time = $4, updated_at = NOW()
+                "#
+            )
+            .bind(tip.point.slot)
+            .bind(tip.number)
+            .bind(&tip.point.hash)
+            .bind(tip.block_time)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        if let Some(after) = self.undo_after {
+            if self.undo_blocks <= MAX_UNDO_BLOCKS && self.undo(after).await? {
+                report.undone = true;
+            } else {
+                self.rebuild().await?;
+                report.rebuilt = true;
+            }
+            self.undo_after = None;
+            self.undo_blocks = 0;
+        }
+        Ok(report)
+    }
+
+    async fn follow_steps(&mut self, max_blocks: usize, report: &mut FollowReport) -> anyhow::Result<()> {
+        if !self.intersected {
+            let points = self.resume_points().await?;
+            let found = self
+                .source
+                .intersect(&points)
+                .await
+                .with_context(|| format!("failed to find where to resume {}", self.source.describe()))?;
+            tracing::info!(
+                "Following {} from {}",
+                self.source.describe(),
+                found.as_ref().map_or("genesis".to_string(), |p| format!("slot {}", p.slot))
+            );
+            // Stored blocks past the intersection are no longer on the chain
+            if self.roll_back(found.as_ref()).await? {
+                report.rollbacks += 1;
+            }
+            self.intersected = true;
+        }
+
+        let mut tracked = self.tracked_addresses().await?;
+        while report.blocks < max_blocks {
+            match self.source.next().await? {
+                None => {
+                    report.at_tip = true;
+                    break;
+                }
+                Some(Step::RollForward(block)) => {
+                    report.kept += self.apply(&block, &mut tracked).await?;
+                    report.blocks += 1;
+                }
+                Some(Step::RollBackward(point)) => {
+                    if self.roll_back(point.as_ref()).await? {
+                        tracing::info!(
+                            "Rolled back to {}",
+                            point.as_ref().map_or("genesis".to_string(), |p| format!("slot {}", p.slot))
+                        );
+                        report.rollbacks += 1;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stored blocks to resume from, newest first, then the start point
+    async fn resume_points(&self) -> anyhow::Result<Vec<Point>> {
+        let rows: Vec<(i64, String)> = sqlx::query_as(
+            r#"
+            SELECT slot, hash FROM (
+                SELECT slot, hash, ROW_NUMBER() OVER (ORDER BY slot DESC) - 1 AS depth FROM chain_blocks
+            ) b
+            WHERE depth = ANY($1)
+            ORDER BY slot DESC
+            "#
+        )
+        .bind(RESUME_DEPTHS)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut points: Vec<Point> = rows.into_iter().map(|(slot, hash)| Point { slot, hash }).collect();
+        points.extend(self.start.clone());
+        Ok(points)
+    }
+
+    /// Contract and vendor addresses the processor recorded, and the
+    /// configured ones
+    async fn tracked_addresses(&self) -> anyhow::Result<HashSet<String>> {
+        let recorded: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT contract_address FROM treasury_contracts WHERE contract_address IS NOT NULL
+            UNION
+            SELECT contract_address FROM vendor_contracts WHERE contract_address IS NOT NULL
+            UNION
+            SELECT vendor_address FROM vendor_contracts WHERE vendor_address IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(recorded.into_iter().chain(self.addresses.iter().cloned()).collect())
+    }
+
+    /// Store a block and the transactions to keep from it, returning how
+    /// many were kept
+    async fn apply(&self, block: &Block, tracked: &mut HashSet<String>) -> anyhow::Result<usize> {
+        let inputs: Vec<&OutputRef> = block.transactions.iter().flat_map(|tx| &tx.inputs).collect();
+        let stored: Vec<(String, i16)> = sqlx::query_as(
+            r#"
+            SELECT o.tx_hash, o.output_index
+            FROM chain_outputs o
+            JOIN UNNEST($1::text[], $2::smallint[]) AS i(tx_hash, output_index)
+                ON o.tx_hash = i.tx_hash AND o.output_index = i.output_index
+            "#
+        )
+        .bind(inputs.iter().map(|i| i.tx_hash.as_str()).collect::<Vec<_>>())
+        .bind(inputs.iter().map(|i| i.output_index).collect::<Vec<_>>())
+        .fetch_all(&self.pool)
+        .await?;
+        let mut stored: HashSet<OutputRef> = stored
+            .into_iter()
+            .map(|(tx_hash, output_index)| OutputRef { tx_hash, output_index })
+            .collect();
+
+        let mut db = self.pool.begin().await?;
+        sqlx::query("INSERT INTO chain_blocks (slot, number, hash, block_time) VALUES ($1, $2, $3, $4)")
+            .bind(block.point.slot)
+            .bind(block.number)
+            .bind(&block.point.hash)
+            .bind(block.block_time)
+            .execute(&mut *db)
+            .await?;
+
+        let mut kept = 0;
+        for (index, tx) in block.transactions.iter().enumerate() {
+            let keep = tx.tom_metadata.is_some()
+                || tx.outputs.iter().any(|o| tracked.contains(&o.address))
+                || tx.inputs.iter().any(|i| stored.contains(i));
+            if !keep {
+                continue;
+            }
+            kept += 1;
+            if tx.tom_metadata.is_some() {
+                // Same heuristic as the event processor for contract outputs
+                tracked.extend(tx.outputs.iter().filter(|o| o.address.starts_with("addr1x")).map(|o| o.address.clone()));
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO chain_transactions (tx_hash, slot, block_index, treasury_donation, tom_metadata)
+                VALUES ($1, $2, $3, $4, $5)
+                "#
+            )
+            .bind(&tx.hash)
+            .bind(block.point.slot)
+            .bind(index as i32)
+            .bind(tx.treasury_donation)
+            .bind(&tx.tom_metadata)
+            .execute(&mut *db)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO chain_outputs (tx_hash, output_index, address, lovelace)
+                SELECT $1, o.output_index, o.address, o.lovelace
+                FROM UNNEST($2::smallint[], $3::text[], $4::bigint[]) AS o(output_index, address, lovelace)
+                "#
+            )
+            .bind(&tx.hash)
+            .bind(tx.outputs.iter().map(|o| o.index).collect::<Vec<_>>())
+            .bind(tx.outputs.iter().map(|o| o.address.as_str()).collect::<Vec<_>>())
+            .bind(tx.outputs.iter().map(|o| o.lovelace).collect::<Vec<_>>())
+            .execute(&mut *db)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO chain_inputs (tx_hash, output_index, spent_tx_hash)
+                SELECT i.tx_hash, i.output_index, $1
+                FROM UNNEST($2::text[], $3::smallint[]) AS i(tx_hash, output_index)
+                "#
+            )
+            .bind(&tx.hash)
+            .bind(tx.inputs.iter().map(|i| i.tx_hash.as_str()).collect::<Vec<_>>())
+            .bind(tx.inputs.iter().map(|i| i.output_index).collect::<Vec<_>>())
+            .execute(&mut *db)
+            .await?;
+
+            // Later transactions in the block may spend these outputs
+            stored.extend(tx.outputs.iter().map(|o| OutputRef {
+                tx_hash: tx.hash.clone(),
+                output_index: o.index,
+            }));
+        }
+        db.commit().await?;
+        Ok(kept)
+    }
+
+    /// Delete the blocks after `point` (after genesis for `None`). Returns
+    /// whether there were any.
+    async fn roll_back(&mut self, point: Option<&Point>) -> anyhow::Result<bool> {
+        let after = point.map_or(-1, |p| p.slot);
+        let mut db = self.pool.begin().await?;
+        let removed: Vec<String> = sqlx::query_scalar("DELETE FROM chain_transactions WHERE slot > $1 RETURNING tx_hash")
+            .bind(after)
+            .fetch_all(&mut *db)
+            .await?;
+        let blocks = sqlx::query("DELETE FROM chain_blocks WHERE slot > $1")
+            .bind(after)
+            .execute(&mut *db)
+            .await?
+            .rows_affected();
+        if blocks == 0 {
+            return Ok(false);
+        }
+
+        // Events were processed past the rollback point, or tracked outputs
+        // were created or spent by removed transactions
+        let reflected: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM sync_status WHERE sync_type = 'events' AND last_slot > $1)
+                OR EXISTS (SELECT 1 FROM utxos WHERE tx_hash = ANY($2) OR spent_tx_hash = ANY($2))
+            "#
+        )
+        .bind(after)
+        .bind(&removed)
+        .fetch_one(&mut *db)
+        .await?;
+        db.commit().await?;
+
+        if reflected {
+            tracing::warn!(
+                "Rollback past slot {} removed {} kept transactions the treasury tables reflect",
+                after,
+                removed.len()
+            );
+            self.undo_after = Some(self.undo_after.map_or(after, |a| a.min(after)));
+            self.undo_blocks += blocks;
+        }
+        Ok(blocks > 0)
+    }
+
+    /// Delete headers that can no longer be rolled back and hold no kept
+    /// transactions
+    async fn prune(&self) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM chain_blocks b
+            WHERE b.number < (SELECT MAX(number) FROM chain_blocks) - $1
+              AND NOT EXISTS (SELECT 1 FROM chain_transactions t WHERE t.slot = b.slot)
+            "#
+        )
+        .bind(SECURITY_PARAM)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Undo the treasury tables past slot `after`. Projects with events after
+    /// it are removed and replayed from their earlier events, and the events
+    /// cursor is rewound to it. Returns `false`, changing nothing, when a
+    /// rolled back event has no project; those need a rebuild.
+    async fn undo(&self, after: i64) -> anyhow::Result<bool> {
+        let mut db = self.pool.begin().await?;
+        let treasury_level: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM events WHERE slot > $1 AND vendor_contract_id IS NULL)"
+        )
+        .bind(after)
+        .fetch_one(&mut *db)
+        .await?;
+        if treasury_level {
+            return Ok(false);
+        }
+
+        let projects: Vec<i32> = sqlx::query_scalar(
+            "SELECT DISTINCT vendor_contract_id FROM events WHERE slot > $1"
+        )
+        .bind(after)
+        .fetch_all(&mut *db)
+        .await?;
+        // In the order they were processed
+        let kept: Vec<String> = sqlx::query_scalar(
+            "SELECT tx_hash FROM events WHERE vendor_contract_id = ANY($1) AND slot <= $2 ORDER BY slot, id"
+        )
+        .bind(&projects)
+        .bind(after)
+        .fetch_all(&mut *db)
+        .await?;
+        let mut events = Vec::with_capacity(kept.len());
+        for tx_hash in &kept {
+            let event = self
+                .chain
+                .tx_metadata(TOM_LABEL, tx_hash)
+                .await?
+                .with_context(|| format!("no TOM metadata for processed transaction {}", tx_hash))?;
+            events.push(event);
+        }
+
+        sqlx::query("DELETE FROM events WHERE slot > $1 OR vendor_contract_id = ANY($2)")
+            .bind(after)
+            .bind(&projects)
+            .execute(&mut *db)
+            .await?;
+        sqlx::query("DELETE FROM utxos WHERE slot > $1")
+            .bind(after)
+            .execute(&mut *db)
+            .await?;
+        sqlx::query("UPDATE utxos SET spent = false, spent_tx_hash = NULL, spent_slot = NULL WHERE spent_slot > $1")
+            .bind(after)
+            .execute(&mut *db)
+            .await?;
+        // Outputs the replay does not record are attributed again by address,
+        // as the UTXO sync does
+        let detached: Vec<i32> = sqlx::query_scalar(
+            "UPDATE utxos SET vendor_contract_id = NULL WHERE vendor_contract_id = ANY($1) RETURNING id"
+        )
+        .bind(&projects)
+        .fetch_all(&mut *db)
+        .await?;
+        // Milestones, revisions and summaries go with their project
+        sqlx::query("DELETE FROM vendor_contracts WHERE id = ANY($1)")
+            .bind(&projects)
+            .execute(&mut *db)
+            .await?;
+
+        let processor = EventProcessor::new(self.pool.clone(), self.chain.clone());
+        for event in &events {
+            processor.process_event_in(&mut db, event).await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE utxos u SET vendor_contract_id = vc.id
+            FROM vendor_contracts vc
+            WHERE u.id = ANY($1) AND u.vendor_contract_id IS NULL
+              AND (vc.contract_address = u.address OR vc.vendor_address = u.address)
+            "#
+        )
+        .bind(&detached)
+        .execute(&mut *db)
+        .await?;
+
+        // The sync cursor is exclusive: events with slot > last_slot are fetched
+        sqlx::query(
+            r#"
+            UPDATE sync_status
+            SET last_slot = $1, last_block = NULL, last_tx_hash = NULL, updated_at = NOW()
+            WHERE sync_type = 'events' AND last_slot > $1
+            "#
+        )
+        .bind(after)
+        .execute(&mut *db)
+        .await?;
+        db.commit().await?;
+
+        // UTXO changes are not tied to a single event's summaries
+        summaries::rebuild(&self.pool).await?;
+        tracing::info!(
+            "Undid the treasury tables past slot {}, replaying {} projects from {} events",
+            after,
+            projects.len(),
+            events.len()
+        );
+        Ok(true)
+    }
+
+    /// Rebuild the treasury tables from the chain data left after a rollback
+    async fn rebuild(&mut self) -> anyhow::Result<()> {
+        tracing::info!("Rebuilding the treasury tables after a rollback");
+        let built = reindex::build(&self.pool, &self.database, self.chain.clone(), self.batch_size)
+            .await
+            .context("failed to rebuild after a rollback")?;
+        let swapped = reindex::swap_as_leader(&self.pool, &self.database, self.chain.clone(), self.batch_size)
+            .await
+            .context("failed to swap in the rebuilt tables")?;
+        tracing::info!(
+            "Rebuilt the treasury tables from {} TOM events",
+            built.replayed_events + swapped.caught_up_events
+        );
+        Ok(())
+    }
+}
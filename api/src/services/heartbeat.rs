@@ -0,0 +1,146 @@
+//! Sync heartbeat and lag
+//!
+//! The sync leader records how far it has scanned the chain source after every
+//! cycle in `treasury.sync_heartbeat`. The scanned slot is the indexer tip
+//! when a cycle drained every pending event, so unlike the event cursor in
+//! `sync_status` it keeps advancing while no TOM events arrive. Readiness
+//! probes, lag reporting and stale-data headers read it from any process.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::LazyLock;
+
+use crate::chain::{BlockInfo, ChainError, ChainSource};
+use crate::config::HealthConfig;
+
+/// Slots the chain advances per second (one-second slots since Shelley)
+const CHAIN_SLOTS_PER_SEC: f64 = 1.0;
+
+/// Identifies this process in heartbeats
+static WORKER: LazyLock<String> = LazyLock::new(|| {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "unknown".to_string());
+    format!("{}:{}", host, std::process::id())
+});
+
+/// Record that this worker has scanned up to `scanned_slot`; `caught_up` if
+/// the cycle processed every event up to the indexer tip
+pub async fn beat(pool: &PgPool, scanned_slot: i64, caught_up: bool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO treasury.sync_heartbeat (worker, scanned_slot, beat_at, caught_up)
+        VALUES ($1, $2, NOW(), $3)
+        ON CONFLICT (id) DO UPDATE SET
+            worker = EXCLUDED.worker,
+            scanned_slot = EXCLUDED.scanned_slot,
+            beat_at = EXCLUDED.beat_at,
+            caught_up = EXCLUDED.caught_up,
+            slots_per_sec = CASE
+                WHEN treasury.sync_heartbeat.worker = EXCLUDED.worker
+                     AND EXCLUDED.beat_at > treasury.sync_heartbeat.beat_at
+                THEN (EXCLUDED.scanned_slot - treasury.sync_heartbeat.scanned_slot)
+                     / EXTRACT(EPOCH FROM EXCLUDED.beat_at - treasury.sync_heartbeat.beat_at)::DOUBLE PRECISION
+            END
+        "#
+    )
+    .bind(WORKER.as_str())
+    .bind(scanned_slot)
+    .bind(caught_up)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The last heartbeat
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Heartbeat {
+    pub worker: String,
+    pub scanned_slot: i64,
+    /// Unix time of the last block at or before the scanned slot
+    #[sqlx(default)]
+    pub scanned_block_time: Option<i64>,
+    pub beat_at: DateTime<Utc>,
+    pub caught_up: bool,
+    pub slots_per_sec: Option<f64>,
+}
+
+/// Sync position relative to the indexer tip and the wall clock
+#[derive(Debug, Clone)]
+pub struct SyncProgress {
+    pub tip: Option<BlockInfo>,
+    /// `None` if sync has never completed a cycle
+    pub heartbeat: Option<Heartbeat>,
+}
+
+/// Read the indexer tip and the last heartbeat
+pub async fn progress(pool: &PgPool, chain: &dyn ChainSource) -> Result<SyncProgress, ChainError> {
+    let tip = chain.tip().await?;
+    let mut heartbeat = sqlx::query_as::<_, Heartbeat>(
+        "SELECT worker, scanned_slot, beat_at, caught_up, slots_per_sec FROM treasury.sync_heartbeat"
+    )
+    .fetch_optional(pool)
+    .await?;
+    if let Some(ref mut beat) = heartbeat {
+        beat.scanned_block_time = chain.block_at_or_before(beat.scanned_slot).await?.map(|b| b.block_time);
+    }
+    Ok(SyncProgress { tip, heartbeat })
+}
+
+impl SyncProgress {
+    /// Seconds since the last heartbeat
+    pub fn heartbeat_age_secs(&self) -> Option<i64> {
+        let beat = self.heartbeat.as_ref()?;
+        Some((Utc::now() - beat.beat_at).num_seconds().max(0))
+    }
+
+    /// Slots between the scanned slot and the indexer tip
+    pub fn slots_behind_tip(&self) -> Option<i64> {
+        Some((self.tip?.slot - self.heartbeat.as_ref()?.scanned_slot).max(0))
+    }
+
+    /// Block time between the scanned slot and the indexer tip
+    pub fn seconds_behind_tip(&self) -> Option<i64> {
+        let scanned = self.heartbeat.as_ref()?.scanned_block_time?;
+        Some((self.tip?.block_time - scanned).max(0))
+    }
+
+    /// Wall-clock time since the block at the scanned slot
+    pub fn seconds_behind_now(&self) -> Option<i64> {
+        let scanned = self.heartbeat.as_ref()?.scanned_block_time?;
+        Some((Utc::now().timestamp() - scanned).max(0))
+    }
+
+    /// Wall-clock time since the indexer's latest block
+    pub fn indexer_lag_secs(&self) -> Option<i64> {
+        Some((Utc::now().timestamp() - self.tip?.block_time).max(0))
+    }
+
+    /// Estimated seconds until sync reaches the tip: zero once a cycle drained
+    /// every pending event, otherwise the gap divided by how much faster than
+    /// the chain the last cycle scanned. `None` if sync is not gaining, or has
+    /// not beaten within `max_heartbeat_age_secs`.
+    pub fn estimated_catch_up_secs(&self, health: &HealthConfig) -> Option<i64> {
+        if self.heartbeat_stale(health) {
+            return None;
+        }
+        let beat = self.heartbeat.as_ref()?;
+        if beat.caught_up {
+            return Some(0);
+        }
+        let behind = self.slots_behind_tip()?;
+        let gain = beat.slots_per_sec? - CHAIN_SLOTS_PER_SEC;
+        (gain > 0.0).then(|| (behind as f64 / gain).ceil() as i64)
+    }
+
+    /// Whether data should be treated as out of date: no recent heartbeat, or
+    /// more than `max_lag_slots` behind the tip
+    pub fn is_stale(&self, health: &HealthConfig) -> bool {
+        let lagging = self.slots_behind_tip().is_some_and(|lag| lag > health.max_lag_slots);
+        self.heartbeat_stale(health) || lagging
+    }
+
+    /// No heartbeat within `max_heartbeat_age_secs`
+    pub fn heartbeat_stale(&self, health: &HealthConfig) -> bool {
+        self.heartbeat_age_secs()
+            .is_none_or(|age| age > health.max_heartbeat_age_secs as i64)
+    }
+}
@@ -0,0 +1,53 @@
+//! Sync leader election
+//!
+//! Only one process may write to the treasury schema at a time. Writers take a
+//! session-level PostgreSQL advisory lock on a dedicated connection; the lock is
+//! released when the connection closes, so a crashed worker cannot keep it.
+
+use sqlx::{Connection, PgConnection, PgPool};
+use std::time::Duration;
+
+/// Advisory lock key shared by sync workers and `treasury-cli` write commands
+const SYNC_LOCK_KEY: &str = "treasury-sync";
+
+/// Held sync leadership. Dropping it closes the connection and releases the lock.
+pub struct LeaderLock {
+    conn: PgConnection,
+}
+
+impl LeaderLock {
+    /// Take the sync lock if no other process holds it
+    pub async fn try_acquire(pool: &PgPool) -> Result<Option<Self>, sqlx::Error> {
+        // Detached so the lock's session is not handed to other queries
+        let mut conn = pool.acquire().await?.detach();
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext($1))")
+            .bind(SYNC_LOCK_KEY)
+            .fetch_one(&mut conn)
+            .await?;
+        if acquired {
+            Ok(Some(Self { conn }))
+        } else {
+            conn.close().await.ok();
+            Ok(None)
+        }
+    }
+
+    /// Resolves once the lock's session is lost, checking every `interval`
+    pub async fn lost(&mut self, interval: Duration) -> sqlx::Error {
+        loop {
+            tokio::time::sleep(interval).await;
+            if let Err(e) = self.conn.ping().await {
+                return e;
+            }
+        }
+    }
+
+    /// Release the lock
+    pub async fn release(mut self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+            .bind(SYNC_LOCK_KEY)
+            .execute(&mut self.conn)
+            .await?;
+        self.conn.close().await
+    }
+}
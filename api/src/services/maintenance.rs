@@ -0,0 +1,395 @@
+//! Maintenance operations behind `treasury-cli`
+//!
+//! Everything here works directly against the database, so it can run while
+//! the API is stopped. Commands that write events take the sync leader lock
+//! and refuse to run while a sync worker is active. Tables are not
+//! schema-qualified, so they resolve to the schema the pool was connected to.
+
+use futures_util::TryStreamExt;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::future::Future;
+use std::io::Write;
+use std::sync::Arc;
+
+use super::curation;
+use super::event_processor::EventProcessor;
+use super::leader::LeaderLock;
+use super::summaries;
+use super::sync::sync_new_events;
+use super::vendors::resolve_vendors;
+use crate::chain::{ChainSource, OutputRef, TOM_LABEL};
+
+// ============================================================================
+// RESYNC / REPROCESS
+// ============================================================================
+
+/// Outcome of a resync
+#[derive(Debug)]
+pub struct ResyncReport {
+    /// Event rows removed before replaying
+    pub deleted_events: u64,
+    /// TOM events fetched from the indexer and replayed
+    pub replayed_events: usize,
+}
+
+/// Drop processed events from `from_slot` onwards and replay them from the
+/// indexer. Projects and milestones are upserted by the replay, so their state
+/// ends up as if the events had been processed for the first time.
+pub async fn resync_from_slot(
+    pool: &PgPool,
+    chain: Arc<dyn ChainSource>,
+    from_slot: i64,
+    batch_size: i64,
+) -> anyhow::Result<ResyncReport> {
+    let lock = leader_lock(pool).await?;
+    let mut tx = pool.begin().await?;
+
+    let deleted_events = sqlx::query("DELETE FROM events WHERE slot >= $1")
+        .bind(from_slot)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    sqlx::query("DELETE FROM vendor_contract_revisions WHERE slot >= $1")
+        .bind(from_slot)
+        .execute(&mut *tx)
+        .await?;
+
+    // The sync cursor is exclusive: events with slot > last_slot are fetched
+    sqlx::query(
+        r#"
+        UPDATE sync_status
+        SET last_slot = $1, last_block = NULL, last_tx_hash = NULL, updated_at = NOW()
+        WHERE sync_type = 'events'
+        "#
+    )
+    .bind(from_slot - 1)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let processor = EventProcessor::new(pool.clone(), chain);
+    let mut replayed_events = 0;
+    loop {
+        let fetched = sync_new_events(pool, &processor, batch_size).await?;
+        replayed_events += fetched;
+        if fetched == 0 {
+            break;
+        }
+    }
+    resolve_vendors(pool).await?;
+    // Deleted events may belong to projects the replay no longer touches
+    summaries::rebuild(pool).await?;
+    lock.release().await?;
+
+    Ok(ResyncReport {
+        deleted_events,
+        replayed_events,
+    })
+}
+
+/// Reprocess a single transaction's TOM event. Returns `false` if the indexer
+/// has no TOM metadata for the transaction.
+pub async fn reprocess_tx(pool: &PgPool, chain: Arc<dyn ChainSource>, tx_hash: &str) -> anyhow::Result<bool> {
+    let event = chain.tx_metadata(TOM_LABEL, tx_hash).await?;
+
+    let Some(event) = event else {
+        return Ok(false);
+    };
+    let lock = leader_lock(pool).await?;
+    // The old copy is only gone once the replay has succeeded
+    let mut tx = pool.begin().await?;
+
+    // Event rows are insert-once, so remove the old copy before replaying
+    sqlx::query("DELETE FROM events WHERE tx_hash = $1")
+        .bind(tx_hash)
+        .execute(&mut *tx)
+        .await?;
+    // Only the latest revision is re-recorded; a superseded amendment is not
+    // re-applied, so its revision is kept as recorded
+    sqlx::query(
+        r#"
+        DELETE FROM vendor_contract_revisions r
+        WHERE r.tx_hash = $1
+          AND NOT EXISTS (
+              SELECT 1 FROM vendor_contract_revisions l
+              WHERE l.vendor_contract_id = r.vendor_contract_id AND l.revision > r.revision
+          )
+        "#
+    )
+    .bind(tx_hash)
+    .execute(&mut *tx)
+    .await?;
+
+    let processor = EventProcessor::new(pool.clone(), chain);
+    processor.process_event_in(&mut tx, &event).await?;
+    tx.commit().await?;
+
+    processor.sync_utxos().await?;
+    resolve_vendors(pool).await?;
+    // The old copy's project may differ from the replay's
+    summaries::rebuild(pool).await?;
+    lock.release().await?;
+
+    Ok(true)
+}
+
+/// Take sync leadership for the duration of a write command
+pub(super) async fn leader_lock(pool: &PgPool) -> anyhow::Result<LeaderLock> {
+    LeaderLock::try_acquire(pool).await?.ok_or_else(|| {
+        anyhow::anyhow!("a sync worker is running; stop it or set FEATURE_SYNC=false before writing events")
+    })
+}
+
+// ============================================================================
+// VERIFY
+// ============================================================================
+
+/// Unspent balance of a tracked address, as recorded and as indexed
+#[derive(Debug)]
+pub struct BalanceCheck {
+    pub address: String,
+    pub address_type: Option<String>,
+    pub tracked_lovelace: i64,
+    pub indexer_lovelace: i64,
+}
+
+impl BalanceCheck {
+    pub fn matches(&self) -> bool {
+        self.tracked_lovelace == self.indexer_lovelace
+    }
+}
+
+/// Compare unspent balances in `utxos` with the indexer for every
+/// tracked address
+pub async fn verify_balances(pool: &PgPool, chain: &dyn ChainSource) -> anyhow::Result<Vec<BalanceCheck>> {
+    let tracked = sqlx::query_as::<_, (String, Option<String>, i64)>(
+        r#"
+        WITH addresses AS (
+            SELECT contract_address AS address FROM treasury_contracts WHERE contract_address IS NOT NULL
+            UNION
+            SELECT contract_address FROM vendor_contracts WHERE contract_address IS NOT NULL
+            UNION
+            SELECT vendor_address FROM vendor_contracts WHERE vendor_address IS NOT NULL
+            UNION
+            SELECT address FROM utxos
+        ),
+        tracked AS (
+            SELECT address, MIN(address_type) AS address_type,
+                   COALESCE(SUM(lovelace_amount) FILTER (WHERE NOT spent), 0) AS lovelace
+            FROM utxos
+            GROUP BY address
+        )
+        SELECT a.address, t.address_type, COALESCE(t.lovelace, 0)::BIGINT AS tracked_lovelace
+        FROM addresses a
+        LEFT JOIN tracked t ON t.address = a.address
+        ORDER BY a.address
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut checks = Vec::with_capacity(tracked.len());
+    for (address, address_type, tracked_lovelace) in tracked {
+        let utxos = chain.address_utxos(&address).await?;
+        let refs: Vec<OutputRef> = utxos
+            .iter()
+            .map(|u| OutputRef {
+                tx_hash: u.tx_hash.clone(),
+                output_index: u.output_index,
+            })
+            .collect();
+        let spent: HashSet<OutputRef> = chain
+            .spends(&refs)
+            .await?
+            .into_iter()
+            .map(|s| OutputRef {
+                tx_hash: s.tx_hash,
+                output_index: s.output_index,
+            })
+            .collect();
+        let indexer_lovelace = utxos
+            .iter()
+            .zip(&refs)
+            .filter(|(_, r)| !spent.contains(r))
+            .map(|(u, _)| u.lovelace)
+            .sum();
+        checks.push(BalanceCheck {
+            address,
+            address_type,
+            tracked_lovelace,
+            indexer_lovelace,
+        });
+    }
+
+    Ok(checks)
+}
+
+// ============================================================================
+// EXPORT
+// ============================================================================
+
+/// Data set to export
+#[derive(Debug, Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportEntity {
+    VendorContracts,
+    Vendors,
+    Milestones,
+    Events,
+    Utxos,
+}
+
+impl ExportEntity {
+    /// Source query, in a stable order. Projects and milestones carry their
+    /// curated values, as the API shows them.
+    fn query(self) -> String {
+        match self {
+            Self::VendorContracts => format!(
+                "SELECT c.* FROM v_vendor_contracts_summary s CROSS JOIN LATERAL {} c ORDER BY c.project_id",
+                curation::curated_contract_row("s")
+            ),
+            Self::Vendors => "SELECT * FROM v_vendor_summary ORDER BY id".to_string(),
+            Self::Milestones => format!(
+                "SELECT c.* FROM milestones m CROSS JOIN LATERAL {} c ORDER BY c.vendor_contract_id, c.milestone_order",
+                curation::curated_milestone_row("m")
+            ),
+            Self::Events => "SELECT * FROM events ORDER BY slot, id".to_string(),
+            Self::Utxos => "SELECT * FROM utxos ORDER BY slot, tx_hash, output_index".to_string(),
+        }
+    }
+}
+
+/// Export file format
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One JSON object per line
+    #[default]
+    Jsonl,
+    /// CSV with a header row
+    Csv,
+}
+
+/// Approximate size of the chunks JSON lines are passed on in
+const EXPORT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Write every row of `entity` to `out`, returning the number of rows
+pub async fn export<W: Write + ?Sized>(
+    pool: &PgPool,
+    entity: ExportEntity,
+    format: ExportFormat,
+    out: &mut W,
+) -> anyhow::Result<u64> {
+    let rows = export_chunks(pool, entity, format, |chunk| {
+        std::future::ready(out.write_all(&chunk).map_err(anyhow::Error::from))
+    })
+    .await?;
+    out.flush()?;
+    Ok(rows)
+}
+
+/// Pass every row of `entity` to `send` in chunks as the rows are read,
+/// returning the number of rows. Only one chunk is held at a time.
+pub async fn export_chunks<F, Fut>(
+    pool: &PgPool,
+    entity: ExportEntity,
+    format: ExportFormat,
+    mut send: F,
+) -> anyhow::Result<u64>
+where
+    F: FnMut(Vec<u8>) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let mut rows: u64 = 0;
+    match format {
+        ExportFormat::Jsonl => {
+            // row_to_json keeps the column order of the source
+            let query = format!("SELECT row_to_json(t)::text FROM ({}) t", entity.query());
+            let mut stream = sqlx::query_scalar::<_, String>(&query).fetch(pool);
+            let mut chunk = Vec::new();
+            while let Some(line) = stream.try_next().await? {
+                chunk.extend_from_slice(line.as_bytes());
+                chunk.push(b'\n');
+                rows += 1;
+                if chunk.len() >= EXPORT_CHUNK_BYTES {
+                    send(std::mem::take(&mut chunk)).await?;
+                }
+            }
+            if !chunk.is_empty() {
+                send(chunk).await?;
+            }
+        }
+        ExportFormat::Csv => {
+            let statement = format!("COPY ({}) TO STDOUT WITH (FORMAT csv, HEADER)", entity.query());
+            let mut conn = pool.acquire().await?;
+            let mut stream = conn.copy_out_raw(&statement).await?;
+            while let Some(chunk) = stream.try_next().await? {
+                rows += chunk.iter().filter(|&&b| b == b'\n').count() as u64;
+                send(chunk.to_vec()).await?;
+            }
+            // Not counting the header
+            rows = rows.saturating_sub(1);
+        }
+    }
+    Ok(rows)
+}
+
+// ============================================================================
+// STATUS
+// ============================================================================
+
+/// Sync progress and table counts
+#[derive(Debug, sqlx::FromRow)]
+pub struct SyncReport {
+    pub last_slot: Option<i64>,
+    pub last_block: Option<i64>,
+    pub last_tx_hash: Option<String>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[sqlx(default)]
+    pub indexer_tip_slot: Option<i64>,
+    #[sqlx(default)]
+    pub indexer_tip_block: Option<i64>,
+    pub treasury_contracts: i64,
+    pub vendor_contracts: i64,
+    pub milestones: i64,
+    pub events: i64,
+    pub unspent_utxos: i64,
+}
+
+/// Current sync position against the indexer tip. `None` if the treasury
+/// schema has not been created yet.
+pub async fn sync_report(pool: &PgPool, chain: &dyn ChainSource) -> anyhow::Result<Option<SyncReport>> {
+    let initialized: bool = sqlx::query_scalar("SELECT to_regclass('sync_status') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+    if !initialized {
+        return Ok(None);
+    }
+
+    let mut report = sqlx::query_as::<_, SyncReport>(
+        r#"
+        SELECT
+            s.last_slot,
+            s.last_block,
+            s.last_tx_hash,
+            s.updated_at,
+            (SELECT COUNT(*) FROM treasury_contracts) AS treasury_contracts,
+            (SELECT COUNT(*) FROM vendor_contracts) AS vendor_contracts,
+            (SELECT COUNT(*) FROM milestones) AS milestones,
+            (SELECT COUNT(*) FROM events) AS events,
+            (SELECT COUNT(*) FROM utxos WHERE NOT spent) AS unspent_utxos
+        FROM (SELECT 1) one
+        LEFT JOIN sync_status s ON s.sync_type = 'events'
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+    let tip = chain.tip().await?;
+    report.indexer_tip_slot = tip.map(|t| t.slot);
+    report.indexer_tip_block = tip.map(|t| t.number);
+
+    Ok(Some(report))
+}
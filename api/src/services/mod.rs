@@ -1,5 +1,17 @@
 // Business logic services
 pub mod sync;
 pub mod event_processor;
+pub mod revisions;
+pub mod evidence;
+pub mod vendors;
+pub mod maintenance;
+pub mod leader;
+pub mod summaries;
+pub mod heartbeat;
+pub mod api_keys;
+pub mod curation;
+pub mod reindex;
+pub mod follower;
+pub mod pending;
 
-pub use sync::run_sync_loop;
+pub use sync::run_sync_worker;
@@ -0,0 +1,187 @@
+//! Pending TOM transactions
+//!
+//! Vendors want to see a disbursement once it is submitted, not only once it
+//! lands in a block. Each sync cycle this:
+//!
+//! 1. reads the submitted-transaction directory (`PENDING_SOURCE_DIR`), a
+//!    local stand-in for a submit API or mempool, and records every new TOM
+//!    transaction in `treasury.pending_transactions`;
+//! 2. confirms transactions whose event has been processed, and returns
+//!    confirmed ones to pending if a rollback removed their event;
+//! 3. expires transactions that can no longer be confirmed: the scanned slot
+//!    has reached their `invalid_hereafter`, or they have been pending longer
+//!    than `pending.expire_after_secs`.
+//!
+//! The directory holds cardano-cli text envelopes (`{"cborHex": ...}`), as
+//! written by `cardano-cli transaction sign`. Removing a file does not expire
+//! its transaction: it may still be in a node's mempool.
+
+use serde_json::Value;
+use sqlx::PgPool;
+use std::path::{Path, PathBuf};
+
+use crate::chain::blocks::{cbor, Transaction};
+use crate::config::PendingConfig;
+
+/// What a pending sync changed
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PendingReport {
+    pub added: u64,
+    pub confirmed: u64,
+    pub reverted: u64,
+    pub expired: u64,
+}
+
+/// A submitted transaction carrying TOM metadata
+#[derive(Debug, Clone)]
+pub struct SubmittedTx {
+    pub tx_hash: String,
+    /// Lowercased event name
+    pub event_type: String,
+    pub instance: Option<String>,
+    pub project_id: Option<String>,
+    /// Lovelace paid to non-script addresses, for disbursements and
+    /// withdrawals
+    pub amount_lovelace: Option<i64>,
+    /// First slot the transaction is no longer valid in
+    pub invalid_hereafter: Option<i64>,
+    pub metadata: Value,
+}
+
+impl SubmittedTx {
+    /// The TOM event a transaction carries, if any
+    pub fn from_transaction(tx: Transaction, invalid_hereafter: Option<i64>) -> Option<Self> {
+        let metadata = tx.tom_metadata?;
+        let event_body = metadata.get("body").unwrap_or(&metadata);
+        let event_type = match event_body.get("event").and_then(|e| e.as_str())?.to_lowercase() {
+            e if e == "sweeptreasury" || e == "sweepvendor" => "sweep".to_string(),
+            e => e,
+        };
+        let instance = metadata.get("instance").and_then(|i| i.as_str()).map(String::from);
+        let project_id = event_body
+            .get("identifier")
+            .and_then(|i| i.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+        // Same rule as the event processor: everything paid to non-script addresses
+        let amount_lovelace = matches!(event_type.as_str(), "disburse" | "withdraw").then(|| {
+            tx.outputs
+                .iter()
+                .filter(|o| !o.address.starts_with("addr1x"))
+                .map(|o| o.lovelace)
+                .sum()
+        });
+        Some(Self {
+            tx_hash: tx.hash,
+            event_type,
+            instance,
+            project_id,
+            amount_lovelace,
+            invalid_hereafter,
+            metadata,
+        })
+    }
+}
+
+/// Decode a cardano-cli text envelope holding a transaction; `Ok(None)` if
+/// the file is not a text envelope
+pub fn read_envelope(path: &Path) -> anyhow::Result<Option<(Transaction, Option<i64>)>> {
+    let Ok(envelope) = serde_json::from_slice::<Value>(&std::fs::read(path)?) else {
+        return Ok(None);
+    };
+    let Some(cbor_hex) = envelope.get("cborHex").and_then(|c| c.as_str()) else {
+        return Ok(None);
+    };
+    cbor::decode_transaction(&hex::decode(cbor_hex)?).map(Some)
+}
+
+/// TOM transactions in the submitted-transaction directory. Other files are
+/// ignored; envelopes that do not decode are skipped with a warning.
+pub fn read_submitted(dir: &Path) -> anyhow::Result<Vec<SubmittedTx>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .collect();
+    paths.sort();
+
+    let mut submitted = Vec::new();
+    for path in paths {
+        match read_envelope(&path) {
+            Ok(Some((tx, invalid_hereafter))) => {
+                submitted.extend(SubmittedTx::from_transaction(tx, invalid_hereafter))
+            }
+            Ok(None) => tracing::debug!("{} is not a text envelope; ignoring it", path.display()),
+            Err(e) => tracing::warn!("Skipping submitted transaction {}: {:#}", path.display(), e),
+        }
+    }
+    Ok(submitted)
+}
+
+/// Record new submitted transactions, then confirm and expire pending ones
+pub async fn sync_pending(pool: &PgPool, config: &PendingConfig) -> anyhow::Result<PendingReport> {
+    let mut report = PendingReport::default();
+
+    if let Some(ref dir) = config.source_dir {
+        for tx in read_submitted(dir)? {
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO pending_transactions
+                    (tx_hash, event_type, instance, project_id, amount_lovelace, invalid_hereafter, metadata)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (tx_hash) DO NOTHING
+                "#
+            )
+            .bind(&tx.tx_hash)
+            .bind(&tx.event_type)
+            .bind(&tx.instance)
+            .bind(&tx.project_id)
+            .bind(tx.amount_lovelace)
+            .bind(tx.invalid_hereafter)
+            .bind(&tx.metadata)
+            .execute(pool)
+            .await?;
+            report.added += inserted.rows_affected();
+        }
+    }
+
+    // A late confirmation wins over expiry
+    report.confirmed = sqlx::query(
+        r#"
+        UPDATE pending_transactions p
+        SET status = 'confirmed', slot = e.slot, resolved_at = NOW()
+        FROM events e
+        WHERE e.tx_hash = p.tx_hash AND p.status <> 'confirmed'
+        "#
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    report.reverted = sqlx::query(
+        r#"
+        UPDATE pending_transactions p
+        SET status = 'pending', slot = NULL, resolved_at = NULL
+        WHERE p.status = 'confirmed'
+          AND NOT EXISTS (SELECT 1 FROM events e WHERE e.tx_hash = p.tx_hash)
+        "#
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    report.expired = sqlx::query(
+        r#"
+        UPDATE pending_transactions
+        SET status = 'expired', resolved_at = NOW()
+        WHERE status = 'pending'
+          AND (invalid_hereafter <= (SELECT MAX(scanned_slot) FROM sync_heartbeat)
+               OR submitted_at < NOW() - make_interval(secs => $1))
+        "#
+    )
+    .bind(config.expire_after_secs as f64)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    Ok(report)
+}
@@ -0,0 +1,657 @@
+//! Full reindex into a shadow schema
+//!
+//! A processor fix changes how already-processed events are interpreted, so
+//! it has to be checked against everything in the database before it goes
+//! live. A reindex runs in three steps behind `treasury-cli reindex`:
+//!
+//! 1. `build` creates `treasury_shadow` from the same migrations and replays
+//!    every label-1694 event from the indexer into it through
+//!    [`EventProcessor`], while the API keeps serving `treasury`;
+//! 2. `diff` compares the two schemas table by table. Rows are matched on
+//!    logical keys (project id, tx hash, ...) since database ids differ
+//!    between the schemas;
+//! 3. `swap` catches the shadow up with the indexer and renames it to
+//!    `treasury` in one transaction. The old schema is kept as
+//!    `treasury_previous` until the next swap.
+//!
+//! Operator data that cannot be replayed (API keys, curation, vendor aliases,
+//! the evidence archive and fetch state, the sync heartbeat, the chain
+//! follower's blocks) is copied from the live schema when the shadow is
+//! built, so the diff reflects it, and again inside the swap transaction, so
+//! nothing written in between is lost.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::{PgConnection, PgPool};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use super::event_processor::EventProcessor;
+use super::evidence::normalise_all;
+use super::maintenance::leader_lock;
+use super::summaries;
+use super::sync::sync_new_events;
+use super::vendors::resolve_vendors;
+use crate::chain::ChainSource;
+use crate::config::DatabaseConfig;
+use crate::db::{self, migrations, Migrator};
+
+/// Schema a reindex is built in
+pub const SHADOW_SCHEMA: &str = "treasury_shadow";
+
+/// Name the live schema is kept under after a swap
+pub const PREVIOUS_SCHEMA: &str = "treasury_previous";
+
+/// Tables written by operators, the API, the chain follower or the pending
+/// feed rather than derived from events, in foreign key order
+const OPERATOR_TABLES: &[&str] = &[
+    "evidence_archive",
+    "vendor_aliases",
+    "api_keys",
+    "curation_overrides",
+    "curation_annotations",
+    "curation_audit_log",
+    "sync_heartbeat",
+    "chain_blocks",
+    "chain_transactions",
+    "chain_outputs",
+    "chain_inputs",
+    "chain_tip",
+    "pending_transactions",
+];
+
+/// How a derived table is compared: `key` identifies a row across schemas and
+/// `row` is its content without database ids and bookkeeping timestamps.
+/// `{s}` is replaced by the schema.
+struct TableSpec {
+    table: &'static str,
+    key: &'static str,
+    row: &'static str,
+    from: &'static str,
+}
+
+const DIFF_TABLES: &[TableSpec] = &[
+    TableSpec {
+        table: "treasury_contracts",
+        key: "t.contract_instance",
+        row: "to_jsonb(t) - ARRAY['id', 'created_at', 'updated_at']",
+        from: "{s}.treasury_contracts t",
+    },
+    TableSpec {
+        table: "vendors",
+        key: "t.vendor_key",
+        row: "to_jsonb(t) - ARRAY['id', 'created_at', 'updated_at']",
+        from: "{s}.vendors t",
+    },
+    TableSpec {
+        table: "vendor_contracts",
+        key: "t.project_id",
+        row: "to_jsonb(t) - ARRAY['id', 'treasury_id', 'vendor_id', 'created_at', 'updated_at']
+              || jsonb_build_object('treasury', tc.contract_instance, 'vendor', v.vendor_key)",
+        from: "{s}.vendor_contracts t
+               LEFT JOIN {s}.treasury_contracts tc ON tc.id = t.treasury_id
+               LEFT JOIN {s}.vendors v ON v.id = t.vendor_id",
+    },
+    TableSpec {
+        table: "milestones",
+        key: "vc.project_id || '/' || t.milestone_id",
+        row: "to_jsonb(t) - ARRAY['id', 'vendor_contract_id', 'created_at', 'updated_at']",
+        from: "{s}.milestones t JOIN {s}.vendor_contracts vc ON vc.id = t.vendor_contract_id",
+    },
+    TableSpec {
+        table: "milestone_evidence",
+        key: "vc.project_id || '/' || m.milestone_id || '#' || t.position",
+        row: "to_jsonb(t) - ARRAY['id', 'milestone_id', 'created_at', 'updated_at']",
+        from: "{s}.milestone_evidence t
+               JOIN {s}.milestones m ON m.id = t.milestone_id
+               JOIN {s}.vendor_contracts vc ON vc.id = m.vendor_contract_id",
+    },
+    TableSpec {
+        table: "vendor_contract_revisions",
+        key: "vc.project_id || '#' || t.revision",
+        row: "to_jsonb(t) - ARRAY['id', 'vendor_contract_id', 'created_at']",
+        from: "{s}.vendor_contract_revisions t JOIN {s}.vendor_contracts vc ON vc.id = t.vendor_contract_id",
+    },
+    TableSpec {
+        table: "events",
+        key: "t.tx_hash",
+        row: "to_jsonb(t) - ARRAY['id', 'treasury_id', 'vendor_contract_id', 'milestone_id', 'created_at']
+              || jsonb_build_object('treasury', tc.contract_instance, 'project_id', vc.project_id,
+                                    'milestone', mvc.project_id || '/' || m.milestone_id)",
+        from: "{s}.events t
+               LEFT JOIN {s}.treasury_contracts tc ON tc.id = t.treasury_id
+               LEFT JOIN {s}.vendor_contracts vc ON vc.id = t.vendor_contract_id
+               LEFT JOIN {s}.milestones m ON m.id = t.milestone_id
+               LEFT JOIN {s}.vendor_contracts mvc ON mvc.id = m.vendor_contract_id",
+    },
+    TableSpec {
+        table: "utxos",
+        key: "t.tx_hash || '#' || t.output_index",
+        row: "to_jsonb(t) - ARRAY['id', 'vendor_contract_id'] || jsonb_build_object('project_id', vc.project_id)",
+        from: "{s}.utxos t LEFT JOIN {s}.vendor_contracts vc ON vc.id = t.vendor_contract_id",
+    },
+    TableSpec {
+        table: "project_summary",
+        key: "vc.project_id",
+        row: "to_jsonb(t) - ARRAY['vendor_contract_id', 'updated_at']",
+        from: "{s}.project_summary t JOIN {s}.vendor_contracts vc ON vc.id = t.vendor_contract_id",
+    },
+    TableSpec {
+        table: "treasury_summary",
+        key: "tc.contract_instance",
+        row: "to_jsonb(t) - ARRAY['treasury_id', 'updated_at']",
+        from: "{s}.treasury_summary t JOIN {s}.treasury_contracts tc ON tc.id = t.treasury_id",
+    },
+    TableSpec {
+        table: "sync_status",
+        key: "t.sync_type",
+        row: "to_jsonb(t) - ARRAY['id', 'updated_at']",
+        from: "{s}.sync_status t",
+    },
+];
+
+// ============================================================================
+// BUILD
+// ============================================================================
+
+/// Outcome of building the shadow schema
+#[derive(Debug)]
+pub struct BuildReport {
+    /// TOM events fetched from the indexer and replayed
+    pub replayed_events: usize,
+    /// Slot of the last replayed event
+    pub last_slot: i64,
+}
+
+/// Build `treasury_shadow` from scratch by replaying every TOM event. Any
+/// previous shadow schema is dropped first. The live schema is only read.
+pub async fn build(
+    pool: &PgPool,
+    config: &DatabaseConfig,
+    chain: Arc<dyn ChainSource>,
+    batch_size: i64,
+) -> anyhow::Result<BuildReport> {
+    let live_version = Migrator::new(pool).current_version().await?;
+    anyhow::ensure!(
+        live_version == migrations::latest_version(),
+        "treasury schema is at version {} but this binary builds version {}; run `treasury-cli migrate` first",
+        live_version,
+        migrations::latest_version()
+    );
+
+    sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", SHADOW_SCHEMA))
+        .execute(pool)
+        .await?;
+    Migrator::for_schema(pool, SHADOW_SCHEMA)?.migrate().await?;
+
+    let mut tx = pool.begin().await?;
+    copy_operator_data(&mut tx).await?;
+    tx.commit().await?;
+
+    let shadow = db::connect_schema(config, SHADOW_SCHEMA).await?;
+    let processor = EventProcessor::new(shadow.clone(), chain);
+    let mut replayed_events = 0;
+    loop {
+        let fetched = sync_new_events(&shadow, &processor, batch_size).await?;
+        replayed_events += fetched;
+        tracing::info!("Replayed {} TOM events into {}", replayed_events, SHADOW_SCHEMA);
+        if fetched == 0 {
+            break;
+        }
+    }
+    resolve_vendors(&shadow).await?;
+    normalise_all(&shadow).await?;
+    summaries::rebuild(&shadow).await?;
+
+    let mut conn = shadow.acquire().await?;
+    carry_evidence_state(&mut conn).await?;
+    let last_slot: i64 = sqlx::query_scalar("SELECT last_slot FROM sync_status WHERE sync_type = 'events'")
+        .fetch_one(&mut *conn)
+        .await?;
+    drop(conn);
+    shadow.close().await;
+
+    Ok(BuildReport {
+        replayed_events,
+        last_slot,
+    })
+}
+
+/// Drop the shadow schema. Returns `false` if there was none.
+pub async fn discard(pool: &PgPool) -> anyhow::Result<bool> {
+    if !schema_exists(pool, SHADOW_SCHEMA).await? {
+        return Ok(false);
+    }
+    sqlx::query(&format!("DROP SCHEMA {} CASCADE", SHADOW_SCHEMA))
+        .execute(pool)
+        .await?;
+    Ok(true)
+}
+
+/// Replace the shadow schema's operator tables with the live rows
+async fn copy_operator_data(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    for table in OPERATOR_TABLES {
+        // Column lists rather than `*`: tables created before versioned
+        // migrations may order their columns differently
+        let columns: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT quote_ident(column_name::TEXT)
+            FROM information_schema.columns
+            WHERE table_schema = $1 AND table_name = $2
+            ORDER BY ordinal_position
+            "#
+        )
+        .bind(SHADOW_SCHEMA)
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await?;
+        let columns = columns.join(", ");
+
+        if *table == "evidence_archive" {
+            // Content-addressed and referenced by milestone_evidence: only add
+            sqlx::query(&format!(
+                "INSERT INTO {s}.{t} ({c}) SELECT {c} FROM treasury.{t} ON CONFLICT DO NOTHING",
+                s = SHADOW_SCHEMA,
+                t = table,
+                c = columns
+            ))
+            .execute(&mut *conn)
+            .await?;
+            continue;
+        }
+
+        sqlx::query(&format!("DELETE FROM {}.{}", SHADOW_SCHEMA, table))
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query(&format!(
+            "INSERT INTO {s}.{t} ({c}) SELECT {c} FROM treasury.{t}",
+            s = SHADOW_SCHEMA,
+            t = table,
+            c = columns
+        ))
+        .execute(&mut *conn)
+        .await?;
+
+        // Copied rows keep their ids; later inserts must not reuse them
+        let sequence: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT pg_get_serial_sequence(format('%I.%I', $1::TEXT, $2::TEXT), 'id')
+            FROM information_schema.columns
+            WHERE table_schema = $1 AND table_name = $2 AND column_name = 'id'
+            "#
+        )
+        .bind(SHADOW_SCHEMA)
+        .bind(table)
+        .fetch_optional(&mut *conn)
+        .await?
+        .flatten();
+        if let Some(sequence) = sequence {
+            sqlx::query(&format!(
+                "SELECT setval($1, COALESCE(MAX(id), 1), MAX(id) IS NOT NULL) FROM {}.{}",
+                SHADOW_SCHEMA, table
+            ))
+            .bind(sequence)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Keep the archive status of evidence entries whose location and declared
+/// hash are unchanged, so archived documents are not fetched again
+async fn carry_evidence_state(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        r#"
+        UPDATE {s}.milestone_evidence se
+        SET content_hash = le.content_hash,
+            status = le.status,
+            fetch_attempts = le.fetch_attempts,
+            last_checked_at = le.last_checked_at
+        FROM {s}.milestones sm, {s}.vendor_contracts svc,
+             treasury.milestone_evidence le
+             JOIN treasury.milestones lm ON lm.id = le.milestone_id
+             JOIN treasury.vendor_contracts lvc ON lvc.id = lm.vendor_contract_id
+        WHERE sm.id = se.milestone_id
+          AND svc.id = sm.vendor_contract_id
+          AND lvc.project_id = svc.project_id
+          AND lm.milestone_id = sm.milestone_id
+          AND le.position = se.position
+          AND (le.url, le.ipfs_cid, le.declared_hash, le.raw)
+              IS NOT DISTINCT FROM (se.url, se.ipfs_cid, se.declared_hash, se.raw)
+        "#,
+        s = SHADOW_SCHEMA
+    ))
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+// ============================================================================
+// DIFF
+// ============================================================================
+
+/// How a row differs between the live and shadow schemas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RowChange {
+    /// Only in the shadow schema
+    Added,
+    /// Only in the live schema
+    Removed,
+    /// In both, with different content
+    Changed,
+}
+
+/// A column whose value differs
+#[derive(Debug, Serialize)]
+pub struct ColumnChange {
+    pub column: String,
+    pub live: Value,
+    pub shadow: Value,
+}
+
+/// A row that differs, identified by its logical key
+#[derive(Debug, Serialize)]
+pub struct RowDiff {
+    pub key: String,
+    pub change: RowChange,
+    /// The added or removed row
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<Value>,
+    /// Differing columns of a changed row
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<ColumnChange>,
+}
+
+/// Differences in one table
+#[derive(Debug, Serialize)]
+pub struct TableDiff {
+    pub table: &'static str,
+    pub live_rows: i64,
+    pub shadow_rows: i64,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub rows: Vec<RowDiff>,
+}
+
+/// Live schema compared with the shadow schema
+#[derive(Debug, Serialize)]
+pub struct SchemaDiff {
+    pub live_schema: &'static str,
+    pub shadow_schema: &'static str,
+    pub tables: Vec<TableDiff>,
+}
+
+impl SchemaDiff {
+    /// Whether any table differs
+    pub fn has_changes(&self) -> bool {
+        self.tables.iter().any(|t| !t.rows.is_empty())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DiffRow {
+    key: String,
+    live: Option<Value>,
+    shadow: Option<Value>,
+}
+
+/// Compare every derived table of the live schema with the shadow schema
+pub async fn diff(pool: &PgPool) -> anyhow::Result<SchemaDiff> {
+    ensure_shadow(pool).await?;
+
+    let mut tables = Vec::with_capacity(DIFF_TABLES.len());
+    for spec in DIFF_TABLES {
+        tables.push(diff_table(pool, spec).await?);
+    }
+    Ok(SchemaDiff {
+        live_schema: migrations::DEFAULT_SCHEMA,
+        shadow_schema: SHADOW_SCHEMA,
+        tables,
+    })
+}
+
+/// Query returning `(key, row)` for every row of a table in `schema`
+fn select_rows(spec: &TableSpec, schema: &str) -> String {
+    format!(
+        "SELECT COALESCE({}, '') AS key, {} AS row FROM {}",
+        spec.key,
+        spec.row,
+        spec.from.replace("{s}", schema)
+    )
+}
+
+/// Every derived table in `schema` as compared by [`diff`]: rows by logical
+/// key, without database ids or bookkeeping timestamps. The event processor
+/// tests compare this with golden files.
+pub async fn snapshot(
+    pool: &PgPool,
+    schema: &str,
+) -> Result<BTreeMap<&'static str, BTreeMap<String, Value>>, sqlx::Error> {
+    let mut tables = BTreeMap::new();
+    for spec in DIFF_TABLES {
+        let rows: Vec<(String, Value)> = sqlx::query_as(&select_rows(spec, schema)).fetch_all(pool).await?;
+        tables.insert(spec.table, rows.into_iter().collect());
+    }
+    Ok(tables)
+}
+
+async fn diff_table(pool: &PgPool, spec: &TableSpec) -> Result<TableDiff, sqlx::Error> {
+    let live = select_rows(spec, migrations::DEFAULT_SCHEMA);
+    let shadow = select_rows(spec, SHADOW_SCHEMA);
+
+    let (live_rows, shadow_rows): (i64, i64) = sqlx::query_as(&format!(
+        "SELECT (SELECT COUNT(*) FROM ({}) l), (SELECT COUNT(*) FROM ({}) s)",
+        live, shadow
+    ))
+    .fetch_one(pool)
+    .await?;
+
+    let rows = sqlx::query_as::<_, DiffRow>(&format!(
+        r#"
+        WITH live AS ({}), shadow AS ({})
+        SELECT COALESCE(l.key, s.key) AS key, l.row AS live, s.row AS shadow
+        FROM live l
+        FULL JOIN shadow s ON s.key = l.key
+        WHERE l.row IS DISTINCT FROM s.row
+        ORDER BY 1
+        "#,
+        live, shadow
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let rows: Vec<RowDiff> = rows
+        .into_iter()
+        .map(|r| match (r.live, r.shadow) {
+            (None, shadow) => RowDiff {
+                key: r.key,
+                change: RowChange::Added,
+                row: shadow,
+                columns: Vec::new(),
+            },
+            (live, None) => RowDiff {
+                key: r.key,
+                change: RowChange::Removed,
+                row: live,
+                columns: Vec::new(),
+            },
+            (Some(live), Some(shadow)) => RowDiff {
+                key: r.key,
+                change: RowChange::Changed,
+                row: None,
+                columns: changed_columns(&live, &shadow),
+            },
+        })
+        .collect();
+
+    let count = |change| rows.iter().filter(|r| r.change == change).count();
+    Ok(TableDiff {
+        table: spec.table,
+        live_rows,
+        shadow_rows,
+        added: count(RowChange::Added),
+        removed: count(RowChange::Removed),
+        changed: count(RowChange::Changed),
+        rows,
+    })
+}
+
+/// Columns whose values differ between two row objects
+fn changed_columns(live: &Value, shadow: &Value) -> Vec<ColumnChange> {
+    let empty = Map::new();
+    let live = live.as_object().unwrap_or(&empty);
+    let shadow = shadow.as_object().unwrap_or(&empty);
+    let columns: BTreeSet<&String> = live.keys().chain(shadow.keys()).collect();
+    columns
+        .into_iter()
+        .filter_map(|column| {
+            let (l, s) = (live.get(column), shadow.get(column));
+            (l != s).then(|| ColumnChange {
+                column: column.clone(),
+                live: l.cloned().unwrap_or(Value::Null),
+                shadow: s.cloned().unwrap_or(Value::Null),
+            })
+        })
+        .collect()
+}
+
+// ============================================================================
+// SWAP
+// ============================================================================
+
+/// Outcome of a swap
+#[derive(Debug)]
+pub struct SwapReport {
+    /// Events replayed to catch the shadow up with the indexer
+    pub caught_up_events: usize,
+}
+
+/// Catch the shadow schema up with the indexer and make it the live schema.
+/// Takes the sync lock, so sync workers must be stopped.
+pub async fn swap(
+    pool: &PgPool,
+    config: &DatabaseConfig,
+    chain: Arc<dyn ChainSource>,
+    batch_size: i64,
+) -> anyhow::Result<SwapReport> {
+    ensure_shadow(pool).await?;
+    let lock = leader_lock(pool).await?;
+    let report = swap_as_leader(pool, config, chain, batch_size).await?;
+    lock.release().await?;
+    Ok(report)
+}
+
+/// [`swap`] for a caller that already holds sync leadership, such as the
+/// chain follower rebuilding after a rollback
+pub async fn swap_as_leader(
+    pool: &PgPool,
+    config: &DatabaseConfig,
+    chain: Arc<dyn ChainSource>,
+    batch_size: i64,
+) -> anyhow::Result<SwapReport> {
+    let shadow = db::connect_schema(config, SHADOW_SCHEMA).await?;
+    let processor = EventProcessor::new(shadow.clone(), chain);
+    let mut caught_up_events = 0;
+    loop {
+        let fetched = sync_new_events(&shadow, &processor, batch_size).await?;
+        caught_up_events += fetched;
+        if fetched == 0 {
+            break;
+        }
+    }
+    resolve_vendors(&shadow).await?;
+    normalise_all(&shadow).await?;
+    shadow.close().await;
+
+    let mut tx = pool.begin().await?;
+    // Block operator writes (curation, key issue) until the swap commits;
+    // reads continue against the old schema
+    for table in OPERATOR_TABLES {
+        sqlx::query(&format!("LOCK TABLE treasury.{} IN EXCLUSIVE MODE", table))
+            .execute(&mut *tx)
+            .await?;
+    }
+    copy_operator_data(&mut tx).await?;
+    carry_evidence_state(&mut tx).await?;
+
+    // API processes clear their response caches when the generation changes
+    sqlx::query(&format!(
+        r#"
+        UPDATE {}.sync_generation
+        SET generation = (SELECT generation FROM treasury.sync_generation) + 1,
+            changed_at = NOW(),
+            last_xid = txid_current()
+        "#,
+        SHADOW_SCHEMA
+    ))
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", PREVIOUS_SCHEMA))
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(&format!("ALTER SCHEMA treasury RENAME TO {}", PREVIOUS_SCHEMA))
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(&format!("ALTER SCHEMA {} RENAME TO treasury", SHADOW_SCHEMA))
+        .execute(&mut *tx)
+        .await?;
+    repin_functions(&mut tx, PREVIOUS_SCHEMA).await?;
+    repin_functions(&mut tx, migrations::DEFAULT_SCHEMA).await?;
+    tx.commit().await?;
+
+    Ok(SwapReport { caught_up_events })
+}
+
+/// Point functions with a pinned search_path (e.g. the generation trigger)
+/// at the schema they now live in; the pin names the schema they were
+/// created in
+async fn repin_functions(conn: &mut PgConnection, schema: &str) -> Result<(), sqlx::Error> {
+    let functions: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT p.oid::regprocedure::TEXT
+        FROM pg_proc p
+        JOIN pg_namespace n ON n.oid = p.pronamespace
+        WHERE n.nspname = $1
+          AND EXISTS (SELECT 1 FROM unnest(p.proconfig) c WHERE c LIKE 'search_path=%')
+        "#
+    )
+    .bind(schema)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for function in functions {
+        sqlx::query(&format!("ALTER FUNCTION {} SET search_path = {}, public", function, schema))
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Fail unless a shadow schema at the current version exists
+async fn ensure_shadow(pool: &PgPool) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        schema_exists(pool, SHADOW_SCHEMA).await?,
+        "no {} schema; run `treasury-cli reindex build` first",
+        SHADOW_SCHEMA
+    );
+    let version = Migrator::for_schema(pool, SHADOW_SCHEMA)?.current_version().await?;
+    anyhow::ensure!(
+        version == migrations::latest_version(),
+        "{} is at version {} but this binary expects {}; rebuild it",
+        SHADOW_SCHEMA,
+        version,
+        migrations::latest_version()
+    );
+    Ok(())
+}
+
+async fn schema_exists(pool: &PgPool, schema: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = $1)")
+        .bind(schema)
+        .fetch_one(pool)
+        .await
+}
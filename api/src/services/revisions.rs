@@ -0,0 +1,143 @@
+//! Vendor contract revisions
+//!
+//! Every `fund` and `modify` event stores a snapshot of the contract terms
+//! (project fields plus the current milestone schedule) in
+//! `treasury.vendor_contract_revisions`. Consecutive snapshots are diffed
+//! on read to show what each amendment changed.
+
+use serde_json::{Map, Value};
+
+use crate::models::v1::{FieldChange, MilestoneChange};
+
+/// SQL expression building the terms snapshot for vendor contract `$1`
+pub const SNAPSHOT_SQL: &str = r#"
+    SELECT jsonb_build_object(
+        'project_name', vc.project_name,
+        'description', vc.description,
+        'vendor_name', vc.vendor_name,
+        'vendor_address', vc.vendor_address,
+        'contract_url', vc.contract_url,
+        'other_identifiers', to_jsonb(vc.other_identifiers),
+        'initial_amount_lovelace', vc.initial_amount_lovelace,
+        'milestones', COALESCE((
+            SELECT jsonb_agg(jsonb_build_object(
+                'milestone_id', m.milestone_id,
+                'milestone_order', m.milestone_order,
+                'label', m.label,
+                'description', m.description,
+                'acceptance_criteria', m.acceptance_criteria,
+                'amount_lovelace', m.amount_lovelace,
+                'maturity', m.maturity
+            ) ORDER BY m.milestone_order)
+            FROM milestones m
+            WHERE m.vendor_contract_id = vc.id AND m.status <> 'removed'
+        ), '[]'::jsonb)
+    )
+    FROM vendor_contracts vc
+    WHERE vc.id = $1
+"#;
+
+/// Contract-level fields compared between revisions, in display order
+const CONTRACT_FIELDS: &[&str] = &[
+    "project_name",
+    "description",
+    "vendor_name",
+    "vendor_address",
+    "contract_url",
+    "other_identifiers",
+    "initial_amount_lovelace",
+];
+
+/// Milestone fields compared between revisions, in display order
+const MILESTONE_FIELDS: &[&str] = &[
+    "milestone_order",
+    "label",
+    "description",
+    "acceptance_criteria",
+    "amount_lovelace",
+    "maturity",
+];
+
+/// Diff two terms snapshots.
+///
+/// `previous` is `None` for the first revision, in which case every
+/// non-null field and every milestone is reported as added.
+pub fn diff_snapshots(previous: Option<&Value>, current: &Value) -> (Vec<FieldChange>, Vec<MilestoneChange>) {
+    let empty = Value::Object(Map::new());
+    let previous = previous.unwrap_or(&empty);
+
+    let changes = diff_fields(previous, current, CONTRACT_FIELDS);
+
+    let prev_milestones = milestones_by_id(previous);
+    let curr_milestones = milestones_by_id(current);
+
+    let mut milestone_changes = Vec::new();
+
+    for (milestone_id, curr) in &curr_milestones {
+        match prev_milestones.iter().find(|(id, _)| id == milestone_id) {
+            Some((_, prev)) => {
+                let fields = diff_fields(prev, curr, MILESTONE_FIELDS);
+                if !fields.is_empty() {
+                    milestone_changes.push(MilestoneChange {
+                        milestone_id: milestone_id.clone(),
+                        change: "modified".to_string(),
+                        fields,
+                    });
+                }
+            }
+            None => milestone_changes.push(MilestoneChange {
+                milestone_id: milestone_id.clone(),
+                change: "added".to_string(),
+                fields: diff_fields(&empty, curr, MILESTONE_FIELDS),
+            }),
+        }
+    }
+
+    for (milestone_id, prev) in &prev_milestones {
+        if !curr_milestones.iter().any(|(id, _)| id == milestone_id) {
+            milestone_changes.push(MilestoneChange {
+                milestone_id: milestone_id.clone(),
+                change: "removed".to_string(),
+                fields: diff_fields(prev, &empty, MILESTONE_FIELDS),
+            });
+        }
+    }
+
+    (changes, milestone_changes)
+}
+
+/// Compare the listed fields of two JSON objects
+fn diff_fields(previous: &Value, current: &Value, fields: &[&str]) -> Vec<FieldChange> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let prev = previous.get(*field).filter(|v| !v.is_null());
+            let curr = current.get(*field).filter(|v| !v.is_null());
+            if prev == curr {
+                return None;
+            }
+            Some(FieldChange {
+                field: field.to_string(),
+                previous: prev.cloned(),
+                current: curr.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Milestones of a snapshot keyed by milestone_id, preserving schedule order
+fn milestones_by_id(snapshot: &Value) -> Vec<(String, Value)> {
+    snapshot
+        .get("milestones")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|m| {
+                    m.get("milestone_id")
+                        .and_then(|id| id.as_str())
+                        .map(|id| (id.to_string(), m.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,224 @@
+//! Project and treasury summary tables
+//!
+//! `treasury.project_summary` and `treasury.treasury_summary` hold the
+//! aggregates behind `v_vendor_contracts_summary` and `v_treasury_summary`.
+//! Rows are recomputed from `v_*_summary_recomputed` for just the projects and
+//! treasuries a change touched, so listing endpoints never aggregate.
+
+use sqlx::{PgConnection, PgPool};
+
+const REFRESH_PROJECTS_SQL: &str = r#"
+    INSERT INTO project_summary (
+        vendor_contract_id, total_milestones, pending_milestones, completed_milestones,
+        disbursed_milestones, total_disbursed_lovelace, current_balance_lovelace,
+        utxo_count, event_count, last_event_time
+    )
+    SELECT * FROM v_project_summary_recomputed
+    WHERE $1::INT[] IS NULL OR vendor_contract_id = ANY($1)
+    ON CONFLICT (vendor_contract_id) DO UPDATE SET
+        total_milestones = EXCLUDED.total_milestones,
+        pending_milestones = EXCLUDED.pending_milestones,
+        completed_milestones = EXCLUDED.completed_milestones,
+        disbursed_milestones = EXCLUDED.disbursed_milestones,
+        total_disbursed_lovelace = EXCLUDED.total_disbursed_lovelace,
+        current_balance_lovelace = EXCLUDED.current_balance_lovelace,
+        utxo_count = EXCLUDED.utxo_count,
+        event_count = EXCLUDED.event_count,
+        last_event_time = EXCLUDED.last_event_time,
+        updated_at = NOW()
+    WHERE (project_summary.total_milestones, project_summary.pending_milestones,
+           project_summary.completed_milestones, project_summary.disbursed_milestones,
+           project_summary.total_disbursed_lovelace, project_summary.current_balance_lovelace,
+           project_summary.utxo_count, project_summary.event_count,
+           project_summary.last_event_time)
+        IS DISTINCT FROM
+          (EXCLUDED.total_milestones, EXCLUDED.pending_milestones, EXCLUDED.completed_milestones,
+           EXCLUDED.disbursed_milestones, EXCLUDED.total_disbursed_lovelace, EXCLUDED.current_balance_lovelace,
+           EXCLUDED.utxo_count, EXCLUDED.event_count, EXCLUDED.last_event_time)
+"#;
+
+const REFRESH_TREASURIES_SQL: &str = r#"
+    INSERT INTO treasury_summary (
+        treasury_id, vendor_contract_count, active_contracts, completed_contracts,
+        cancelled_contracts, treasury_balance, utxo_count, total_events, last_event_time
+    )
+    SELECT * FROM v_treasury_summary_recomputed
+    WHERE $1::INT[] IS NULL OR treasury_id = ANY($1)
+    ON CONFLICT (treasury_id) DO UPDATE SET
+        vendor_contract_count = EXCLUDED.vendor_contract_count,
+        active_contracts = EXCLUDED.active_contracts,
+        completed_contracts = EXCLUDED.completed_contracts,
+        cancelled_contracts = EXCLUDED.cancelled_contracts,
+        treasury_balance = EXCLUDED.treasury_balance,
+        utxo_count = EXCLUDED.utxo_count,
+        total_events = EXCLUDED.total_events,
+        last_event_time = EXCLUDED.last_event_time,
+        updated_at = NOW()
+    WHERE (treasury_summary.vendor_contract_count, treasury_summary.active_contracts,
+           treasury_summary.completed_contracts, treasury_summary.cancelled_contracts,
+           treasury_summary.treasury_balance, treasury_summary.utxo_count,
+           treasury_summary.total_events, treasury_summary.last_event_time)
+        IS DISTINCT FROM
+          (EXCLUDED.vendor_contract_count, EXCLUDED.active_contracts, EXCLUDED.completed_contracts,
+           EXCLUDED.cancelled_contracts, EXCLUDED.treasury_balance, EXCLUDED.utxo_count,
+           EXCLUDED.total_events, EXCLUDED.last_event_time)
+"#;
+
+/// Recompute the summaries of the given projects and treasuries
+pub async fn refresh(conn: &mut PgConnection, project_ids: &[i32], treasury_ids: &[i32]) -> Result<(), sqlx::Error> {
+    if !project_ids.is_empty() {
+        sqlx::query(REFRESH_PROJECTS_SQL).bind(project_ids).execute(&mut *conn).await?;
+    }
+    if !treasury_ids.is_empty() {
+        sqlx::query(REFRESH_TREASURIES_SQL).bind(treasury_ids).execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// Recompute the summaries affected by a transaction: the project and
+/// treasury of its event, projects whose milestones or terms it changed, and
+/// the owners of the UTXOs it created or spent
+pub async fn refresh_for_tx(conn: &mut PgConnection, tx_hash: &str) -> Result<(), sqlx::Error> {
+    let project_ids: Vec<i32> = sqlx::query_scalar(
+        r#"
+        SELECT vendor_contract_id FROM events
+        WHERE tx_hash = $1 AND vendor_contract_id IS NOT NULL
+        UNION
+        SELECT vendor_contract_id FROM milestones
+        WHERE complete_tx_hash = $1 OR disburse_tx_hash = $1
+        UNION
+        SELECT vendor_contract_id FROM vendor_contract_revisions
+        WHERE tx_hash = $1
+        UNION
+        SELECT vendor_contract_id FROM utxos
+        WHERE (tx_hash = $1 OR spent_tx_hash = $1) AND vendor_contract_id IS NOT NULL
+        "#
+    )
+    .bind(tx_hash)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let treasury_ids: Vec<i32> = sqlx::query_scalar(
+        r#"
+        SELECT treasury_id FROM events
+        WHERE tx_hash = $1 AND treasury_id IS NOT NULL
+        UNION
+        SELECT treasury_id FROM vendor_contracts
+        WHERE id = ANY($2) AND treasury_id IS NOT NULL
+        UNION
+        SELECT tc.id FROM treasury_contracts tc
+        JOIN utxos u ON u.address = tc.contract_address
+        WHERE u.tx_hash = $1 OR u.spent_tx_hash = $1
+        "#
+    )
+    .bind(tx_hash)
+    .bind(&project_ids)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    refresh(conn, &project_ids, &treasury_ids).await
+}
+
+/// Recompute the summaries of projects and treasuries holding UTXOs at the
+/// given addresses
+pub async fn refresh_for_addresses(conn: &mut PgConnection, addresses: &[String]) -> Result<(), sqlx::Error> {
+    if addresses.is_empty() {
+        return Ok(());
+    }
+
+    let project_ids: Vec<i32> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT vendor_contract_id FROM utxos
+        WHERE address = ANY($1) AND vendor_contract_id IS NOT NULL
+        "#
+    )
+    .bind(addresses)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let treasury_ids: Vec<i32> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM treasury_contracts WHERE contract_address = ANY($1)
+        UNION
+        SELECT treasury_id FROM vendor_contracts
+        WHERE id = ANY($2) AND treasury_id IS NOT NULL
+        "#
+    )
+    .bind(addresses)
+    .bind(&project_ids)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    refresh(conn, &project_ids, &treasury_ids).await
+}
+
+/// Rows written by a rebuild
+#[derive(Debug)]
+pub struct RebuildReport {
+    pub projects: u64,
+    pub treasuries: u64,
+}
+
+/// Recompute every summary row from scratch
+pub async fn rebuild(pool: &PgPool) -> Result<RebuildReport, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM project_summary").execute(&mut *tx).await?;
+    sqlx::query("DELETE FROM treasury_summary").execute(&mut *tx).await?;
+    let projects = sqlx::query(REFRESH_PROJECTS_SQL)
+        .bind(None::<Vec<i32>>)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    let treasuries = sqlx::query(REFRESH_TREASURIES_SQL)
+        .bind(None::<Vec<i32>>)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    tx.commit().await?;
+    Ok(RebuildReport { projects, treasuries })
+}
+
+/// A summary row that differs from its recomputation
+#[derive(Debug, sqlx::FromRow)]
+pub struct SummaryMismatch {
+    /// `project` or `treasury`
+    pub kind: String,
+    /// Vendor contract or treasury id
+    pub id: i32,
+    /// Stored row (`None` if missing)
+    pub stored: Option<serde_json::Value>,
+    /// Recomputed row (`None` if the row should not exist)
+    pub expected: Option<serde_json::Value>,
+}
+
+/// Compare every summary row with a from-scratch recomputation
+pub async fn check(pool: &PgPool) -> Result<Vec<SummaryMismatch>, sqlx::Error> {
+    sqlx::query_as::<_, SummaryMismatch>(
+        r#"
+        WITH projects AS (
+            SELECT
+                COALESCE(s.vendor_contract_id, r.vendor_contract_id) as id,
+                to_jsonb(s) - 'updated_at' as stored,
+                to_jsonb(r) as expected
+            FROM project_summary s
+            FULL JOIN v_project_summary_recomputed r USING (vendor_contract_id)
+        ),
+        treasuries AS (
+            SELECT
+                COALESCE(s.treasury_id, r.treasury_id) as id,
+                to_jsonb(s) - 'updated_at' as stored,
+                to_jsonb(r) as expected
+            FROM treasury_summary s
+            FULL JOIN v_treasury_summary_recomputed r USING (treasury_id)
+        )
+        SELECT 'project' as kind, id, stored, expected FROM projects
+        WHERE stored IS DISTINCT FROM expected
+        UNION ALL
+        SELECT 'treasury', id, stored, expected FROM treasuries
+        WHERE stored IS DISTINCT FROM expected
+        ORDER BY kind, id
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -1,22 +1,99 @@
 //! Background sync service for TOM events
 //!
-//! Periodically fetches new TOM (Treasury Oversight Metadata) events from
-//! yaci_store.transaction_metadata and processes them into the normalized
-//! treasury schema.
+//! Periodically fetches new TOM (Treasury Oversight Metadata) events from the
+//! configured chain source and processes them into the normalized treasury
+//! schema. With the `follower` source, each cycle first follows the chain
+//! (see [`super::follower`]), without pausing until it reaches the tip. With
+//! `pending.source_dir` set, each cycle also tracks submitted transactions
+//! (see [`super::pending`]).
 
 use sqlx::PgPool;
-use std::time::Duration;
+use std::sync::Arc;
 
 use super::event_processor::EventProcessor;
+use super::follower::Follower;
+use super::heartbeat;
+use super::evidence::{sync_evidence, EvidenceArchive, LocalDirFetcher};
+use super::leader::LeaderLock;
+use super::pending::sync_pending;
+use super::summaries;
+use super::vendors::resolve_vendors;
+use crate::chain::{ChainSource, TOM_LABEL};
+use crate::config::{ChainSourceKind, Config};
+use crate::metrics::METRICS;
 
-/// Run the background sync loop
-pub async fn run_sync_loop(pool: PgPool) {
-    let processor = EventProcessor::new(pool.clone());
+/// Run the sync loop whenever this process holds sync leadership. Standby
+/// workers retry every sync interval; if the leader's lock connection is lost
+/// the loop stops and the worker rejoins the election.
+pub async fn run_sync_worker(pool: PgPool, chain: Arc<dyn ChainSource>, config: &Config) {
+    let mut standby_logged = false;
+    loop {
+        match LeaderLock::try_acquire(&pool).await {
+            Ok(Some(mut lock)) => {
+                tracing::info!("Acquired sync leadership");
+                standby_logged = false;
+                METRICS.sync_leader.set(1);
+                tokio::select! {
+                    _ = run_sync_loop(pool.clone(), chain.clone(), config) => {}
+                    e = lock.lost(config.sync.interval()) => {
+                        tracing::warn!("Lost sync leadership: {}", e);
+                    }
+                }
+                METRICS.sync_leader.set(0);
+            }
+            Ok(None) => {
+                if !standby_logged {
+                    tracing::info!("Another worker holds sync leadership; standing by");
+                    standby_logged = true;
+                }
+            }
+            Err(e) => tracing::error!("Sync leader election failed: {}", e),
+        }
+        tokio::time::sleep(config.sync.interval()).await;
+    }
+}
+
+/// Run the background sync loop. Callers must hold sync leadership.
+pub async fn run_sync_loop(pool: PgPool, chain: Arc<dyn ChainSource>, config: &Config) {
+    let processor = EventProcessor::new(pool.clone(), chain.clone());
+    let batch_size = i64::from(config.sync.batch_size);
+    let evidence_enabled = config.features.evidence;
+    let evidence_archive = EvidenceArchive::new(&config.evidence.archive_dir);
+    let evidence_fetcher = config.evidence.source_dir.as_ref().map(LocalDirFetcher::new);
+    if !evidence_enabled {
+        tracing::info!("Evidence sync disabled (features.evidence = false)");
+    } else if evidence_fetcher.is_none() {
+        tracing::info!("evidence.source_dir not set; evidence will be normalised but not archived");
+    }
+
+    let mut follower = if config.chain.source == ChainSourceKind::Follower {
+        match Follower::new(pool.clone(), chain.clone(), &config.chain, &config.database, batch_size) {
+            Ok(follower) => Some(follower),
+            Err(e) => {
+                tracing::error!("Chain follower unavailable: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let blocks_per_cycle = config.sync.batch_size as usize;
+    let mut at_tip = follow(follower.as_mut(), blocks_per_cycle).await;
 
     // Initial sync: process all events from beginning
-    tracing::info!("Starting initial TOM event sync...");
-    if let Err(e) = processor.sync_all_events().await {
-        tracing::error!("Initial sync failed: {}", e);
+    tracing::info!("Starting initial TOM event sync from {}...", chain.name());
+    let tip = chain.tip().await.ok().flatten();
+    match processor.sync_all_events().await {
+        Ok(_) => {
+            METRICS.record_sync_cycle(true);
+            if let Err(e) = record_progress(&pool, chain.as_ref(), tip.map(|t| t.slot)).await {
+                tracing::warn!("Failed to record sync heartbeat: {}", e);
+            }
+        }
+        Err(e) => {
+            METRICS.record_sync_cycle(false);
+            tracing::error!("Initial sync failed: {}", e);
+        }
     }
 
     // Sync UTXOs for tracked addresses
@@ -25,50 +102,157 @@ pub async fn run_sync_loop(pool: PgPool) {
         tracing::error!("UTXO sync failed: {}", e);
     }
 
+    // Group projects by vendor
+    if let Err(e) = resolve_vendors(&pool).await {
+        tracing::error!("Vendor resolution failed: {}", e);
+    }
+
+    // Archive completion evidence
+    if evidence_enabled {
+        if let Err(e) = sync_evidence(&pool, evidence_fetcher.as_ref(), &evidence_archive).await {
+            tracing::error!("Evidence sync failed: {}", e);
+        }
+    }
+
+    // Confirm or expire submitted transactions against the processed events
+    if config.pending.source_dir.is_some() {
+        track_pending(&pool, config).await;
+    }
+
+    // Summary tables are maintained per event; report any drift
+    match summaries::check(&pool).await {
+        Ok(mismatches) if !mismatches.is_empty() => tracing::warn!(
+            "{} summary rows differ from a recomputation; run `treasury-cli summaries rebuild`",
+            mismatches.len()
+        ),
+        Ok(_) => {}
+        Err(e) => tracing::error!("Summary check failed: {}", e),
+    }
+
     tracing::info!("Initial sync complete. Starting continuous sync loop.");
 
     // Continuous sync loop
     loop {
-        tokio::time::sleep(Duration::from_secs(15)).await;
+        // A follower behind the tip keeps going without pausing
+        if at_tip {
+            tokio::time::sleep(config.sync.interval()).await;
+        }
+        at_tip = follow(follower.as_mut(), blocks_per_cycle).await;
+
+        let tip = chain.tip().await.ok().flatten();
+        let timer = METRICS.sync_batch_duration.start_timer();
+        let synced = sync_new_events(&pool, &processor, batch_size).await;
+        timer.observe_duration();
+        match synced {
+            Ok(fetched) => {
+                METRICS.record_sync_cycle(true);
+                // A short batch drained every event up to the tip read before it
+                let drained_to = tip.filter(|_| (fetched as i64) < batch_size).map(|t| t.slot);
+                if let Err(e) = record_progress(&pool, chain.as_ref(), drained_to).await {
+                    tracing::warn!("Failed to record sync heartbeat: {}", e);
+                }
+            }
+            Err(e) => {
+                METRICS.record_sync_cycle(false);
+                tracing::error!("Sync error: {}", e);
+            }
+        }
+
+        // Re-run every cycle so alias changes apply without new events
+        if let Err(e) = resolve_vendors(&pool).await {
+            tracing::error!("Vendor resolution failed: {}", e);
+        }
 
-        if let Err(e) = sync_new_events(&pool, &processor).await {
-            tracing::error!("Sync error: {}", e);
+        // Normalise new evidence and retry unavailable documents
+        if evidence_enabled {
+            if let Err(e) = sync_evidence(&pool, evidence_fetcher.as_ref(), &evidence_archive).await {
+                tracing::error!("Evidence sync failed: {}", e);
+            }
         }
+
+        if config.pending.source_dir.is_some() {
+            track_pending(&pool, config).await;
+        }
+    }
+}
+
+/// Read newly submitted transactions and resolve pending ones
+async fn track_pending(pool: &PgPool, config: &Config) {
+    match sync_pending(pool, &config.pending).await {
+        Ok(report) => {
+            if report.added > 0 || report.confirmed > 0 || report.reverted > 0 || report.expired > 0 {
+                tracing::info!(
+                    "Pending transactions: {} submitted, {} confirmed, {} rolled back, {} expired",
+                    report.added,
+                    report.confirmed,
+                    report.reverted,
+                    report.expired
+                );
+            }
+        }
+        Err(e) => tracing::error!("Pending transaction sync failed: {:#}", e),
     }
 }
 
-/// Fetch and process new TOM events since last sync
-async fn sync_new_events(pool: &PgPool, processor: &EventProcessor) -> anyhow::Result<()> {
+/// Follow the chain for a cycle, returning whether the follower reached the
+/// tip. Without a follower there is nothing to catch up with.
+async fn follow(follower: Option<&mut Follower>, max_blocks: usize) -> bool {
+    let Some(follower) = follower else {
+        return true;
+    };
+    match follower.follow(max_blocks).await {
+        Ok(report) => {
+            if report.blocks > 0 || report.rollbacks > 0 {
+                tracing::info!(
+                    "Followed {} blocks ({} rollbacks), keeping {} transactions",
+                    report.blocks,
+                    report.rollbacks,
+                    report.kept
+                );
+            }
+            report.at_tip
+        }
+        Err(e) => {
+            tracing::error!("Chain follower failed: {:#}", e);
+            true
+        }
+    }
+}
+
+/// Fetch and process about `batch_size` new TOM events since the last sync,
+/// returning how many were fetched. The cursor is a slot, so a batch always
+/// ends on a whole slot: events of a slot cut off by the limit are left for the
+/// next batch, and a single slot holding more events than the limit is fetched
+/// whole.
+pub async fn sync_new_events(pool: &PgPool, processor: &EventProcessor, batch_size: i64) -> anyhow::Result<usize> {
     // Get last synced slot
     let last_slot: i64 = sqlx::query_scalar(
-        "SELECT last_slot FROM treasury.sync_status WHERE sync_type = 'events'"
+        "SELECT last_slot FROM sync_status WHERE sync_type = 'events'"
     )
     .fetch_one(pool)
     .await
     .unwrap_or(0);
 
-    // Fetch new TOM events from yaci_store
-    let rows = sqlx::query_as::<_, RawTomEvent>(
-        r#"
-        SELECT
-            m.tx_hash,
-            m.slot,
-            m.body::jsonb as body,
-            b.number as block_number,
-            b.block_time
-        FROM yaci_store.transaction_metadata m
-        JOIN yaci_store.block b ON b.slot = m.slot
-        WHERE m.label = '1694' AND m.slot > $1
-        ORDER BY m.slot ASC
-        LIMIT 1000
-        "#
-    )
-    .bind(last_slot)
-    .fetch_all(pool)
-    .await?;
+    // Fetch new TOM events from the chain source
+    let mut limit = batch_size;
+    let (rows, fetched) = loop {
+        let mut rows = processor.chain().metadata_since(TOM_LABEL, last_slot, Some(limit)).await?;
+        let fetched = rows.len();
+        if (fetched as i64) < limit {
+            break (rows, fetched);
+        }
+        // The last slot may continue past the limit
+        let last = rows.last().map(|r| r.slot);
+        let whole = rows.iter().position(|r| Some(r.slot) == last).unwrap_or(0);
+        if whole > 0 {
+            rows.truncate(whole);
+            break (rows, fetched);
+        }
+        limit *= 2;
+    };
 
     if rows.is_empty() {
-        return Ok(());
+        return Ok(0);
     }
 
     tracing::info!("Processing {} new TOM events", rows.len());
@@ -91,7 +275,7 @@ async fn sync_new_events(pool: &PgPool, processor: &EventProcessor) -> anyhow::R
     // Update sync status
     sqlx::query(
         r#"
-        UPDATE treasury.sync_status
+        UPDATE sync_status
         SET last_slot = $1, last_block = $2, last_tx_hash = $3, updated_at = NOW()
         WHERE sync_type = 'events'
         "#
@@ -105,15 +289,24 @@ async fn sync_new_events(pool: &PgPool, processor: &EventProcessor) -> anyhow::R
     // Also sync any new UTXOs
     processor.sync_utxos().await?;
 
-    Ok(())
+    Ok(fetched)
 }
 
-/// Raw TOM event from yaci_store
-#[derive(Debug, sqlx::FromRow)]
-pub struct RawTomEvent {
-    pub tx_hash: String,
-    pub slot: Option<i64>,
-    pub body: Option<serde_json::Value>,
-    pub block_number: Option<i64>,
-    pub block_time: Option<i64>,
+/// Write the heartbeat after a successful cycle and refresh the lag gauges.
+/// `drained_to` is the indexer tip read before the cycle, if the cycle
+/// processed every event up to it; otherwise only the event cursor counts as
+/// scanned.
+async fn record_progress(pool: &PgPool, chain: &dyn ChainSource, drained_to: Option<i64>) -> anyhow::Result<()> {
+    let cursor: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(last_slot), 0) FROM sync_status WHERE sync_type = 'events'"
+    )
+    .fetch_one(pool)
+    .await?;
+    let scanned_slot = drained_to.map_or(cursor, |tip| tip.max(cursor));
+    heartbeat::beat(pool, scanned_slot, drained_to.is_some()).await?;
+
+    let progress = heartbeat::progress(pool, chain).await?;
+    METRICS.sync_lag_slots.set(progress.slots_behind_tip().unwrap_or(0));
+    METRICS.sync_lag_seconds.set(progress.seconds_behind_tip().unwrap_or(0));
+    Ok(())
 }
@@ -0,0 +1,304 @@
+//! Vendor resolution
+//!
+//! Projects only carry the vendor as free text (`vendor_name`, or its curated
+//! override, and the payout address in `vendor_address`). This groups projects into `treasury.vendors`,
+//! keyed by the stake credential of the payout address when it has one and by
+//! the payout address itself otherwise. Rows in `treasury.vendor_aliases`
+//! override the derived key, e.g. to merge two wallets of the same vendor or
+//! to group projects that only have a vendor name.
+
+use bech32::FromBase32;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+
+use super::curation;
+
+/// Vendor grouping derived from the current projects
+#[derive(Debug, Default)]
+struct ResolvedVendor {
+    name: Option<String>,
+    name_from_alias: bool,
+    payout_address: Option<String>,
+    stake_credential: Option<String>,
+    project_ids: Vec<i32>,
+}
+
+/// Manual override from treasury.vendor_aliases
+#[derive(Debug, sqlx::FromRow)]
+pub struct VendorAlias {
+    pub id: i32,
+    pub alias_type: String,
+    pub alias_value: String,
+    pub vendor_key: String,
+    pub vendor_name: Option<String>,
+    pub note: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+const ALIAS_COLUMNS: &str = "id, alias_type, alias_value, vendor_key, vendor_name, note, created_at";
+
+/// What a vendor alias matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum AliasType {
+    /// A payout address
+    Address,
+    /// A stake credential (hex), or a stake address
+    StakeCredential,
+    /// A vendor name, ignoring case
+    Name,
+}
+
+impl AliasType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AliasType::Address => "address",
+            AliasType::StakeCredential => "stake_credential",
+            AliasType::Name => "name",
+        }
+    }
+}
+
+/// Project fields used to identify its vendor
+#[derive(Debug, sqlx::FromRow)]
+struct ProjectVendor {
+    id: i32,
+    vendor_name: Option<String>,
+    vendor_address: Option<String>,
+}
+
+/// Re-derive vendors from all projects and link each project to its vendor.
+/// Safe to run repeatedly; vendors keep their id while they have projects.
+pub async fn resolve_vendors(pool: &PgPool) -> anyhow::Result<()> {
+    let aliases = list_aliases(pool).await?;
+
+    // Oldest first, so the most recent project's name and address win. A
+    // curated vendor name stands in for the chain one.
+    let projects = sqlx::query_as::<_, ProjectVendor>(&format!(
+        "SELECT vc.id, {} AS vendor_name, vc.vendor_address FROM vendor_contracts vc ORDER BY vc.fund_slot NULLS FIRST, vc.id",
+        curation::curated_contract_field("vc", "vendor_name")
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    let find_alias = |alias_type: &str, value: &str| {
+        aliases.iter().find(|a| a.alias_type == alias_type && a.alias_value.eq_ignore_ascii_case(value))
+    };
+
+    let mut vendors: BTreeMap<String, ResolvedVendor> = BTreeMap::new();
+    let mut unassigned: Vec<i32> = Vec::new();
+
+    for project in projects {
+        let address = project.vendor_address.as_deref().map(str::trim).filter(|a| !a.is_empty());
+        let name = project.vendor_name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+        let stake = address.and_then(stake_credential);
+
+        let alias = address.and_then(|a| find_alias("address", a))
+            .or_else(|| stake.as_deref().and_then(|s| find_alias("stake_credential", s)))
+            .or_else(|| name.and_then(|n| find_alias("name", n)));
+
+        let key = match (alias, &stake, address) {
+            (Some(alias), _, _) => alias.vendor_key.clone(),
+            (None, Some(stake), _) => format!("stake:{}", stake),
+            (None, None, Some(address)) => format!("addr:{}", address),
+            (None, None, None) => {
+                unassigned.push(project.id);
+                continue;
+            }
+        };
+
+        let vendor = vendors.entry(key).or_default();
+        // Alias names win over project names; otherwise the latest project name is used
+        if let Some(alias_name) = alias.and_then(|a| a.vendor_name.clone()) {
+            vendor.name = Some(alias_name);
+            vendor.name_from_alias = true;
+        } else if !vendor.name_from_alias && name.is_some() {
+            vendor.name = name.map(str::to_string);
+        }
+        if address.is_some() {
+            vendor.payout_address = address.map(str::to_string);
+            vendor.stake_credential = stake.or(vendor.stake_credential.take());
+        }
+        vendor.project_ids.push(project.id);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    for (key, vendor) in &vendors {
+        let vendor_id: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO vendors (vendor_key, name, payout_address, stake_credential)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (vendor_key) DO UPDATE
+                SET name = EXCLUDED.name,
+                    payout_address = EXCLUDED.payout_address,
+                    stake_credential = EXCLUDED.stake_credential,
+                    updated_at = CASE
+                        WHEN (vendors.name, vendors.payout_address, vendors.stake_credential)
+                            IS DISTINCT FROM (EXCLUDED.name, EXCLUDED.payout_address, EXCLUDED.stake_credential)
+                        THEN NOW()
+                        ELSE vendors.updated_at
+                    END
+            RETURNING id
+            "#
+        )
+        .bind(key)
+        .bind(&vendor.name)
+        .bind(&vendor.payout_address)
+        .bind(&vendor.stake_credential)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE vendor_contracts SET vendor_id = $1 WHERE id = ANY($2) AND vendor_id IS DISTINCT FROM $1"
+        )
+        .bind(vendor_id)
+        .bind(&vendor.project_ids)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE vendor_contracts SET vendor_id = NULL WHERE id = ANY($1) AND vendor_id IS NOT NULL")
+        .bind(&unassigned)
+        .execute(&mut *tx)
+        .await?;
+
+    // Vendors whose projects all moved to another key (e.g. after adding an alias)
+    sqlx::query(
+        "DELETE FROM vendors v WHERE NOT EXISTS (SELECT 1 FROM vendor_contracts vc WHERE vc.vendor_id = v.id)"
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Stake credential (hex) of a Shelley base address or reward address.
+/// Enterprise, pointer and Byron addresses have none.
+pub fn stake_credential(address: &str) -> Option<String> {
+    let (hrp, data, _) = bech32::decode(address).ok()?;
+    let bytes = Vec::<u8>::from_base32(&data).ok()?;
+    let header = *bytes.first()?;
+
+    match (hrp.as_str(), header >> 4) {
+        // Base addresses: header | payment credential (28) | stake credential (28)
+        ("addr" | "addr_test", 0..=3) if bytes.len() == 57 => Some(hex::encode(&bytes[29..57])),
+        // Reward addresses: header | stake credential (28)
+        ("stake" | "stake_test", 0xe | 0xf) if bytes.len() == 29 => Some(hex::encode(&bytes[1..29])),
+        _ => None,
+    }
+}
+
+/// Every vendor alias, oldest first
+pub async fn list_aliases(pool: &PgPool) -> Result<Vec<VendorAlias>, sqlx::Error> {
+    sqlx::query_as::<_, VendorAlias>(&format!("SELECT {} FROM vendor_aliases ORDER BY id", ALIAS_COLUMNS))
+        .fetch_all(pool)
+        .await
+}
+
+/// Add an alias, or repoint the existing alias for the same value. Takes
+/// effect the next time vendors are resolved.
+pub async fn add_alias(
+    pool: &PgPool,
+    alias_type: AliasType,
+    value: &str,
+    vendor_key: &str,
+    vendor_name: Option<&str>,
+    note: Option<&str>,
+) -> anyhow::Result<VendorAlias> {
+    let value = value.trim();
+    anyhow::ensure!(!value.is_empty(), "alias value must not be empty");
+    anyhow::ensure!(!vendor_key.trim().is_empty(), "vendor key must not be empty");
+    let value = match alias_type {
+        // Projects are matched on the credential, so store it rather than the address
+        AliasType::StakeCredential => match stake_credential(value) {
+            Some(credential) => credential,
+            None if value.len() == 56 && value.bytes().all(|b| b.is_ascii_hexdigit()) => value.to_ascii_lowercase(),
+            None => anyhow::bail!("{} is neither a stake address nor a 28-byte hex stake credential", value),
+        },
+        AliasType::Address | AliasType::Name => value.to_string(),
+    };
+
+    let alias = sqlx::query_as::<_, VendorAlias>(&format!(
+        r#"
+        INSERT INTO vendor_aliases (alias_type, alias_value, vendor_key, vendor_name, note)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (alias_type, alias_value) DO UPDATE
+            SET vendor_key = EXCLUDED.vendor_key,
+                vendor_name = EXCLUDED.vendor_name,
+                note = EXCLUDED.note
+        RETURNING {}
+        "#,
+        ALIAS_COLUMNS
+    ))
+    .bind(alias_type.as_str())
+    .bind(&value)
+    .bind(vendor_key.trim())
+    .bind(vendor_name.map(str::trim).filter(|n| !n.is_empty()))
+    .bind(note)
+    .fetch_one(pool)
+    .await?;
+    Ok(alias)
+}
+
+/// Remove an alias by id, returning it if it existed
+pub async fn remove_alias(pool: &PgPool, id: i32) -> Result<Option<VendorAlias>, sqlx::Error> {
+    sqlx::query_as::<_, VendorAlias>(&format!(
+        "DELETE FROM vendor_aliases WHERE id = $1 RETURNING {}",
+        ALIAS_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stake_credential;
+
+    // CIP-19 test vectors: payment key hash 9493315c..., stake key hash 337b62cf...
+    const STAKE_KEY_HASH: &str = "337b62cfff6403a06a3acbc34f8c46003c69fe79a3628cefa9c47251";
+
+    #[test]
+    fn base_address_has_its_stake_credential() {
+        let address = "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x";
+        assert_eq!(stake_credential(address).as_deref(), Some(STAKE_KEY_HASH));
+    }
+
+    #[test]
+    fn script_base_address_has_its_stake_credential() {
+        // Type 1: script payment credential, key stake credential
+        let address = "addr1z8phkx6acpnf78fuvxn0mkew3l0fd058hzquvz7w36x4gten0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs9yc0hh";
+        assert_eq!(stake_credential(address).as_deref(), Some(STAKE_KEY_HASH));
+    }
+
+    #[test]
+    fn reward_address_has_its_stake_credential() {
+        let address = "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw";
+        assert_eq!(stake_credential(address).as_deref(), Some(STAKE_KEY_HASH));
+    }
+
+    #[test]
+    fn enterprise_and_pointer_addresses_have_none() {
+        for address in [
+            "addr1vx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzers66hrl8",
+            "addr1gx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer5pnz75xxcrzqf96k",
+        ] {
+            assert!(bech32::decode(address).is_ok(), "{} should decode", address);
+            assert_eq!(stake_credential(address), None);
+        }
+    }
+
+    #[test]
+    fn invalid_addresses_have_none() {
+        // Checksum broken by changing the last character
+        let corrupted = "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3y";
+        assert_eq!(stake_credential(corrupted), None);
+        assert_eq!(stake_credential("addr1notanaddress"), None);
+        assert_eq!(stake_credential(""), None);
+        // Byron addresses are base58, not bech32
+        assert_eq!(stake_credential("Ae2tdPwUPEZFRbyhz3cpfC2CumGzNkFBN2L42rcUc2yjQpEkxDbkPodpMAi"), None);
+    }
+}
@@ -0,0 +1,55 @@
+//! Shared application state
+//!
+//! Handlers extract the parts they need, e.g. `State(pool): State<PgPool>`.
+//! Read-only handlers take `State(ReadPool(pool)): State<ReadPool>` instead,
+//! which is the read replica while it is fresh and the primary otherwise.
+
+use axum::extract::FromRef;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::auth::Auth;
+use crate::cache::ResponseCache;
+use crate::chain::ChainSource;
+use crate::config::Config;
+use crate::db::{ReadPool, Replica};
+use crate::freshness::LagMonitor;
+use crate::services::evidence::EvidenceArchive;
+
+/// State injected into every handler
+#[derive(Clone, FromRef)]
+pub struct AppState {
+    /// Primary (read-write) pool
+    pub pool: PgPool,
+    pub replica: Option<Replica>,
+    /// Indexer the sync worker reads; handlers report lag against its tip
+    pub chain: Arc<dyn ChainSource>,
+    pub config: Arc<Config>,
+    pub evidence_archive: EvidenceArchive,
+    pub cache: ResponseCache,
+    pub lag: LagMonitor,
+    pub auth: Auth,
+}
+
+impl AppState {
+    pub fn new(pool: PgPool, replica: Option<Replica>, chain: Arc<dyn ChainSource>, config: Config) -> Self {
+        let evidence_archive = EvidenceArchive::new(&config.evidence.archive_dir);
+        let cache = ResponseCache::new(config.cache.max_entries);
+        Self {
+            pool,
+            replica,
+            chain,
+            config: Arc::new(config),
+            evidence_archive,
+            cache,
+            lag: LagMonitor::new(),
+            auth: Auth::new(),
+        }
+    }
+}
+
+impl FromRef<AppState> for ReadPool {
+    fn from_ref(state: &AppState) -> Self {
+        ReadPool::select(&state.pool, state.replica.as_ref())
+    }
+}
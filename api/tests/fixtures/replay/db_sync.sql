@@ -0,0 +1,69 @@
+-- The parts of the cardano-db-sync schema the db_sync chain source reads,
+-- with the same column names, domains and types, in a `db_sync` schema.
+-- Columns the source does not read are left out.
+
+CREATE SCHEMA db_sync;
+
+CREATE DOMAIN db_sync.hash32type AS BYTEA CHECK (octet_length(VALUE) = 32);
+CREATE DOMAIN db_sync.lovelace AS NUMERIC(20, 0) CHECK (VALUE >= 0 AND VALUE <= 18446744073709551615);
+CREATE DOMAIN db_sync.txindex AS SMALLINT CHECK (VALUE >= 0);
+CREATE DOMAIN db_sync.word31type AS INTEGER CHECK (VALUE >= 0);
+CREATE DOMAIN db_sync.word63type AS BIGINT CHECK (VALUE >= 0);
+CREATE DOMAIN db_sync.word64type AS NUMERIC(20, 0) CHECK (VALUE >= 0 AND VALUE <= 18446744073709551615);
+
+CREATE TABLE db_sync.block (
+    id BIGSERIAL PRIMARY KEY,
+    hash db_sync.hash32type NOT NULL UNIQUE,
+    slot_no db_sync.word63type,
+    block_no db_sync.word31type,
+    time TIMESTAMP NOT NULL,
+    tx_count BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE db_sync.tx (
+    id BIGSERIAL PRIMARY KEY,
+    hash db_sync.hash32type NOT NULL UNIQUE,
+    block_id BIGINT NOT NULL REFERENCES db_sync.block (id),
+    block_index db_sync.word31type NOT NULL,
+    fee db_sync.lovelace NOT NULL DEFAULT 170000,
+    valid_contract BOOLEAN NOT NULL DEFAULT TRUE,
+    treasury_donation db_sync.lovelace NOT NULL DEFAULT 0
+);
+
+CREATE TABLE db_sync.tx_metadata (
+    id BIGSERIAL PRIMARY KEY,
+    key db_sync.word64type NOT NULL,
+    json JSONB,
+    tx_id BIGINT NOT NULL REFERENCES db_sync.tx (id)
+);
+
+CREATE TABLE db_sync.tx_out (
+    id BIGSERIAL PRIMARY KEY,
+    tx_id BIGINT NOT NULL REFERENCES db_sync.tx (id),
+    index db_sync.txindex NOT NULL,
+    address VARCHAR NOT NULL,
+    value db_sync.lovelace NOT NULL,
+    UNIQUE (tx_id, index)
+);
+
+CREATE TABLE db_sync.tx_in (
+    id BIGSERIAL PRIMARY KEY,
+    tx_in_id BIGINT NOT NULL REFERENCES db_sync.tx (id),
+    tx_out_id BIGINT NOT NULL REFERENCES db_sync.tx (id),
+    tx_out_index db_sync.txindex NOT NULL
+);
+
+CREATE TABLE db_sync.multi_asset (
+    id BIGSERIAL PRIMARY KEY,
+    policy BYTEA NOT NULL,
+    name BYTEA NOT NULL,
+    fingerprint VARCHAR NOT NULL,
+    UNIQUE (policy, name)
+);
+
+CREATE TABLE db_sync.ma_tx_out (
+    id BIGSERIAL PRIMARY KEY,
+    quantity db_sync.word64type NOT NULL,
+    tx_out_id BIGINT NOT NULL REFERENCES db_sync.tx_out (id),
+    ident BIGINT NOT NULL REFERENCES db_sync.multi_asset (id)
+);
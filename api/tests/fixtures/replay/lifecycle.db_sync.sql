@@ -0,0 +1,96 @@
+-- The `lifecycle` chain as cardano-db-sync rows, for the db_sync chain source.
+-- Transaction hashes are the SHA-256 of the names `lifecycle.dump.json` uses:
+--
+--   tx_seed            ff3a97b76a1a88996c6cab8f476e19fb647b80d230c887529f1f1e18d2aec94d
+--   tx_publish         11cab339af89387bf5fe60d1c4dfaf16733f22adbb4d2df66cb0127d71da2594
+--   tx_initialize      5376ca6595a8100613db38d9caea0284c0e2559fc104cbb79a05c86253892782
+--   tx_fund            5aeb9a6893c9d3f11e2a9167ce235abcbab823403c93a0cc36414b77b9d201a2
+--   tx_pause           a0a4ce26ad58e099f750f647fa521b9fb6ef09a0f04df4f5d6e3efaf35c5a2b7
+--   tx_resume          86282bf028b20d8ead20f05c512dd338b1745c7e7d0cf03e17ee0f6260b4b073
+--   tx_modify          90680a31794f9e05ab73695b543f543b65ea0609884712d0a85056c84ea875af
+--   tx_complete        d6d33f24101b873106904bddb7dea1a15c4dae5890691b3751180b4264a325b5
+--   tx_withdraw        ce1dc811da1a7106bf229c5125fc91ea4643f44e3cb53a95dd51a2ace96cb0fd
+--   tx_disburse        9f641ed0a9dee9a87c198732cc8568de45ab29d7bac2e3a1868e9815c8bc7808
+--   tx_cancel          1030f1c058f4b21da9faffd131c769b361c94c9cc251e7e78ac11c56568e1754
+--   tx_sweep_vendor    7c1d3b23f2b514ca508935a0cdf44575b43a209fc34b2e766dc4a1c3b17e37e6
+--   tx_reorganize      69308b11552ffd0dc6f1c4bf094ae2dbffec40a577faf67436d33c3ea72d0a27
+--   tx_sweep_treasury  3df267929ba54fe205327e18ac29dc64b7a725ce27c87dc3e3a25e38e594ad0c
+--
+-- tx_fund's change output also carries a native asset, and tx_publish a
+-- CIP-20 message under label 674; neither may change the result.
+
+INSERT INTO db_sync.block (id, hash, slot_no, block_no, time) VALUES
+    (1, decode('0266372bb214196932c78d919c5b8e15d4e18feeb9cee50bd3940480b42077df', 'hex'), 50, 1, '2023-11-14 22:14:10'),
+    (2, decode('facdb5fce8c3a78eb9d2b17fc7657b8edf7c45a32d79f5a8f2193ccfaebdd799', 'hex'), 100, 2, '2023-11-14 22:15:00'),
+    (3, decode('d70b0a11a85cb66e1e84d4a0a7e76f4ad43637556767c5b82523ca3ea3000cb0', 'hex'), 150, 3, '2023-11-14 22:15:50'),
+    (4, decode('96bda935edefa964058451c8184f2415678299f5b9165e01da8c5e0c219ed80e', 'hex'), 200, 4, '2023-11-14 22:16:40'),
+    (5, decode('ebee620ba82b6b4576d11d6822cef6ca22cec994892269fad24018764f6c7f1c', 'hex'), 300, 6, '2023-11-14 22:18:20'),
+    (6, decode('de4a7179bc47b8969d33023e0e553805d6752815800a9145acedcea80ccc5603', 'hex'), 400, 8, '2023-11-14 22:20:00'),
+    (7, decode('18c4e6f30403d0f2d006d6b0136e43959ee8dfaf6109a15d4564068be5d5f368', 'hex'), 500, 10, '2023-11-14 22:21:40'),
+    (8, decode('76dfe9d811adab865a6a8135ffc1ca4ef32e9c70820edb1c2e85acd9e6cb5720', 'hex'), 600, 12, '2023-11-14 22:23:20'),
+    (9, decode('46ef57d3f4fa0bb7942a4a5f281e074cfa7ccd428d3f5abcd1db88e67b785a4c', 'hex'), 700, 14, '2023-11-14 22:25:00'),
+    (10, decode('421a7618fbb2fd8cc1bbd458428afb75a8b50c32651d38b8c9206af81d2e0f98', 'hex'), 800, 16, '2023-11-14 22:26:40'),
+    (11, decode('373439dc5c8b525f7b559ce54ea22b18bdc55e18efa5f956de4a20eab019f2c8', 'hex'), 900, 18, '2023-11-14 22:28:20'),
+    (12, decode('ce8260d9315bd3c4cc005621b78276ca07c489d807b035949de25ec43f7dc19e', 'hex'), 1000, 20, '2023-11-14 22:30:00'),
+    (13, decode('e2c445c5298d1b56fab9f1dc77ad22ea7d79ac5a533b15130f76a3e165fdbcc3', 'hex'), 1100, 22, '2023-11-14 22:31:40'),
+    (14, decode('7851e35ba0e9e28b95990771861e14984b6a3aa61baa094b9b84cf643d5de3e0', 'hex'), 1200, 24, '2023-11-14 22:33:20');
+
+INSERT INTO db_sync.tx (id, hash, block_id, block_index, treasury_donation) VALUES
+    (1, decode('ff3a97b76a1a88996c6cab8f476e19fb647b80d230c887529f1f1e18d2aec94d', 'hex'), 1, 0, 0),
+    (2, decode('11cab339af89387bf5fe60d1c4dfaf16733f22adbb4d2df66cb0127d71da2594', 'hex'), 2, 0, 0),
+    (3, decode('5376ca6595a8100613db38d9caea0284c0e2559fc104cbb79a05c86253892782', 'hex'), 3, 0, 0),
+    (4, decode('5aeb9a6893c9d3f11e2a9167ce235abcbab823403c93a0cc36414b77b9d201a2', 'hex'), 4, 0, 0),
+    (5, decode('a0a4ce26ad58e099f750f647fa521b9fb6ef09a0f04df4f5d6e3efaf35c5a2b7', 'hex'), 5, 0, 0),
+    (6, decode('86282bf028b20d8ead20f05c512dd338b1745c7e7d0cf03e17ee0f6260b4b073', 'hex'), 6, 0, 0),
+    (7, decode('90680a31794f9e05ab73695b543f543b65ea0609884712d0a85056c84ea875af', 'hex'), 7, 0, 0),
+    (8, decode('d6d33f24101b873106904bddb7dea1a15c4dae5890691b3751180b4264a325b5', 'hex'), 8, 0, 0),
+    (9, decode('ce1dc811da1a7106bf229c5125fc91ea4643f44e3cb53a95dd51a2ace96cb0fd', 'hex'), 9, 0, 0),
+    (10, decode('9f641ed0a9dee9a87c198732cc8568de45ab29d7bac2e3a1868e9815c8bc7808', 'hex'), 10, 0, 0),
+    (11, decode('1030f1c058f4b21da9faffd131c769b361c94c9cc251e7e78ac11c56568e1754', 'hex'), 11, 0, 0),
+    (12, decode('7c1d3b23f2b514ca508935a0cdf44575b43a209fc34b2e766dc4a1c3b17e37e6', 'hex'), 12, 0, 0),
+    (13, decode('69308b11552ffd0dc6f1c4bf094ae2dbffec40a577faf67436d33c3ea72d0a27', 'hex'), 13, 0, 0),
+    (14, decode('3df267929ba54fe205327e18ac29dc64b7a725ce27c87dc3e3a25e38e594ad0c', 'hex'), 14, 0, 6500000);
+
+INSERT INTO db_sync.tx_metadata (key, json, tx_id) VALUES
+    (1694, '{"instance": "inst1", "body": {"event": "publish", "label": "Test Treasury", "permissions": {"disburse": {"signatures": ["ops"]}}}}', 2),
+    (674, '{"msg": ["Treasury published"]}', 2),
+    (1694, '{"instance": "inst1", "body": {"event": "initialize"}}', 3),
+    (1694, '{"instance": "inst1", "body": {"event": "fund", "identifier": "EC-1", "otherIdentifiers": ["PO-1"], "label": "Audit tooling", "description": ["Static analysis ", "for contracts"], "vendor": {"name": "Acme", "label": "addr1qvendor1"}, "contract": "https://example.org/contract.pdf", "milestones": [{"identifier": "m-0", "label": "Design", "amount": 1000000, "maturity": 1767225600000, "acceptanceCriteria": "Spec approved"}, {"identifier": "m-1", "label": "Build", "amount": 2000000}]}}', 4),
+    (1694, '{"instance": "inst1", "body": {"event": "pause", "identifier": "EC-1", "reason": "Audit pending"}}', 5),
+    (1694, '{"instance": "inst1", "body": {"event": "resume", "identifier": "EC-1"}}', 6),
+    (1694, '{"instance": "inst1", "body": {"event": "modify", "identifier": "EC-1", "reason": "Rescoped", "label": "Audit tooling v2", "milestones": [{"identifier": "m-0", "amount": 1500000}, {"identifier": "m-2", "label": "Docs", "amount": 1500000}]}}', 7),
+    (1694, '{"instance": "inst1", "body": {"event": "complete", "identifier": "EC-1", "milestones": {"m-0": {"description": "Design done", "evidence": [{"label": "Specification", "url": "https://example.org/spec.pdf", "sha256": "abababababababababababababababababababababababababababababababab"}, "Demo at the community call"]}}}}', 8),
+    (1694, '{"instance": "inst1", "body": {"event": "withdraw", "identifier": "EC-1", "milestones": ["m-0"]}}', 9),
+    (1694, '{"instance": "inst1", "body": {"event": "disburse", "destination": "Marketing agency"}}', 10),
+    (1694, '{"instance": "inst1", "body": {"event": "cancel", "identifier": "EC-1", "reason": "Vendor unresponsive"}}', 11),
+    (1694, '{"instance": "inst1", "body": {"event": "sweep", "identifier": "EC-1"}}', 12),
+    (1694, '{"instance": "inst1", "body": {"event": "reorganize"}}', 13),
+    (1694, '{"instance": "inst1", "body": {"event": "sweepTreasury"}}', 14);
+
+INSERT INTO db_sync.tx_out (id, tx_id, index, address, value) VALUES
+    (1, 1, 0, 'addr1xtreasury', 10000000),
+    (2, 4, 0, 'addr1xproject1', 3000000),
+    (3, 4, 1, 'addr1xtreasury', 7000000),
+    (4, 9, 0, 'addr1qvendor1', 1500000),
+    (5, 9, 1, 'addr1xproject1', 1500000),
+    (6, 10, 0, 'addr1qexternal', 2000000),
+    (7, 10, 1, 'addr1xtreasury', 5000000),
+    (8, 12, 0, 'addr1xtreasury', 1500000),
+    (9, 13, 0, 'addr1xtreasury', 3250000),
+    (10, 13, 1, 'addr1xtreasury', 3250000);
+
+INSERT INTO db_sync.tx_in (tx_in_id, tx_out_id, tx_out_index) VALUES
+    (4, 1, 0),
+    (9, 4, 0),
+    (10, 4, 1),
+    (12, 9, 1),
+    (13, 10, 1),
+    (13, 12, 0),
+    (14, 13, 0),
+    (14, 13, 1);
+
+INSERT INTO db_sync.multi_asset (id, policy, name, fingerprint) VALUES
+    (1, decode('823412d1eacb67956220e532959f0104603057c88704863ca38e7cd1', 'hex'), convert_to('TREASURY', 'UTF8'), 'asset1treasury');
+
+INSERT INTO db_sync.ma_tx_out (quantity, tx_out_id, ident) VALUES
+    (1000, 3, 1);
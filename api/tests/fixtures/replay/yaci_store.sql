@@ -0,0 +1,47 @@
+-- The parts of the YACI Store schema the event processor reads, with the
+-- same column names and types. Replay fixtures fill these tables.
+
+CREATE SCHEMA yaci_store;
+
+CREATE TABLE yaci_store.block (
+    hash VARCHAR(64) PRIMARY KEY,
+    number BIGINT,
+    slot BIGINT,
+    block_time BIGINT
+);
+
+CREATE TABLE yaci_store.transaction (
+    tx_hash VARCHAR(64) PRIMARY KEY,
+    slot BIGINT,
+    block BIGINT,
+    tx_index INT,
+    outputs JSONB,
+    treasury_donation BIGINT
+);
+
+CREATE TABLE yaci_store.transaction_metadata (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    slot BIGINT,
+    tx_hash VARCHAR(64) NOT NULL,
+    -- Fixtures only list TOM metadata
+    label VARCHAR(255) DEFAULT '1694',
+    body TEXT
+);
+
+CREATE TABLE yaci_store.address_utxo (
+    tx_hash VARCHAR(64) NOT NULL,
+    output_index SMALLINT NOT NULL,
+    slot BIGINT,
+    owner_addr VARCHAR(500),
+    lovelace_amount BIGINT,
+    block BIGINT,
+    PRIMARY KEY (output_index, tx_hash)
+);
+
+CREATE TABLE yaci_store.tx_input (
+    tx_hash VARCHAR(64) NOT NULL,
+    output_index SMALLINT NOT NULL,
+    spent_tx_hash VARCHAR(64),
+    spent_at_slot BIGINT,
+    PRIMARY KEY (output_index, tx_hash)
+);
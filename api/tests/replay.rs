@@ -0,0 +1,451 @@
+//! Event processor replay tests
+//!
+//! Each fixture in `tests/fixtures/replay/<name>.json` holds `yaci_store`
+//! rows, keyed by table name. A test loads them into a throwaway database,
+//! replays the TOM events the way the sync worker does and compares the
+//! derived treasury tables with `<name>.snapshot.json`. Snapshots are the
+//! rows `treasury-cli reindex diff` compares: keyed by logical ids, without
+//! database ids or timestamps.
+//!
+//! A fixture can also come as a chain dump, `<name>.dump.json`, replayed
+//! through the file chain source instead. It must produce the same snapshot
+//! as the `yaci_store` rows.
+//!
+//! `<name>.db_sync.sql` holds the same chain as cardano-db-sync rows, loaded
+//! into a `db_sync` schema created by `db_sync.sql` and replayed through the
+//! db-sync chain source. db-sync transaction hashes are real 32-byte hashes,
+//! so these replays have their own `<name>.db_sync.snapshot.json`.
+//!
+//! `<name>.blocks.jsonl` is a block file the chain follower replays one
+//! block at a time, processing events in between, so rollbacks in it undo
+//! processed events: in place, or by a rebuild for `REBUILT_BLOCK_FILES`.
+//! It is compared with `<name>.snapshot.json`.
+//!
+//! `tests/fixtures/pending` holds submitted transactions for the pending
+//! feed, which is checked against events inserted by hand.
+//!
+//! The tests need a PostgreSQL server, so they are ignored by default: set
+//! `TEST_DATABASE_URL` to a database the user may create databases from and
+//! run them with `cargo test -- --ignored`. Without it they fail. Run with
+//! `UPDATE_SNAPSHOTS=1` to rewrite the snapshots after an intended change,
+//! and review the diff.
+
+use serde_json::Value;
+use sqlx::{Executor, PgPool};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use treasury_api::chain::{ChainSource, DbSync, FileDump, FollowerStore, YaciStore};
+use treasury_api::config::{ChainConfig, ChainSourceKind, DatabaseConfig, PendingConfig};
+use treasury_api::db;
+use treasury_api::services::event_processor::EventProcessor;
+use treasury_api::services::evidence::normalise_all;
+use treasury_api::services::follower::Follower;
+use treasury_api::services::heartbeat;
+use treasury_api::services::maintenance;
+use treasury_api::services::pending::sync_pending;
+use treasury_api::services::reindex;
+use treasury_api::services::summaries;
+use treasury_api::services::sync::sync_new_events;
+use treasury_api::services::vendors::resolve_vendors;
+
+/// Tables a fixture may fill, in load order
+const FIXTURE_TABLES: &[&str] = &["block", "transaction", "transaction_metadata", "address_utxo", "tx_input"];
+
+/// Addresses the follower tracks in block-file replays: the treasury is
+/// seeded by a transaction without TOM metadata
+const FOLLOWER_ADDRESSES: &[&str] = &["addr1xtreasury"];
+
+/// Block files whose rollbacks remove treasury-level events, so the follower
+/// rebuilds the treasury tables instead of undoing the rollback in place
+const REBUILT_BLOCK_FILES: &[&str] = &["treasury_fork"];
+
+/// Where a test reads chain data from
+#[derive(Clone, Copy)]
+enum Source {
+    /// `<name>.json`, loaded into a `yaci_store` schema
+    YaciStore,
+    /// `<name>.dump.json`, read by the file chain source
+    FileDump,
+    /// `<name>.db_sync.sql`, loaded into a `db_sync` schema
+    DbSync,
+    /// `<name>.blocks.jsonl`, replayed by the chain follower
+    BlockFile,
+}
+
+impl Source {
+    /// Extension of the snapshot a replay is compared with
+    fn snapshot(self) -> &'static str {
+        match self {
+            Self::YaciStore | Self::FileDump | Self::BlockFile => "snapshot.json",
+            Self::DbSync => "db_sync.snapshot.json",
+        }
+    }
+}
+
+/// Every event type, fund through a treasury-level sweep
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn lifecycle() {
+    replay("lifecycle", Source::YaciStore, &[]).await;
+}
+
+/// The same chain as `lifecycle`, without YACI Store
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn lifecycle_from_file_dump() {
+    replay("lifecycle", Source::FileDump, &[]).await;
+}
+
+/// The same chain again, as cardano-db-sync rows
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn lifecycle_from_db_sync() {
+    replay("lifecycle", Source::DbSync, &[]).await;
+}
+
+/// The same chain followed block by block, with a fork that is rolled back
+/// after its withdrawal was processed
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn lifecycle_from_block_file() {
+    replay("lifecycle", Source::BlockFile, &[]).await;
+}
+
+/// A fork that also publishes a treasury is rolled back by rebuilding the
+/// treasury tables
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn treasury_fork() {
+    replay("treasury_fork", Source::BlockFile, &[]).await;
+}
+
+/// `complete` and `disburse` naming a single `milestone`
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn legacy_milestone() {
+    replay("legacy_milestone", Source::YaciStore, &[]).await;
+}
+
+/// Events without a project identifier, attributed through the tracked
+/// outputs their inputs spend
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn chained_inputs() {
+    replay("chained_inputs", Source::YaciStore, &[]).await;
+}
+
+/// A project sweep whose outputs are not indexed leaves the project as it is
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn unindexed_sweep() {
+    replay("unindexed_sweep", Source::YaciStore, &[]).await;
+}
+
+/// Two amendments, with the first reprocessed after the second was applied
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn amendments() {
+    replay("amendments", Source::YaciStore, &["tx_modify_1"]).await;
+}
+
+/// Two amendments in the same slot, with the first reprocessed
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn amendments_in_one_slot() {
+    replay("amendments_same_slot", Source::YaciStore, &["tx_modify_1"]).await;
+}
+
+/// Slots holding more TOM events than fit in a sync batch, at and across
+/// batch boundaries
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn busy_slots() {
+    replay("busy_slots", Source::FileDump, &[]).await;
+}
+
+/// Submitted transactions are listed until their event is processed or
+/// they can no longer be
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn pending_transactions() {
+    let server_url = test_database_url();
+
+    let database = format!("treasury_pending_{}", uuid::Uuid::new_v4().simple());
+    let server = PgPool::connect(&server_url).await.unwrap();
+    server.execute(format!("CREATE DATABASE {}", database).as_str()).await.unwrap();
+
+    let config = DatabaseConfig {
+        url: database_url(&server_url, &database),
+        connect_attempts: 1,
+        ..Default::default()
+    };
+    let result = track_pending(&config).await;
+
+    server
+        .execute(format!("DROP DATABASE {} WITH (FORCE)", database).as_str())
+        .await
+        .unwrap();
+    result.unwrap_or_else(|e| panic!("tracking pending transactions failed: {:#}", e));
+}
+
+/// Replay `name`, then reprocess the `reprocess` transactions as
+/// `treasury-cli reprocess` would; the snapshot must not depend on them
+async fn replay(name: &str, source: Source, reprocess: &[&str]) {
+    let server_url = test_database_url();
+
+    let database = format!("treasury_replay_{}", uuid::Uuid::new_v4().simple());
+    let server = PgPool::connect(&server_url).await.unwrap();
+    server.execute(format!("CREATE DATABASE {}", database).as_str()).await.unwrap();
+
+    let config = DatabaseConfig {
+        url: database_url(&server_url, &database),
+        connect_attempts: 1,
+        ..Default::default()
+    };
+    let result = replay_into(&config, name, source, reprocess).await;
+
+    server
+        .execute(format!("DROP DATABASE {} WITH (FORCE)", database).as_str())
+        .await
+        .unwrap();
+    let snapshot = result.unwrap_or_else(|e| panic!("replaying {} failed: {:#}", name, e));
+
+    let path = fixture_path(name, source.snapshot());
+    let actual = serde_json::to_string_pretty(&snapshot).unwrap() + "\n";
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("no snapshot at {}; run with UPDATE_SNAPSHOTS=1", path.display()));
+    if actual != expected {
+        let actual_path = path.with_extension("actual.json");
+        std::fs::write(&actual_path, &actual).unwrap();
+        panic!(
+            "replaying {} no longer matches {}; the new result is in {}",
+            name,
+            path.display(),
+            actual_path.display()
+        );
+    }
+}
+
+/// The server the tests create their databases on
+fn test_database_url() -> String {
+    std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must point at a PostgreSQL server to run the replay tests")
+}
+
+/// Load the fixture, replay it and snapshot the treasury tables
+async fn replay_into(config: &DatabaseConfig, name: &str, source: Source, reprocess: &[&str]) -> anyhow::Result<Value> {
+    let pool = db::connect(config).await?;
+    db::migrate(&pool).await?;
+    let chain: Arc<dyn ChainSource> = match source {
+        Source::YaciStore => {
+            load_yaci_store(&pool, name).await?;
+            Arc::new(YaciStore::new(pool.clone()))
+        }
+        Source::FileDump => Arc::new(FileDump::load(&fixture_path(name, "dump.json"))?),
+        Source::DbSync => {
+            for file in [fixture_path("db_sync", "sql"), fixture_path(name, "db_sync.sql")] {
+                pool.execute(std::fs::read_to_string(file)?.as_str()).await?;
+            }
+            let chain = ChainConfig {
+                source: ChainSourceKind::DbSync,
+                db_sync_schema: "db_sync".to_string(),
+                ..Default::default()
+            };
+            Arc::new(DbSync::new(db::connect_db_sync(config, &chain).await?))
+        }
+        Source::BlockFile => {
+            let chain: Arc<dyn ChainSource> = Arc::new(FollowerStore::new(pool.clone()));
+            follow_block_file(&pool, config, chain.clone(), name).await?;
+            chain
+        }
+    };
+
+    // As the sync worker does, with a small batch so batching is exercised
+    let processor = EventProcessor::new(pool.clone(), chain.clone());
+    while sync_new_events(&pool, &processor, 4).await? > 0 {}
+    resolve_vendors(&pool).await?;
+
+    // Resolving again changes nothing, so cached responses stay valid
+    let generation = || sqlx::query_scalar::<_, i64>("SELECT generation FROM sync_generation").fetch_one(&pool);
+    let before = generation().await?;
+    resolve_vendors(&pool).await?;
+    anyhow::ensure!(generation().await? == before, "an unchanged vendor resolution bumped the data generation");
+    for tx_hash in reprocess {
+        anyhow::ensure!(
+            maintenance::reprocess_tx(&pool, chain.clone(), tx_hash).await?,
+            "no TOM event to reprocess in {}",
+            tx_hash
+        );
+    }
+    normalise_all(&pool).await?;
+
+    // Summaries are maintained per event and must match a recomputation
+    let mismatches = summaries::check(&pool).await?;
+    anyhow::ensure!(mismatches.is_empty(), "summary rows differ from a recomputation: {:?}", mismatches);
+
+    let snapshot = reindex::snapshot(&pool, db::migrations::DEFAULT_SCHEMA).await?;
+    pool.close().await;
+    Ok(serde_json::to_value(snapshot)?)
+}
+
+/// Follow `<name>.blocks.jsonl` one block at a time, processing events after
+/// each, with a restart halfway so the follower resumes from stored blocks
+async fn follow_block_file(
+    pool: &PgPool,
+    config: &DatabaseConfig,
+    chain: Arc<dyn ChainSource>,
+    name: &str,
+) -> anyhow::Result<()> {
+    let chain_config = ChainConfig {
+        source: ChainSourceKind::Follower,
+        block_file: Some(fixture_path(name, "blocks.jsonl")),
+        addresses: FOLLOWER_ADDRESSES.iter().map(|a| a.to_string()).collect(),
+        ..Default::default()
+    };
+    let processor = EventProcessor::new(pool.clone(), chain.clone());
+    let mut follower = Follower::new(pool.clone(), chain.clone(), &chain_config, config, 4)?;
+    let (mut undone, mut rebuilt) = (false, false);
+    for step in 1.. {
+        let report = follower.follow(1).await?;
+        undone |= report.undone;
+        rebuilt |= report.rebuilt;
+        while sync_new_events(pool, &processor, 4).await? > 0 {}
+        if report.at_tip {
+            break;
+        }
+        if step == 5 {
+            follower = Follower::new(pool.clone(), chain.clone(), &chain_config, config, 4)?;
+        }
+    }
+    if REBUILT_BLOCK_FILES.contains(&name) {
+        anyhow::ensure!(rebuilt && !undone, "no rollback rebuilt the treasury tables");
+    } else {
+        anyhow::ensure!(undone && !rebuilt, "no rollback undid a processed event in place");
+    }
+    Ok(())
+}
+
+/// Read the submitted transactions, then confirm the disbursement, expire
+/// the pause past its validity interval and roll the disbursement back
+async fn track_pending(config: &DatabaseConfig) -> anyhow::Result<()> {
+    const DISBURSE: &str = "87dffd08389776d8845327c5ecbc15e475e8dd591a18d6df6487836b5635bfb5";
+    const PAUSE: &str = "51a59f9469f4652d129c1622da0291133ac989edfe4df28801648fae0ea4f2ab";
+
+    let pool = db::connect(config).await?;
+    db::migrate(&pool).await?;
+    let pending = PendingConfig {
+        source_dir: Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pending")),
+        ..Default::default()
+    };
+    let statuses = || async {
+        sqlx::query_as::<_, (String, String, Option<i64>, Option<i64>)>(
+            "SELECT tx_hash, status, amount_lovelace, invalid_hereafter FROM pending_transactions ORDER BY tx_hash"
+        )
+        .fetch_all(&pool)
+        .await
+    };
+
+    // The unrelated transfer and the notes file are skipped
+    let report = sync_pending(&pool, &pending).await?;
+    anyhow::ensure!(report.added == 2, "expected 2 submitted transactions, got {:?}", report);
+    let expected = vec![
+        (PAUSE.to_string(), "pending".to_string(), None, Some(500)),
+        (DISBURSE.to_string(), "pending".to_string(), Some(25_000_000_000), Some(5_000)),
+    ];
+    anyhow::ensure!(statuses().await? == expected, "unexpected rows {:?}", statuses().await?);
+
+    // Reading the directory again adds nothing
+    let report = sync_pending(&pool, &pending).await?;
+    anyhow::ensure!(report.added == 0, "resubmitted transactions were added again");
+
+    sqlx::query("INSERT INTO events (tx_hash, slot, event_type) VALUES ($1, 480, 'disburse')")
+        .bind(DISBURSE)
+        .execute(&pool)
+        .await?;
+    heartbeat::beat(&pool, 600, true).await?;
+    let report = sync_pending(&pool, &pending).await?;
+    anyhow::ensure!(
+        report.confirmed == 1 && report.expired == 1,
+        "expected the disbursement confirmed and the pause expired, got {:?}",
+        report
+    );
+    let resolved: Vec<(String, Option<i64>)> =
+        sqlx::query_as("SELECT status, slot FROM pending_transactions ORDER BY tx_hash")
+            .fetch_all(&pool)
+            .await?;
+    anyhow::ensure!(
+        resolved == vec![("expired".to_string(), None), ("confirmed".to_string(), Some(480))],
+        "unexpected resolution {:?}",
+        resolved
+    );
+
+    sqlx::query("DELETE FROM events WHERE tx_hash = $1").bind(DISBURSE).execute(&pool).await?;
+    let report = sync_pending(&pool, &pending).await?;
+    anyhow::ensure!(report.reverted == 1, "a rolled back disbursement stayed confirmed: {:?}", report);
+
+    pool.close().await;
+    Ok(())
+}
+
+/// Create the `yaci_store` tables and fill them from `<name>.json`
+async fn load_yaci_store(pool: &PgPool, name: &str) -> anyhow::Result<()> {
+    let fixture: Value = serde_json::from_str(&std::fs::read_to_string(fixture_path(name, "json"))?)?;
+    pool.execute(std::fs::read_to_string(fixture_path("yaci_store", "sql"))?.as_str())
+        .await?;
+
+    for table in FIXTURE_TABLES {
+        for row in fixture.get(*table).and_then(Value::as_array).into_iter().flatten() {
+            insert_row(pool, table, row).await?;
+        }
+    }
+    if let Some(unknown) = fixture
+        .as_object()
+        .and_then(|o| o.keys().find(|k| *k != "description" && !FIXTURE_TABLES.contains(&k.as_str())))
+    {
+        anyhow::bail!("fixture has rows for unknown table {}", unknown);
+    }
+    Ok(())
+}
+
+/// Insert a fixture row; columns it leaves out take their defaults
+async fn insert_row(pool: &PgPool, table: &str, row: &Value) -> anyhow::Result<()> {
+    let columns = row
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("{} rows must be objects", table))?
+        .keys()
+        .map(|c| format!("\"{}\"", c.replace('"', "")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    sqlx::query(&format!(
+        "INSERT INTO yaci_store.{t} ({c}) SELECT {c} FROM jsonb_populate_record(NULL::yaci_store.{t}, $1)",
+        t = table,
+        c = columns
+    ))
+    .bind(row)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+fn fixture_path(name: &str, extension: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/replay")
+        .join(format!("{}.{}", name, extension))
+}
+
+/// `url` with its database replaced by `database`
+fn database_url(url: &str, database: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, format!("?{}", query)),
+        None => (url, String::new()),
+    };
+    let server = match base.split_once("://") {
+        Some((scheme, rest)) => format!("{}://{}", scheme, rest.split('/').next().unwrap_or(rest)),
+        None => base.to_string(),
+    };
+    format!("{}/{}{}", server, database, query)
+}